@@ -0,0 +1,110 @@
+//! # Fowler-Noll-Vo Hash
+//!
+//! `fnv` contains a `Hasher` implementing the 64-bit FNV-1a hash, a small and fast hash with no
+//! setup cost, suitable for the short keys typically used in embedded lookup tables.
+//!
+//! # How does it work?
+//!
+//! The hash starts from a fixed offset basis. For every byte of input, the byte is XORed into the
+//! low bits of the hash, which is then multiplied by the FNV prime. The multiply spreads each
+//! input bit across the higher bits of the hash.
+//!
+//! # Note
+//!
+//! FNV is not resistant to collision attacks. Keys chosen by an adversary should be hashed with
+//! a keyed hash instead.
+
+use core::hash::{BuildHasherDefault, Hasher};
+
+//
+// Constants
+//
+
+const FNV_OFFSET_BASIS_64: u64 = 0xCBF29CE4_84222325;
+
+const FNV_PRIME_64: u64 = 0x00000100_000001B3;
+
+/// Defines a `Hasher` computing the 64-bit FNV-1a hash.
+///
+/// # Examples
+/// ```
+/// use core::hash::Hasher;
+/// use raztos_util::algorithms::fnv::FnvHasher;
+///
+/// let mut hasher = FnvHasher::new();
+/// hasher.write(b"foobar");
+///
+/// assert_eq!(hasher.finish(), 0x85944171_F73967E8);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FnvHasher {
+    /// Holds the hash of the bytes written so far.
+    hash: u64,
+}
+
+/// Implements the functionality unique to `FnvHasher`.
+impl FnvHasher {
+    /// Creates a new FnvHasher.
+    ///
+    /// # Returns
+    /// A FnvHasher starting from the FNV offset basis.
+    pub const fn new() -> Self {
+        FnvHasher {
+            hash: FNV_OFFSET_BASIS_64,
+        }
+    }
+}
+
+/// Defines the default value for FnvHasher.
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher::new()
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME_64);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Defines a `BuildHasher` creating FnvHashers.
+pub type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fnv(bytes: &[u8]) -> u64 {
+        let mut hasher = FnvHasher::new();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn reference_values() {
+        assert_eq!(fnv(b""), 0xCBF29CE4_84222325);
+        assert_eq!(fnv(b"a"), 0xAF63DC4C_8601EC8C);
+        assert_eq!(fnv(b"foobar"), 0x85944171_F73967E8);
+    }
+
+    #[test]
+    fn split_writes() {
+        let mut hasher = FnvHasher::new();
+        hasher.write(b"foo");
+        hasher.write(b"bar");
+
+        assert_eq!(hasher.finish(), fnv(b"foobar"));
+    }
+}
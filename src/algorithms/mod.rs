@@ -4,4 +4,8 @@
 //! and sorts.
 //!
 
-pub mod debruijin;
\ No newline at end of file
+pub mod debruijin;
+
+pub mod fnv;
+
+pub mod popcount;
\ No newline at end of file
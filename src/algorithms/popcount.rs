@@ -0,0 +1,207 @@
+//! # Population Count
+//!
+//! `popcount` contains utilities to count the number of set bits in a value in constant time
+//! without relying on a hardware population count instruction.
+//!
+//! # How does it work?
+//!
+//! The count is computed "SIMD within a register" (SWAR) style. The value is treated as a set of
+//! small counters that are summed together in parallel, doubling the width of each counter on
+//! every step.
+//!
+//! ### Example:
+//!
+//! 8-bits: `0xB7` = `10110111`\
+//! 2-bit counters: `01 10 01 10` (each pair of bits replaced with its count)\
+//! 4-bit counters: `0011 0011` (each pair of 2-bit counters summed)\
+//! 8-bit counter: `00000110` (both 4-bit counters summed) = 6
+//!
+//! For values wider than 8 bits, once every byte holds its own count, the bytes are summed by
+//! multiplying with a value that has a `1` in every byte (`0x0101...`). The sum of all bytes
+//! accumulates in the top byte, which is shifted down to produce the result.
+
+//
+// Constants - 16-bit
+//
+
+const POPCOUNT_MASK_1_16: u16 = 0x5555;
+
+const POPCOUNT_MASK_2_16: u16 = 0x3333;
+
+const POPCOUNT_MASK_4_16: u16 = 0x0F0F;
+
+const POPCOUNT_BYTE_SUM_16: u16 = 0x0101;
+
+const POPCOUNT_SHIFT_16: u16 = 8;
+
+//
+// Constants - 32-bit
+//
+
+const POPCOUNT_MASK_1_32: u32 = 0x5555_5555;
+
+const POPCOUNT_MASK_2_32: u32 = 0x3333_3333;
+
+const POPCOUNT_MASK_4_32: u32 = 0x0F0F_0F0F;
+
+const POPCOUNT_BYTE_SUM_32: u32 = 0x0101_0101;
+
+const POPCOUNT_SHIFT_32: u32 = 24;
+
+//
+// Constants - 64-bit
+//
+
+const POPCOUNT_MASK_1_64: u64 = 0x5555_5555_5555_5555;
+
+const POPCOUNT_MASK_2_64: u64 = 0x3333_3333_3333_3333;
+
+const POPCOUNT_MASK_4_64: u64 = 0x0F0F_0F0F_0F0F_0F0F;
+
+const POPCOUNT_BYTE_SUM_64: u64 = 0x0101_0101_0101_0101;
+
+const POPCOUNT_SHIFT_64: u64 = 56;
+
+//
+// Private Functions
+//
+
+fn count_ones_16(value: u16) -> u16 {
+    let value = value - ((value >> 1) & POPCOUNT_MASK_1_16);
+    let value = (value & POPCOUNT_MASK_2_16) + ((value >> 2) & POPCOUNT_MASK_2_16);
+    let value = (value + (value >> 4)) & POPCOUNT_MASK_4_16;
+    value.wrapping_mul(POPCOUNT_BYTE_SUM_16) >> POPCOUNT_SHIFT_16
+}
+
+fn count_ones_32(value: u32) -> u32 {
+    let value = value - ((value >> 1) & POPCOUNT_MASK_1_32);
+    let value = (value & POPCOUNT_MASK_2_32) + ((value >> 2) & POPCOUNT_MASK_2_32);
+    let value = (value + (value >> 4)) & POPCOUNT_MASK_4_32;
+    value.wrapping_mul(POPCOUNT_BYTE_SUM_32) >> POPCOUNT_SHIFT_32
+}
+
+fn count_ones_64(value: u64) -> u64 {
+    let value = value - ((value >> 1) & POPCOUNT_MASK_1_64);
+    let value = (value & POPCOUNT_MASK_2_64) + ((value >> 2) & POPCOUNT_MASK_2_64);
+    let value = (value + (value >> 4)) & POPCOUNT_MASK_4_64;
+    value.wrapping_mul(POPCOUNT_BYTE_SUM_64) >> POPCOUNT_SHIFT_64
+}
+
+//
+// Public Functions
+//
+
+/// Counts the number of set bits in constant time using parallel bit summation.
+///
+/// # Examples
+///
+/// ```
+/// use raztos_util::algorithms::popcount;
+///
+/// let result = popcount::count_ones(0b1011);
+/// assert_eq!(result, 3);
+///```
+///
+pub fn count_ones(value: usize) -> usize {
+    if cfg!(target_pointer_width = "16") {
+        count_ones_16(value as u16) as usize
+    } else if cfg!(target_pointer_width = "32") {
+        count_ones_32(value as u32) as usize
+    } else if cfg!(target_pointer_width = "64") {
+        count_ones_64(value as u64) as usize
+    } else {
+        0
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //
+    // Helper functions
+    //
+
+    fn simple_count_ones(value: u64) -> usize {
+        let mut count = 0;
+        for index in 0..core::mem::size_of::<u64>() * 8 {
+            if (value & (1 << index)) != 0 {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    //
+    // 16-bit Tests
+    //
+
+    #[test]
+    fn popcount16() {
+        //
+        // 16-bits is also small enough, just test everything
+        //
+
+        for value in u16::min_value()..=u16::max_value() {
+            assert_eq!(
+                simple_count_ones(value as u64),
+                count_ones_16(value) as usize
+            );
+        }
+    }
+
+    //
+    // 32-bit Tests
+    //
+
+    #[test]
+    fn popcount32() {
+        //
+        // 32-bits is too big! Test growing runs of set bits as well as some patterns.
+        //
+
+        let mut run: u32 = 0;
+        for bit in 0..32 {
+            run |= 1 << bit;
+            assert_eq!(count_ones_32(run) as usize, bit + 1);
+            assert_eq!(count_ones_32(!run) as usize, 31 - bit);
+            assert_eq!(count_ones_32(1 << bit), 1);
+        }
+
+        assert_eq!(count_ones_32(0), 0);
+        assert_eq!(count_ones_32(0xFFFF_FFFF), 32);
+        assert_eq!(count_ones_32(0xAAAA_AAAA), 16);
+        assert_eq!(count_ones_32(0x1234_5678), 13);
+    }
+
+    //
+    // 64-bit Tests
+    //
+
+    #[test]
+    fn popcount64() {
+        //
+        // 64-bits is too big! Test growing runs of set bits as well as some patterns.
+        //
+
+        let mut run: u64 = 0;
+        for bit in 0..64 {
+            run |= 1 << bit;
+            assert_eq!(count_ones_64(run) as usize, bit + 1);
+            assert_eq!(count_ones_64(!run) as usize, 63 - bit);
+            assert_eq!(count_ones_64(1 << bit), 1);
+        }
+
+        assert_eq!(count_ones_64(0), 0);
+        assert_eq!(count_ones_64(0xFFFF_FFFF_FFFF_FFFF), 64);
+        assert_eq!(count_ones_64(0xAAAA_AAAA_AAAA_AAAA), 32);
+
+        let pattern = 0x0218_A392_CD3D_5DBF;
+        assert_eq!(count_ones_64(pattern) as usize, simple_count_ones(pattern));
+    }
+}
@@ -0,0 +1,604 @@
+//! # Array String
+//!
+//! `array_string` contains a UTF-8 string with a fixed capacity that stores its bytes inline,
+//! without any allocation.
+//!
+//! `ArrayString` mirrors the familiar `String` API, except that operations which would exceed the
+//! capacity return an error instead of growing or panicking. It implements `core::fmt::Write`, so
+//! `write!` can format directly into it, failing with `fmt::Error` once it is full.
+
+use crate::collections::array_vec::{ArrayVec, CapacityError};
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::str::{self, Chars, FromStr};
+
+/// Defines the structure for the Array String.
+///
+/// The string holds up to `N` bytes of UTF-8 and dereferences to a `str`.
+///
+/// # Examples
+/// ```
+/// use core::fmt::Write;
+/// use raztos_util::collections::array_string::ArrayString;
+///
+/// let mut text: ArrayString<16> = ArrayString::new();
+///
+/// write!(text, "tick {}", 42).unwrap();
+/// text.push('!').unwrap();
+/// assert_eq!(text, "tick 42!");
+///
+/// assert!(text.push_str(" overflowing").is_err());
+/// assert_eq!(text, "tick 42!");
+/// ```
+pub struct ArrayString<const N: usize> {
+    /// Holds the bytes of the string, which are always valid UTF-8.
+    bytes: ArrayVec<u8, N>,
+}
+
+/// Implements the functionality unique to `ArrayString`.
+impl<const N: usize> ArrayString<N> {
+    /// Creates a new, empty ArrayString.
+    ///
+    /// # Returns
+    /// An ArrayString.
+    pub const fn new() -> Self {
+        ArrayString {
+            bytes: ArrayVec::new(),
+        }
+    }
+
+    /// Gets the maximum number of bytes the string can hold.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the length of the string in bytes.
+    ///
+    /// # Returns
+    /// The number of bytes.
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Determines whether or not the string is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Gets the number of bytes that can still be added.
+    ///
+    /// # Returns
+    /// The remaining capacity.
+    pub const fn remaining_capacity(&self) -> usize {
+        self.bytes.remaining_capacity()
+    }
+
+    /// Gets the string as a `str`.
+    ///
+    /// # Returns
+    /// A string slice.
+    pub fn as_str(&self) -> &str {
+        //
+        // UNSAFE: The bytes are always valid UTF-8.
+        //
+
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /// Gets the string as a mutable `str`.
+    ///
+    /// # Returns
+    /// A mutable string slice.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        //
+        // UNSAFE: The bytes are always valid UTF-8, and `str` only allows changes that keep them
+        //         valid.
+        //
+
+        unsafe { str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Appends a character to the end of the string.
+    ///
+    /// # Arguments
+    /// character - Provides the character to append.
+    ///
+    /// # Returns
+    /// `Ok` if appended, or the character back if it does not fit.
+    pub fn push(&mut self, character: char) -> Result<(), char> {
+        let mut encoded = [0; 4];
+        self.push_str(character.encode_utf8(&mut encoded))
+            .map_err(|_| character)
+    }
+
+    /// Appends a string slice to the end of the string.
+    ///
+    /// Nothing is appended if the slice does not fit.
+    ///
+    /// # Arguments
+    /// string - Provides the string slice to append.
+    ///
+    /// # Returns
+    /// `Ok` if appended, or `CapacityError` if the slice does not fit.
+    pub fn push_str(&mut self, string: &str) -> Result<(), CapacityError> {
+        self.bytes.extend_from_slice(string.as_bytes())
+    }
+
+    /// Removes the last character of the string.
+    ///
+    /// # Returns
+    /// The last character or `None` if the string is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let character = self.as_str().chars().next_back()?;
+        self.bytes.truncate(self.len() - character.len_utf8());
+        Some(character)
+    }
+
+    /// Inserts a character at a byte position.
+    ///
+    /// # Arguments
+    /// index - Provides the byte position to insert at.
+    /// character - Provides the character to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the character back if it does not fit.
+    ///
+    /// # Panics
+    /// Panics if `index` is past the end of the string or not on a character boundary.
+    pub fn insert(&mut self, index: usize, character: char) -> Result<(), char> {
+        let mut encoded = [0; 4];
+        self.insert_str(index, character.encode_utf8(&mut encoded))
+            .map_err(|_| character)
+    }
+
+    /// Inserts a string slice at a byte position.
+    ///
+    /// Nothing is inserted if the slice does not fit.
+    ///
+    /// # Arguments
+    /// index - Provides the byte position to insert at.
+    /// string - Provides the string slice to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or `CapacityError` if the slice does not fit.
+    ///
+    /// # Panics
+    /// Panics if `index` is past the end of the string or not on a character boundary.
+    pub fn insert_str(&mut self, index: usize, string: &str) -> Result<(), CapacityError> {
+        assert!(self.is_char_boundary(index), "index is not a char boundary");
+
+        self.bytes.extend_from_slice(string.as_bytes())?;
+        self.bytes[index..].rotate_right(string.len());
+        Ok(())
+    }
+
+    /// Removes the character at a byte position.
+    ///
+    /// # Arguments
+    /// index - Provides the byte position of the character.
+    ///
+    /// # Returns
+    /// The removed character.
+    ///
+    /// # Panics
+    /// Panics if `index` is not the start of a character in the string.
+    pub fn remove(&mut self, index: usize) -> char {
+        let character = self.as_str()[index..]
+            .chars()
+            .next()
+            .expect("cannot remove a char from the end of a string");
+
+        self.bytes.drain(index..index + character.len_utf8());
+        character
+    }
+
+    /// Keeps only the characters for which a predicate returns `true`, preserving their order.
+    ///
+    /// # Arguments
+    /// keep - Provides the predicate.
+    pub fn retain<F: FnMut(char) -> bool>(&mut self, mut keep: F) {
+        //
+        // Truncates the string to the kept characters when dropped, so a panicking predicate
+        // cannot leave the moved characters behind as invalid UTF-8.
+        //
+
+        struct Guard<'a, const N: usize> {
+            string: &'a mut ArrayString<N>,
+            written: usize,
+        }
+
+        impl<'a, const N: usize> Drop for Guard<'a, N> {
+            fn drop(&mut self) {
+                self.string.bytes.truncate(self.written);
+            }
+        }
+
+        let length = self.len();
+        let mut read = 0;
+        let mut guard = Guard {
+            string: self,
+            written: 0,
+        };
+
+        while read < length {
+            //
+            // UNSAFE: Only whole characters are ever moved below `read`, so the bytes from `read`
+            //         onwards are still valid UTF-8.
+            //
+
+            let character = unsafe { str::from_utf8_unchecked(&guard.string.bytes[read..]) }
+                .chars()
+                .next()
+                .unwrap();
+
+            let width = character.len_utf8();
+
+            if keep(character) {
+                guard
+                    .string
+                    .bytes
+                    .copy_within(read..read + width, guard.written);
+                guard.written += width;
+            }
+
+            read += width;
+        }
+    }
+
+    /// Shortens the string to a byte length.
+    ///
+    /// # Arguments
+    /// length - Provides the new length. Has no effect if it is not less than the current length.
+    ///
+    /// # Panics
+    /// Panics if `length` is not on a character boundary.
+    pub fn truncate(&mut self, length: usize) {
+        if length < self.len() {
+            assert!(
+                self.is_char_boundary(length),
+                "length is not a char boundary"
+            );
+            self.bytes.truncate(length);
+        }
+    }
+
+    /// Removes every character of the string.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    /// Removes a byte range of the string, returning its characters through an iterator.
+    ///
+    /// The range is removed when the iterator is dropped, even if it was not fully iterated.
+    ///
+    /// # Arguments
+    /// range - Provides the byte range to remove.
+    ///
+    /// # Returns
+    /// An iterator over the removed characters.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or either end is not on a character boundary.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, N> {
+        let (start, end) = super::resolve_range(range, self.len());
+        let string = self as *mut Self;
+
+        //
+        // UNSAFE: The characters borrow the string for as long as the iterator borrows it
+        //         mutably, and the string is only modified once the iterator is dropped.
+        //
+
+        let characters = unsafe { (*string).as_str()[start..end].chars() };
+
+        Drain {
+            string,
+            start,
+            end,
+            characters,
+        }
+    }
+}
+
+/// Defines the default value for ArrayString.
+impl<const N: usize> Default for ArrayString<N> {
+    fn default() -> Self {
+        ArrayString::new()
+    }
+}
+
+impl<const N: usize> Clone for ArrayString<N> {
+    fn clone(&self) -> Self {
+        ArrayString {
+            bytes: self.bytes.clone(),
+        }
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for ArrayString<N> {
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for ArrayString<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> Borrow<str> for ArrayString<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Allows formatting directly into an ArrayString, failing once it is full.
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.push_str(string).map_err(|_| fmt::Error)
+    }
+
+    fn write_char(&mut self, character: char) -> fmt::Result {
+        self.push(character).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ArrayString<M>> for ArrayString<N> {
+    fn eq(&self, other: &ArrayString<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, const N: usize> PartialEq<&'a str> for ArrayString<N> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialOrd for ArrayString<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for ArrayString<N> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> Hash for ArrayString<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+/// Creates an ArrayString from a copy of a string slice.
+impl<const N: usize> FromStr for ArrayString<N> {
+    type Err = CapacityError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut result = ArrayString::new();
+        result.push_str(string)?;
+        Ok(result)
+    }
+}
+
+/// Creates an ArrayString from a copy of a string slice.
+impl<'a, const N: usize> TryFrom<&'a str> for ArrayString<N> {
+    type Error = CapacityError;
+
+    fn try_from(string: &'a str) -> Result<Self, Self::Error> {
+        string.parse()
+    }
+}
+
+/// Defines an iterator that removes a range of characters from an ArrayString.
+pub struct Drain<'a, const N: usize> {
+    /// Holds the string being drained.
+    string: *mut ArrayString<N>,
+
+    /// Holds the byte position of the start of the drained range.
+    start: usize,
+
+    /// Holds the byte position after the end of the drained range.
+    end: usize,
+
+    /// Holds the characters of the drained range that have not been iterated.
+    characters: Chars<'a>,
+}
+
+impl<'a, const N: usize> Iterator for Drain<'a, N> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.characters.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.characters.size_hint()
+    }
+}
+
+impl<'a, const N: usize> DoubleEndedIterator for Drain<'a, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.characters.next_back()
+    }
+}
+
+impl<'a, const N: usize> FusedIterator for Drain<'a, N> {}
+
+/// Removes the drained range from the string.
+impl<'a, const N: usize> Drop for Drain<'a, N> {
+    fn drop(&mut self) {
+        //
+        // UNSAFE: The iterator holds the only borrow of the string, and the characters are no
+        //         longer used.
+        //
+
+        unsafe { (*self.string).bytes.drain(self.start..self.end) };
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::string::String;
+
+    #[test]
+    fn push_pop() {
+        let mut text: ArrayString<5> = ArrayString::new();
+
+        text.push('a').unwrap();
+        text.push('é').unwrap();
+        text.push_str("bc").unwrap();
+
+        assert_eq!(text.len(), 5);
+        assert_eq!(text.push('d'), Err('d'));
+        assert_eq!(text.push_str("d"), Err(CapacityError));
+
+        assert_eq!(text.pop(), Some('c'));
+        assert_eq!(text.pop(), Some('b'));
+        assert_eq!(text.pop(), Some('é'));
+        assert_eq!(text, "a");
+        assert_eq!(text.remaining_capacity(), 4);
+
+        //
+        // A multi-byte character is rejected whole rather than split.
+        //
+
+        text.push_str("bcd").unwrap();
+        assert_eq!(text.push('é'), Err('é'));
+        assert_eq!(text, "abcd");
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut text: ArrayString<9> = "hllo".parse().unwrap();
+
+        text.insert(1, 'e').unwrap();
+        text.insert_str(5, " wö").unwrap();
+        assert_eq!(text, "hello wö");
+        assert_eq!(text.insert(0, '!'), Err('!'));
+
+        assert_eq!(text.remove(6), 'w');
+        assert_eq!(text.remove(6), 'ö');
+        assert_eq!(text.remove(0), 'h');
+        assert_eq!(text, "ello ");
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_inside_character() {
+        let mut text: ArrayString<8> = "ö".parse().unwrap();
+        let _ = text.insert(1, 'a');
+    }
+
+    #[test]
+    fn retain_truncate() {
+        let mut text: ArrayString<16> = "a1ö2b3€".parse().unwrap();
+
+        text.retain(|character| !character.is_ascii_digit());
+        assert_eq!(text, "aöb€");
+
+        text.truncate(3);
+        assert_eq!(text, "aö");
+
+        text.clear();
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn retain_panic_keeps_utf8() {
+        let mut text: ArrayString<8> = "éab".parse().unwrap();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            text.retain(|character| match character {
+                'a' => true,
+                'b' => panic!(),
+                _ => false,
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(text, "a");
+    }
+
+    #[test]
+    fn drain() {
+        let mut text: ArrayString<16> = "héllo wörld".parse().unwrap();
+
+        let drained: String = text.drain(..7).collect();
+        assert_eq!(drained, "héllo ");
+        assert_eq!(text, "wörld");
+
+        let mut drain = text.drain(1..3);
+        assert_eq!(drain.next_back(), Some('ö'));
+        drop(drain);
+        assert_eq!(text, "wrld");
+    }
+
+    #[test]
+    fn formatting() {
+        let mut text: ArrayString<8> = ArrayString::new();
+
+        write!(text, "{}-{}", 12, 34).unwrap();
+        assert_eq!(text, "12-34");
+        assert!(write!(text, "{}", 5678).is_err());
+
+        assert_eq!(text, "12-34");
+
+        assert_eq!(std::format!("{}|{:?}", text, text), "12-34|\"12-34\"");
+        assert_eq!(ArrayString::<2>::try_from("abc"), Err(CapacityError));
+    }
+}
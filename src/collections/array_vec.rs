@@ -0,0 +1,834 @@
+//! # Array Vec
+//!
+//! `array_vec` contains a vector with a fixed capacity that stores its elements inline, without
+//! any allocation.
+//!
+//! `ArrayVec` mirrors the familiar `Vec` API, except that operations which would exceed the
+//! capacity return an error instead of growing or panicking. Adding a single element hands the
+//! element back on failure, while bulk operations return a `CapacityError` and leave the vector
+//! untouched.
+
+use core::borrow::{Borrow, BorrowMut};
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut, RangeBounds};
+use core::ptr;
+use core::slice;
+
+/// Defines the error returned when an operation would exceed the capacity of a fixed capacity
+/// collection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CapacityError;
+
+/// Formats a CapacityError as a short description.
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("insufficient capacity")
+    }
+}
+
+/// Defines the structure for the Array Vec.
+///
+/// The vector holds up to `N` elements of type `T` and dereferences to a slice of its elements.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::array_vec::{ArrayVec, CapacityError};
+///
+/// let mut values: ArrayVec<u32, 4> = ArrayVec::new();
+///
+/// values.push(1).unwrap();
+/// values.extend_from_slice(&[2, 3, 4]).unwrap();
+///
+/// assert_eq!(values.push(5), Err(5));
+/// assert_eq!(values.extend_from_slice(&[5]), Err(CapacityError));
+///
+/// values.retain(|value| value % 2 == 0);
+/// assert_eq!(values.as_slice(), &[2, 4]);
+/// ```
+pub struct ArrayVec<T, const N: usize> {
+    /// Holds the storage for the elements.
+    buffer: [MaybeUninit<T>; N],
+
+    /// Holds the number of elements in the vector.
+    length: usize,
+}
+
+/// Implements the functionality unique to `ArrayVec`.
+impl<T, const N: usize> ArrayVec<T, N> {
+    /// Creates a new, empty ArrayVec.
+    ///
+    /// # Returns
+    /// An ArrayVec.
+    pub const fn new() -> Self {
+        ArrayVec {
+            //
+            // UNSAFE: An array of `MaybeUninit` does not require initialization.
+            //
+            buffer: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+            length: 0,
+        }
+    }
+
+    /// Gets the maximum number of elements the vector can hold.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the number of elements in the vector.
+    ///
+    /// # Returns
+    /// The number of elements.
+    pub const fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not the vector is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub const fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Determines whether or not the vector is full.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    pub const fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    /// Gets the number of elements that can still be added.
+    ///
+    /// # Returns
+    /// The remaining capacity.
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.length
+    }
+
+    /// Gets the elements of the vector.
+    ///
+    /// # Returns
+    /// A slice of the elements.
+    pub fn as_slice(&self) -> &[T] {
+        //
+        // UNSAFE: The first `length` slots are initialized.
+        //
+
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.length) }
+    }
+
+    /// Gets the mutable elements of the vector.
+    ///
+    /// # Returns
+    /// A mutable slice of the elements.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        //
+        // UNSAFE: The first `length` slots are initialized.
+        //
+
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.length) }
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// # Arguments
+    /// item - Provides the element to append.
+    ///
+    /// # Returns
+    /// `Ok` if appended, or the element back if the vector is full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+
+        self.buffer[self.length] = MaybeUninit::new(item);
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Removes the last element of the vector.
+    ///
+    /// # Returns
+    /// The last element or `None` if the vector is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.length -= 1;
+
+        //
+        // UNSAFE: The slot was initialized and is no longer considered part of the vector.
+        //
+
+        Some(unsafe { self.buffer[self.length].as_ptr().read() })
+    }
+
+    /// Inserts an element, shifting all elements after it to the right.
+    ///
+    /// # Arguments
+    /// index - Provides the position to insert at.
+    /// item - Provides the element to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the element back if the vector is full.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the length of the vector.
+    pub fn insert(&mut self, index: usize, item: T) -> Result<(), T> {
+        assert!(index <= self.length, "insertion index out of bounds");
+
+        if self.is_full() {
+            return Err(item);
+        }
+
+        //
+        // UNSAFE: There is room for one more element, so shifting the tail right stays within the
+        //         storage and leaves the slot at `index` free to write.
+        //
+
+        unsafe {
+            let slot = self.as_mut_ptr().add(index);
+            ptr::copy(slot, slot.add(1), self.length - index);
+            slot.write(item);
+        }
+
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Removes an element, shifting all elements after it to the left.
+    ///
+    /// # Arguments
+    /// index - Provides the position of the element.
+    ///
+    /// # Returns
+    /// The removed element.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.length, "removal index out of bounds");
+
+        self.length -= 1;
+
+        //
+        // UNSAFE: The element is read out before the tail is shifted over its slot.
+        //
+
+        unsafe {
+            let slot = self.as_mut_ptr().add(index);
+            let item = slot.read();
+            ptr::copy(slot.add(1), slot, self.length - index);
+            item
+        }
+    }
+
+    /// Removes an element by replacing it with the last element.
+    ///
+    /// # Arguments
+    /// index - Provides the position of the element.
+    ///
+    /// # Returns
+    /// The removed element.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.length, "removal index out of bounds");
+
+        let last = self.length - 1;
+        self.as_mut_slice().swap(index, last);
+        self.length = last;
+
+        //
+        // UNSAFE: The slot was initialized and is no longer considered part of the vector.
+        //
+
+        unsafe { self.buffer[last].as_ptr().read() }
+    }
+
+    /// Shortens the vector, dropping the elements past the new length.
+    ///
+    /// # Arguments
+    /// length - Provides the new length. Has no effect if it is not less than the current length.
+    pub fn truncate(&mut self, length: usize) {
+        if length >= self.length {
+            return;
+        }
+
+        let tail = &mut self.as_mut_slice()[length..] as *mut [T];
+
+        //
+        // UNSAFE: The tail is forgotten before being dropped, so a panicking destructor cannot
+        //         cause a double drop.
+        //
+
+        self.length = length;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    /// Removes and drops every element of the vector.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Keeps only the elements for which a predicate returns `true`, preserving their order.
+    ///
+    /// # Arguments
+    /// keep - Provides the predicate.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        let length = self.length;
+
+        //
+        // Forget the elements while they are being shuffled, so a panicking predicate or
+        // destructor leaks the rest rather than dropping anything twice.
+        //
+
+        self.length = 0;
+
+        let base = self.as_mut_ptr();
+        let mut kept = 0;
+
+        for index in 0..length {
+            //
+            // UNSAFE: Every index below `length` is initialized, and the kept elements are moved
+            //         down into slots that were either kept or already dropped.
+            //
+
+            unsafe {
+                let slot = base.add(index);
+
+                if keep(&*slot) {
+                    if kept != index {
+                        ptr::copy_nonoverlapping(slot, base.add(kept), 1);
+                    }
+
+                    kept += 1;
+                } else {
+                    ptr::drop_in_place(slot);
+                }
+            }
+        }
+
+        self.length = kept;
+    }
+
+    /// Removes a range of elements, returning them through an iterator.
+    ///
+    /// The elements after the range are shifted down when the iterator is dropped, and any
+    /// elements of the range that were not iterated are dropped.
+    ///
+    /// # Arguments
+    /// range - Provides the range of positions to remove.
+    ///
+    /// # Returns
+    /// An iterator over the removed elements.
+    ///
+    /// # Panics
+    /// Panics if the range is decreasing or extends past the length of the vector.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, N> {
+        let (start, end) = super::resolve_range(range, self.length);
+        let tail_length = self.length - end;
+
+        //
+        // Forget the drained elements and the tail until the iterator is dropped.
+        //
+
+        self.length = start;
+
+        Drain {
+            vector: self,
+            front: start,
+            back: end,
+            tail_start: end,
+            tail_length,
+        }
+    }
+
+    /// Gets a pointer to the first element of the storage.
+    fn as_ptr(&self) -> *const T {
+        self.buffer.as_ptr() as *const T
+    }
+
+    /// Gets a mutable pointer to the first element of the storage.
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.buffer.as_mut_ptr() as *mut T
+    }
+}
+
+/// Implements the clone functionality of `ArrayVec`.
+impl<T: Clone, const N: usize> ArrayVec<T, N> {
+    /// Appends clones of every element of a slice.
+    ///
+    /// Nothing is appended if the slice does not fit.
+    ///
+    /// # Arguments
+    /// items - Provides the elements to append.
+    ///
+    /// # Returns
+    /// `Ok` if appended, or `CapacityError` if the slice does not fit.
+    pub fn extend_from_slice(&mut self, items: &[T]) -> Result<(), CapacityError> {
+        if items.len() > self.remaining_capacity() {
+            return Err(CapacityError);
+        }
+
+        for item in items {
+            self.buffer[self.length] = MaybeUninit::new(item.clone());
+            self.length += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// Drops the elements of the vector.
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Defines the default value for ArrayVec.
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        ArrayVec::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = ArrayVec::new();
+
+        for item in self.iter() {
+            clone.buffer[clone.length] = MaybeUninit::new(item.clone());
+            clone.length += 1;
+        }
+
+        clone
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArrayVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for ArrayVec<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for ArrayVec<T, N> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> BorrowMut<[T]> for ArrayVec<T, N> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+/// Formats an ArrayVec as the list of its elements.
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T: PartialEq, const N: usize, const M: usize> PartialEq<ArrayVec<T, M>> for ArrayVec<T, N> {
+    fn eq(&self, other: &ArrayVec<T, M>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<[T]> for ArrayVec<T, N> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayVec<T, N> {}
+
+impl<T: Hash, const N: usize> Hash for ArrayVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+
+/// Creates a full ArrayVec from an array.
+impl<T, const N: usize> From<[T; N]> for ArrayVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        let array = ManuallyDrop::new(array);
+
+        ArrayVec {
+            //
+            // UNSAFE: `[T; N]` and `[MaybeUninit<T>; N]` have the same layout, and the original
+            //         array is never dropped.
+            //
+            buffer: unsafe { ptr::read(&*array as *const [T; N] as *const [MaybeUninit<T>; N]) },
+            length: N,
+        }
+    }
+}
+
+/// Creates an ArrayVec from clones of the elements of a slice.
+impl<T: Clone, const N: usize> TryFrom<&[T]> for ArrayVec<T, N> {
+    type Error = CapacityError;
+
+    fn try_from(items: &[T]) -> Result<Self, Self::Error> {
+        let mut vector = ArrayVec::new();
+        vector.extend_from_slice(items)?;
+        Ok(vector)
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            vector: self,
+            index: 0,
+        }
+    }
+}
+
+/// Defines an iterator that moves the elements out of an ArrayVec.
+pub struct IntoIter<T, const N: usize> {
+    /// Holds the vector being consumed.
+    vector: ArrayVec<T, N>,
+
+    /// Holds the position of the next element to yield from the front.
+    index: usize,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.vector.length {
+            return None;
+        }
+
+        self.index += 1;
+
+        //
+        // UNSAFE: Slots between `index` and `length` are initialized and each is read once.
+        //
+
+        Some(unsafe { self.vector.buffer[self.index - 1].as_ptr().read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vector.length - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index == self.vector.length {
+            return None;
+        }
+
+        self.vector.pop()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+/// Drops the elements that were not iterated.
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let index = self.index;
+        let length = self.vector.length;
+        self.vector.length = 0;
+
+        let remaining = &mut self.vector.buffer[index..length] as *mut [MaybeUninit<T>];
+
+        //
+        // UNSAFE: The slots between `index` and `length` are initialized and were not yielded.
+        //
+
+        unsafe { ptr::drop_in_place(remaining as *mut [T]) };
+    }
+}
+
+/// Defines an iterator that removes a range of elements from an ArrayVec.
+pub struct Drain<'a, T, const N: usize> {
+    /// Holds the vector being drained.
+    vector: &'a mut ArrayVec<T, N>,
+
+    /// Holds the position of the next element to yield from the front.
+    front: usize,
+
+    /// Holds the position after the next element to yield from the back.
+    back: usize,
+
+    /// Holds the position of the first element after the drained range.
+    tail_start: usize,
+
+    /// Holds the number of elements after the drained range.
+    tail_length: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for Drain<'a, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.front += 1;
+
+        //
+        // UNSAFE: Slots in the drained range are initialized and each is read once.
+        //
+
+        Some(unsafe { self.vector.buffer[self.front - 1].as_ptr().read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Drain<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+
+        //
+        // UNSAFE: Slots in the drained range are initialized and each is read once.
+        //
+
+        Some(unsafe { self.vector.buffer[self.back].as_ptr().read() })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Drain<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for Drain<'a, T, N> {}
+
+/// Drops the elements that were not iterated and closes the gap left by the drained range.
+impl<'a, T, const N: usize> Drop for Drain<'a, T, N> {
+    fn drop(&mut self) {
+        let remaining = &mut self.vector.buffer[self.front..self.back] as *mut [MaybeUninit<T>];
+        let start = self.vector.length;
+
+        //
+        // UNSAFE: The remaining slots of the range are initialized and were not yielded. The tail
+        //         is initialized and is moved down to directly follow the kept elements.
+        //
+
+        unsafe {
+            ptr::drop_in_place(remaining as *mut [T]);
+
+            let base = self.vector.as_mut_ptr();
+            ptr::copy(base.add(self.tail_start), base.add(start), self.tail_length);
+        }
+
+        self.vector.length = start + self.tail_length;
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::test_helpers::DropCounter;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn push_pop() {
+        let mut values: ArrayVec<u32, 3> = ArrayVec::new();
+
+        assert!(values.is_empty());
+        assert_eq!(values.pop(), None);
+
+        values.push(1).unwrap();
+        values.push(2).unwrap();
+        values.push(3).unwrap();
+
+        assert!(values.is_full());
+        assert_eq!(values.remaining_capacity(), 0);
+        assert_eq!(values.push(4), Err(4));
+        assert_eq!(values[1], 2);
+
+        assert_eq!(values.pop(), Some(3));
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut values: ArrayVec<u32, 5> = ArrayVec::new();
+
+        values.insert(0, 2).unwrap();
+        values.insert(0, 0).unwrap();
+        values.insert(1, 1).unwrap();
+        values.insert(3, 4).unwrap();
+        values.insert(3, 3).unwrap();
+        assert_eq!(values.as_slice(), &[0, 1, 2, 3, 4]);
+        assert_eq!(values.insert(2, 9), Err(9));
+
+        assert_eq!(values.remove(1), 1);
+        assert_eq!(values.swap_remove(0), 0);
+        assert_eq!(values.as_slice(), &[4, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_out_of_bounds() {
+        let mut values: ArrayVec<u32, 5> = ArrayVec::new();
+        let _ = values.insert(1, 1);
+    }
+
+    #[test]
+    fn extend_from_slice() {
+        let mut values: ArrayVec<u32, 4> = ArrayVec::new();
+
+        values.extend_from_slice(&[1, 2]).unwrap();
+        assert_eq!(values.extend_from_slice(&[3, 4, 5]), Err(CapacityError));
+        assert_eq!(values.as_slice(), &[1, 2]);
+
+        values.extend_from_slice(&[3, 4]).unwrap();
+        assert_eq!(values, ArrayVec::<u32, 4>::from([1, 2, 3, 4]));
+        assert_eq!(
+            ArrayVec::<u32, 2>::try_from(&values[..]),
+            Err(CapacityError)
+        );
+    }
+
+    #[test]
+    fn retain_drops_rejected() {
+        let drops = Cell::new(0);
+        let mut values: ArrayVec<(u32, DropCounter), 6> = ArrayVec::new();
+
+        for value in 0..6 {
+            let _ = values.push((value, DropCounter(&drops)));
+        }
+
+        values.retain(|(value, _)| value % 3 != 0);
+        assert_eq!(drops.get(), 2);
+        assert_eq!(
+            values.iter().map(|(value, _)| *value).collect::<Vec<_>>(),
+            [1, 2, 4, 5]
+        );
+
+        values.truncate(1);
+        assert_eq!(drops.get(), 5);
+
+        drop(values);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn drain() {
+        let mut values: ArrayVec<u32, 6> = ArrayVec::from([0, 1, 2, 3, 4, 5]);
+
+        let mut drained = values.drain(1..4);
+        assert_eq!(drained.len(), 3);
+        assert_eq!(drained.next(), Some(1));
+        assert_eq!(drained.next_back(), Some(3));
+        drop(drained);
+        assert_eq!(values.as_slice(), &[0, 4, 5]);
+
+        assert_eq!(values.drain(..).collect::<Vec<_>>(), [0, 4, 5]);
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn drain_drops_remaining() {
+        let drops = Cell::new(0);
+        let mut values: ArrayVec<DropCounter, 4> = ArrayVec::new();
+
+        for _ in 0..4 {
+            let _ = values.push(DropCounter(&drops));
+        }
+
+        values.drain(1..=2);
+        assert_eq!(drops.get(), 2);
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn into_iter() {
+        let drops = Cell::new(0);
+        let values: ArrayVec<u32, 4> = ArrayVec::from([1, 2, 3, 4]);
+
+        let mut iter = values.clone().into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.collect::<Vec<_>>(), [2, 3]);
+
+        assert_eq!((&values).into_iter().sum::<u32>(), 10);
+        assert_eq!(std::format!("{:?}", values), "[1, 2, 3, 4]");
+
+        let mut counters: ArrayVec<DropCounter, 3> = ArrayVec::new();
+        for _ in 0..3 {
+            let _ = counters.push(DropCounter(&drops));
+        }
+
+        let mut iter = counters.into_iter();
+        drop(iter.next());
+        drop(iter);
+        assert_eq!(drops.get(), 3);
+    }
+}
@@ -1,4 +1,10 @@
-use super::{find_highest_set_bit, find_lowest_set_bit, FastBitField};
+use super::{
+    bit_range_mask, clamp_range, count_set_bits, find_clear_run_in_groups, find_highest_set_bit,
+    find_lowest_set_bit, find_nth_set_bit, FastBitField, SetBitIter,
+};
+use core::fmt;
+use core::iter::FromIterator;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, RangeBounds};
 
 /// Defines the number of bitfield groups in a large bitfield
 const LARGE_BIT_FIELD_GROUP_COUNT: usize = core::mem::size_of::<usize>() * 8;
@@ -8,17 +14,167 @@ const LARGE_BIT_FIELD_BIT_SIZE: usize = LARGE_BIT_FIELD_GROUP_COUNT * LARGE_BIT_
 
 /// Defines the structure and fast_bitfield interface for Large Bitfieds.
 /// A Large Bitfield is a strcture that holds an array of `sizeof(usize) * 8` `usize` values as well
-/// as a "layer_cache" `usize` field to quickly determine highest and lowest set bits.
+/// as a "layer_cache" `usize` field to quickly determine highest and lowest set bits and a
+/// "full_layer_cache" `usize` field to quickly determine highest and lowest clear bits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LargeBitField {
     /// Holds a bitfield describing which sub bitfields currently have any set bits.
     layer_cache: usize,
 
+    /// Holds a bitfield describing which sub bitfields currently have all bits set.
+    full_layer_cache: usize,
+
     /// Holds the bitfield state.
     bitfield: [usize; LARGE_BIT_FIELD_GROUP_COUNT],
 }
 
 /// Defines the FastBitField interface for LargeBitField.
 impl LargeBitField {
+    /// Creates a new, empty LargeBitField. Usable in `const` and `static` items.
+    ///
+    /// # Returns
+    /// A LargeBitField.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// static EMPTY: LargeBitField = LargeBitField::new();
+    /// assert!(EMPTY.is_empty());
+    /// ```
+    pub const fn new() -> Self {
+        LargeBitField {
+            layer_cache: 0,
+            full_layer_cache: 0,
+            bitfield: [0; LARGE_BIT_FIELD_GROUP_COUNT],
+        }
+    }
+
+    /// Creates a new LargeBitField from its raw group representation, building the layer caches.
+    /// Usable in `const` and `static` items.
+    ///
+    /// # Arguments
+    /// bits - Provides the initial state of every group in the bit field.
+    ///
+    /// # Returns
+    /// A LargeBitField.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// const fn reserved() -> [usize; BITS_OF] {
+    ///     let mut bits = [0; BITS_OF];
+    ///     bits[1] = 0b11;
+    ///     bits
+    /// }
+    ///
+    /// static RESERVED: LargeBitField = LargeBitField::from_bits(reserved());
+    /// assert_eq!(RESERVED.get_lowest_set_bit(), Some(BITS_OF));
+    /// ```
+    pub const fn from_bits(bits: [usize; LARGE_BIT_FIELD_GROUP_COUNT]) -> Self {
+        let mut layer_cache = 0;
+        let mut full_layer_cache = 0;
+        let mut index = 0;
+
+        while index < LARGE_BIT_FIELD_GROUP_COUNT {
+            layer_cache |= ((bits[index] != 0) as usize) << index;
+            full_layer_cache |= ((bits[index] == core::usize::MAX) as usize) << index;
+            index += 1;
+        }
+
+        LargeBitField {
+            layer_cache,
+            full_layer_cache,
+            bitfield: bits,
+        }
+    }
+
+    /// Gets the raw group representation of the bit field.
+    ///
+    /// # Returns
+    /// The state of every group in the bit field.
+    pub const fn to_bits(&self) -> [usize; LARGE_BIT_FIELD_GROUP_COUNT] {
+        self.bitfield
+    }
+
+    /// Creates a copy of the bit field with an additional bit set. Usable in `const` and `static`
+    /// items.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to set.
+    ///
+    /// # Returns
+    /// The updated LargeBitField. If the index is invalid, the bit field is unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// static MASK: LargeBitField = LargeBitField::new().with_bit(1).with_bit(400);
+    /// assert_eq!(MASK.get_highest_set_bit(), Some(400));
+    /// ```
+    pub const fn with_bit(mut self, index: usize) -> Self {
+        if index < LARGE_BIT_FIELD_BIT_SIZE {
+            let top_layer = index / LARGE_BIT_FIELD_GROUP_COUNT;
+            let bottom_layer = index % LARGE_BIT_FIELD_GROUP_COUNT;
+
+            self.bitfield[top_layer] |= 1 << bottom_layer;
+            self.layer_cache |= 1 << top_layer;
+            self.full_layer_cache |=
+                ((self.bitfield[top_layer] == core::usize::MAX) as usize) << top_layer;
+        }
+
+        self
+    }
+
+    /// Creates a copy of the bit field with a bit cleared. Usable in `const` and `static` items.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to clear.
+    ///
+    /// # Returns
+    /// The updated LargeBitField. If the index is invalid, the bit field is unchanged.
+    pub const fn without_bit(mut self, index: usize) -> Self {
+        if index < LARGE_BIT_FIELD_BIT_SIZE {
+            let top_layer = index / LARGE_BIT_FIELD_GROUP_COUNT;
+            let bottom_layer = index % LARGE_BIT_FIELD_GROUP_COUNT;
+
+            self.bitfield[top_layer] &= !(1 << bottom_layer);
+            self.full_layer_cache &= !(1 << top_layer);
+            self.layer_cache &= !(((self.bitfield[top_layer] == 0) as usize) << top_layer);
+        }
+
+        self
+    }
+
+    /// Gets the value of a specific bit in the bit field. Usable in `const` and `static` items.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to test.
+    ///
+    /// # Returns
+    /// `true` if the bit is set, `false` if it is cleared or the index is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::LargeBitField;
+    ///
+    /// const MASK: LargeBitField = LargeBitField::new().with_bit(200);
+    /// const IS_SET: bool = MASK.is_bit_set(200);
+    /// assert!(IS_SET);
+    /// ```
+    pub const fn is_bit_set(&self, index: usize) -> bool {
+        if index >= LARGE_BIT_FIELD_BIT_SIZE {
+            return false;
+        }
+
+        let top_layer = index / LARGE_BIT_FIELD_GROUP_COUNT;
+        let bottom_mask = 1 << (index % LARGE_BIT_FIELD_GROUP_COUNT);
+        (self.bitfield[top_layer] & bottom_mask) != 0
+    }
+
     /// Gets whether or not a specific group in the bit field has any bits set.
     ///
     /// # Arguments
@@ -135,6 +291,242 @@ impl LargeBitField {
         }
     }
 
+    /// Determines whether every bit set in this bit field is also set in another.
+    ///
+    /// # Arguments
+    /// other - Provides the bit field to compare against.
+    ///
+    /// # Returns
+    /// `true` if this bit field is a subset of `other`, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// let mut other = LargeBitField::new();
+    /// large.set_bit(100);
+    /// other.set_bit(100);
+    /// other.set_bit(3);
+    ///
+    /// assert!(large.is_subset(&other));
+    /// assert!(!other.is_subset(&large));
+    /// ```
+    pub fn is_subset(&self, other: &LargeBitField) -> bool {
+        if (self.layer_cache & !other.layer_cache) != 0 {
+            return false;
+        }
+
+        self.bitfield
+            .iter()
+            .zip(other.bitfield.iter())
+            .all(|(group, other_group)| (group & !other_group) == 0)
+    }
+
+    /// Determines whether this bit field and another have no set bits in common.
+    ///
+    /// # Arguments
+    /// other - Provides the bit field to compare against.
+    ///
+    /// # Returns
+    /// `true` if no bit is set in both bit fields, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// let mut other = LargeBitField::new();
+    /// large.set_bit(100);
+    /// other.set_bit(101);
+    ///
+    /// assert!(large.is_disjoint(&other));
+    ///
+    /// other.set_bit(100);
+    /// assert!(!large.is_disjoint(&other));
+    /// ```
+    pub fn is_disjoint(&self, other: &LargeBitField) -> bool {
+        if (self.layer_cache & other.layer_cache) == 0 {
+            return true;
+        }
+
+        self.bitfield
+            .iter()
+            .zip(other.bitfield.iter())
+            .all(|(group, other_group)| (group & other_group) == 0)
+    }
+
+    /// Determines whether this bit field and another have any set bits in common.
+    ///
+    /// # Arguments
+    /// other - Provides the bit field to compare against.
+    ///
+    /// # Returns
+    /// `true` if at least one bit is set in both bit fields, `false` otherwise.
+    pub fn intersects(&self, other: &LargeBitField) -> bool {
+        !self.is_disjoint(other)
+    }
+
+    /// Sets a contiguous range of bits in the bit field, a group at a time.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to set.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_range(60..200);
+    ///
+    /// assert_eq!(large.count_ones(), 140);
+    /// assert_eq!(large.get_lowest_set_bit(), Some(60));
+    /// assert_eq!(large.get_highest_set_bit(), Some(199));
+    /// ```
+    pub fn set_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = clamp_range(range, LARGE_BIT_FIELD_BIT_SIZE);
+        let mut index = start;
+
+        while index < end {
+            let (group_index, group_mask, next_index) = Self::next_range_group(index, end);
+
+            //
+            // UNSAFE: group_index is derived from an index clamped to the size of the bit field.
+            //
+
+            unsafe {
+                self.set_group_unchecked(group_index, group_mask);
+            }
+
+            index = next_index;
+        }
+    }
+
+    /// Clears a contiguous range of bits in the bit field, a group at a time.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to clear.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    pub fn clear_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = clamp_range(range, LARGE_BIT_FIELD_BIT_SIZE);
+        let mut index = start;
+
+        while index < end {
+            let (group_index, group_mask, next_index) = Self::next_range_group(index, end);
+
+            //
+            // UNSAFE: group_index is derived from an index clamped to the size of the bit field.
+            //
+
+            unsafe {
+                self.clear_group_unchecked(group_index, group_mask);
+            }
+
+            index = next_index;
+        }
+    }
+
+    /// Determines whether every bit in a contiguous range is set.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to test.
+    ///
+    /// # Returns
+    /// `true` if every bit in the range is set or the range is empty, `false` otherwise.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_range(60..200);
+    ///
+    /// assert!(large.all_set_in(60..200));
+    /// assert!(!large.all_set_in(59..200));
+    /// ```
+    pub fn all_set_in<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        let (start, end) = clamp_range(range, LARGE_BIT_FIELD_BIT_SIZE);
+        let mut index = start;
+
+        while index < end {
+            let (group_index, group_mask, next_index) = Self::next_range_group(index, end);
+            if (self.bitfield[group_index] & group_mask) != group_mask {
+                return false;
+            }
+
+            index = next_index;
+        }
+
+        true
+    }
+
+    /// Determines whether any bit in a contiguous range is set.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to test.
+    ///
+    /// # Returns
+    /// `true` if at least one bit in the range is set, `false` otherwise.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_bit(200);
+    ///
+    /// assert!(large.any_set_in(100..201));
+    /// assert!(!large.any_set_in(100..200));
+    /// ```
+    pub fn any_set_in<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        let (start, end) = clamp_range(range, LARGE_BIT_FIELD_BIT_SIZE);
+        let mut index = start;
+
+        while index < end {
+            let (group_index, group_mask, next_index) = Self::next_range_group(index, end);
+            if (self.bitfield[group_index] & group_mask) != 0 {
+                return true;
+            }
+
+            index = next_index;
+        }
+
+        false
+    }
+
+    /// Gets an iterator over the indices of the set bits, from lowest to highest.
+    ///
+    /// # Returns
+    /// A SetBitIter.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_bit(7);
+    /// large.set_bit(300);
+    ///
+    /// let mut iter = large.iter_set_bits();
+    /// assert_eq!(iter.next(), Some(7));
+    /// assert_eq!(iter.next(), Some(300));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_set_bits(&self) -> SetBitIter<'_> {
+        SetBitIter::new(&self.bitfield)
+    }
+
     /// Gets whether or not a specific group in the bit field has any bits set.
     ///
     /// # Arguments
@@ -190,7 +582,11 @@ impl LargeBitField {
         let subfield = self.bitfield.get_unchecked_mut(group_index);
         *subfield |= group_field;
 
+        let is_full = (*subfield == core::usize::MAX) as usize;
+        let full_layer_cache_update = (1 << group_index) * is_full;
+
         self.layer_cache |= layer_cache_update;
+        self.full_layer_cache |= full_layer_cache_update;
     }
 
     /// Clears bits in a specific group in the bit field.
@@ -214,6 +610,71 @@ impl LargeBitField {
         let is_clear = (*subfield == 0) as usize;
         let layer_cache_update = (1 << group_index) * is_clear;
         self.layer_cache &= !layer_cache_update;
+
+        let is_not_full = (*subfield != core::usize::MAX) as usize;
+        let full_layer_cache_update = (1 << group_index) * is_not_full;
+        self.full_layer_cache &= !full_layer_cache_update;
+    }
+}
+
+/// Defines private helpers for LargeBitField.
+impl LargeBitField {
+    /// Gets the portion of a bit range that falls within a single group.
+    ///
+    /// # Arguments
+    /// index - Provides the current bit in the range. Must be less than `end`.
+    /// end - Provides the end of the range (exclusive). Must be no larger than the size of the bit
+    /// field.
+    ///
+    /// # Returns
+    /// A tuple of the group index containing `index`, the mask of bits within that group that are
+    /// in the range, and the index of the first bit after the group or `end`.
+    fn next_range_group(index: usize, end: usize) -> (usize, usize, usize) {
+        let group_index = index / LARGE_BIT_FIELD_GROUP_COUNT;
+        let group_base = group_index * LARGE_BIT_FIELD_GROUP_COUNT;
+        let group_end = core::cmp::min(group_base + LARGE_BIT_FIELD_GROUP_COUNT, end);
+        let group_mask = bit_range_mask(index - group_base, group_end - group_base);
+
+        (group_index, group_mask, group_end)
+    }
+
+    /// Counts the set bits in a selection of groups.
+    ///
+    /// # Arguments
+    /// groups - Provides a bitfield describing which groups to count.
+    ///
+    /// # Returns
+    /// The number of set bits in the selected groups.
+    fn count_ones_in_groups(&self, groups: usize) -> usize {
+        let mut count = 0;
+        let mut groups = groups;
+
+        while groups != 0 {
+            let level = find_lowest_set_bit(groups);
+            count += count_set_bits(self.bitfield[level]);
+            groups &= groups - 1;
+        }
+
+        count
+    }
+
+    /// Rebuilds the layer caches from the current state of every group. Used after operations
+    /// that modify whole groups at once.
+    fn refresh_layer_caches(&mut self) {
+        let mut layer_cache = 0;
+        let mut full_layer_cache = 0;
+
+        for (index, group) in self.bitfield.iter().enumerate() {
+            //
+            // Turn boolean into a usize to avoid branching.
+            //
+
+            layer_cache |= ((*group != 0) as usize) << index;
+            full_layer_cache |= ((*group == core::usize::MAX) as usize) << index;
+        }
+
+        self.layer_cache = layer_cache;
+        self.full_layer_cache = full_layer_cache;
     }
 }
 
@@ -224,10 +685,7 @@ impl FastBitField for LargeBitField {
     /// # Returns
     /// A LargeBitField.
     fn new() -> Self {
-        LargeBitField {
-            layer_cache: 0,
-            bitfield: [0; LARGE_BIT_FIELD_GROUP_COUNT],
-        }
+        LargeBitField::new()
     }
 
     /// Gets the number of bits available in the bitfield type.
@@ -262,6 +720,9 @@ impl FastBitField for LargeBitField {
 
         self.layer_cache |= 1 << top_layer;
         *sub_field |= 1 << bottom_layer;
+        if *sub_field == core::usize::MAX {
+            self.full_layer_cache |= 1 << top_layer;
+        }
     }
 
     /// Clears a bit in the bit field
@@ -279,6 +740,7 @@ impl FastBitField for LargeBitField {
         };
 
         *sub_field &= !(1 << bottom_layer);
+        self.full_layer_cache &= !(1 << top_layer);
         if *sub_field == 0 {
             self.layer_cache &= !(1 << top_layer);
         }
@@ -409,6 +871,208 @@ impl FastBitField for LargeBitField {
         self.layer_cache == 0
     }
 
+    /// Determines whether or not every bit in the bitfield is set.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// let mut large = LargeBitField::new();
+    /// assert!(!large.is_full());
+    ///
+    /// large.set_field(&[core::usize::MAX; BITS_OF]);
+    /// assert!(large.is_full());
+    /// ```
+    fn is_full(&self) -> bool {
+        self.full_layer_cache == core::usize::MAX
+    }
+
+    /// Gets the lowest clear bit.
+    ///
+    /// # Returns
+    /// The lowest clear bit index or `None` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_field(&[core::usize::MAX; BITS_OF]);
+    ///
+    /// assert_eq!(large.get_lowest_clear_bit(), None);
+    ///
+    /// large.clear_bit(70);
+    /// assert_eq!(large.get_lowest_clear_bit(), Some(70));
+    ///
+    /// large.clear_bit(90);
+    /// assert_eq!(large.get_lowest_clear_bit(), Some(70));
+    /// ```
+    fn get_lowest_clear_bit(&self) -> Option<usize> {
+        if self.is_full() {
+            return None;
+        }
+
+        Some(self.get_lowest_clear_bit_unchecked())
+    }
+
+    /// Gets the highest clear bit.
+    ///
+    /// # Returns
+    /// The highest clear bit index or `None` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_field(&[core::usize::MAX; BITS_OF]);
+    ///
+    /// assert_eq!(large.get_highest_clear_bit(), None);
+    ///
+    /// large.clear_bit(70);
+    /// assert_eq!(large.get_highest_clear_bit(), Some(70));
+    ///
+    /// large.clear_bit(90);
+    /// assert_eq!(large.get_highest_clear_bit(), Some(90));
+    /// ```
+    fn get_highest_clear_bit(&self) -> Option<usize> {
+        if self.is_full() {
+            return None;
+        }
+
+        Some(self.get_highest_clear_bit_unchecked())
+    }
+
+    /// Gets the number of set bits in the bit field.
+    ///
+    /// # Returns
+    /// The number of set bits.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// assert_eq!(large.count_ones(), 0);
+    ///
+    /// large.set_bit(3);
+    /// large.set_bit(300);
+    /// assert_eq!(large.count_ones(), 2);
+    /// ```
+    fn count_ones(&self) -> usize {
+        self.count_ones_in_groups(self.layer_cache)
+    }
+
+    /// Gets the number of set bits below a specific bit in the bit field.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to count up to (exclusive).
+    ///
+    /// # Returns
+    /// The number of set bits with an index lower than `index`. If `index` is larger than the
+    /// number of bits in the bit field, all set bits are counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_bit(3);
+    /// large.set_bit(300);
+    ///
+    /// assert_eq!(large.rank(3), 0);
+    /// assert_eq!(large.rank(4), 1);
+    /// assert_eq!(large.rank(301), 2);
+    /// ```
+    fn rank(&self, index: usize) -> usize {
+        if index >= LARGE_BIT_FIELD_BIT_SIZE {
+            return self.count_ones();
+        }
+
+        let top_layer = index / LARGE_BIT_FIELD_GROUP_COUNT;
+        let bottom_mask = (1 << (index % LARGE_BIT_FIELD_GROUP_COUNT)) - 1;
+        let lower_groups = self.layer_cache & ((1 << top_layer) - 1);
+        let partial_group = self.bitfield[top_layer] & bottom_mask;
+
+        self.count_ones_in_groups(lower_groups) + count_set_bits(partial_group)
+    }
+
+    /// Gets the index of the n-th set bit in the bit field.
+    ///
+    /// # Arguments
+    /// n - Provides the zero based position of the set bit to find, counting from the lowest
+    /// set bit.
+    ///
+    /// # Returns
+    /// The index of the n-th set bit or `None` if fewer than `n + 1` bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_bit(3);
+    /// large.set_bit(300);
+    ///
+    /// assert_eq!(large.select(0), Some(3));
+    /// assert_eq!(large.select(1), Some(300));
+    /// assert_eq!(large.select(2), None);
+    /// ```
+    fn select(&self, n: usize) -> Option<usize> {
+        let mut remaining = n;
+        let mut groups = self.layer_cache;
+
+        //
+        // Only visit groups that have bits set.
+        //
+
+        while groups != 0 {
+            let level = find_lowest_set_bit(groups);
+            let sub_field = self.bitfield[level];
+            let group_count = count_set_bits(sub_field);
+
+            if remaining < group_count {
+                return find_nth_set_bit(sub_field, remaining)
+                    .map(|bit| (level * LARGE_BIT_FIELD_GROUP_COUNT) + bit);
+            }
+
+            remaining -= group_count;
+            groups &= groups - 1;
+        }
+
+        None
+    }
+
+    /// Finds the lowest run of contiguous clear bits of a given length.
+    ///
+    /// # Arguments
+    /// length - Provides the number of contiguous clear bits to find.
+    ///
+    /// # Returns
+    /// The index of the first bit of the run or `None` if no such run exists or `length` is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_range(0..10);
+    /// large.set_range(20..BITS_OF);
+    ///
+    /// assert_eq!(large.find_clear_run(10), Some(10));
+    /// assert_eq!(large.find_clear_run(11), Some(BITS_OF));
+    /// ```
+    fn find_clear_run(&self, length: usize) -> Option<usize> {
+        find_clear_run_in_groups(&self.bitfield, length)
+    }
+
     /// Gets the lowest set bit, guaranteed to have no branches and be in constant time, completely
     /// invariant of the state of the bit field. If no bits are set, the result is undefined.
     ///
@@ -485,6 +1149,78 @@ impl FastBitField for LargeBitField {
         }
     }
 
+    /// Gets the lowest clear bit, guaranteed to have no branches and be in constant time,
+    /// completely invariant of the state of the bit field. If all bits are set, the result is
+    /// undefined.
+    ///
+    /// This function should only be used if the caller can guarantee the bitfield will always
+    /// have at least one bit clear.
+    ///
+    /// # Returns
+    /// The lowest clear bit index or `UNDEFINED` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_group(0, core::usize::MAX);
+    /// assert_eq!(large.get_lowest_clear_bit_unchecked(), BITS_OF);
+    ///
+    /// large.clear_bit(9);
+    /// assert_eq!(large.get_lowest_clear_bit_unchecked(), 9);
+    /// ```
+    fn get_lowest_clear_bit_unchecked(&self) -> usize {
+        let level = find_lowest_set_bit(!self.full_layer_cache);
+
+        //
+        // UNSAFE: level is guaranteed to be between 0 and SMALL_BIT_FIELD_SIZE - 1 by the
+        // the definition of find_lowest_set_bit. No need to perform bounds checking on the array.
+        //
+
+        unsafe {
+            let sub_field = self.bitfield.get_unchecked(level);
+            (level * LARGE_BIT_FIELD_GROUP_COUNT) + find_lowest_set_bit(!*sub_field)
+        }
+    }
+
+    /// Gets the highest clear bit, guaranteed to have no branches and be in constant time,
+    /// completely invariant of the state of the bit field. If all bits are set, the result is
+    /// undefined.
+    ///
+    /// This function should only be used if the caller can guarantee the bitfield will always
+    /// have at least one bit clear.
+    ///
+    /// # Returns
+    /// The highest clear bit index or `UNDEFINED` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, LargeBitField};
+    /// const BITS_OF: usize = core::mem::size_of::<usize>() * 8;
+    ///
+    /// let mut large = LargeBitField::new();
+    /// large.set_group(BITS_OF - 1, core::usize::MAX);
+    /// assert_eq!(large.get_highest_clear_bit_unchecked(), (BITS_OF * BITS_OF) - BITS_OF - 1);
+    ///
+    /// large.clear_bit((BITS_OF * BITS_OF) - 9);
+    /// assert_eq!(large.get_highest_clear_bit_unchecked(), (BITS_OF * BITS_OF) - 9);
+    /// ```
+    fn get_highest_clear_bit_unchecked(&self) -> usize {
+        let level = find_highest_set_bit(!self.full_layer_cache);
+
+        //
+        // UNSAFE: level is guaranteed to be between 0 and SMALL_BIT_FIELD_SIZE - 1 by the
+        // the definition of find_highest_set_bit. No need to perform bounds checking on the array.
+        //
+
+        unsafe {
+            let sub_field = self.bitfield.get_unchecked(level);
+            (level * LARGE_BIT_FIELD_GROUP_COUNT) + find_highest_set_bit(!*sub_field)
+        }
+    }
+
     /// Sets a bit in the bit field.
     ///
     /// # Arguments
@@ -500,6 +1236,14 @@ impl FastBitField for LargeBitField {
         self.layer_cache |= 1 << top_layer;
         let sub_field = self.bitfield.get_unchecked_mut(top_layer);
         *sub_field |= 1 << bottom_layer;
+
+        //
+        // Turn boolean into a usize to avoid branching.
+        //
+
+        let is_full = (*sub_field == core::usize::MAX) as usize;
+        let full_layer_cache_update = (1 << top_layer) * is_full;
+        self.full_layer_cache |= full_layer_cache_update;
     }
 
     /// Clears a bit in the bit field
@@ -523,7 +1267,8 @@ impl FastBitField for LargeBitField {
 
         let is_clear = (*sub_field == 0) as usize;
         let layer_cache_update = (1 << top_layer) * is_clear;
-        self.layer_cache &= !layer_cache_update
+        self.layer_cache &= !layer_cache_update;
+        self.full_layer_cache &= !(1 << top_layer);
     }
 
     /// Gets the value of a specific bit in the bit field.
@@ -559,8 +1304,209 @@ impl FastBitField for LargeBitField {
         let top_layer = index / LARGE_BIT_FIELD_GROUP_COUNT;
         let bottom_mask = 1 << (index % LARGE_BIT_FIELD_GROUP_COUNT);
 
-        let sub_field = self.bitfield.get_unchecked(top_layer);
-        (*sub_field & bottom_mask) != 0
+        let sub_field = self.bitfield.get_unchecked(top_layer);
+        (*sub_field & bottom_mask) != 0
+    }
+}
+
+/// Defines the default value for LargeBitField.
+impl Default for LargeBitField {
+    /// Creates a new, empty LargeBitField
+    fn default() -> Self {
+        LargeBitField::new()
+    }
+}
+
+/// Defines debug formatting for LargeBitField as the set of its set bit indices.
+impl fmt::Debug for LargeBitField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter_set_bits()).finish()
+    }
+}
+
+/// Defines binary formatting for LargeBitField. The groups are written as a single number, highest
+/// group first, with every group padded to its full width.
+impl fmt::Binary for LargeBitField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0b")?;
+        }
+
+        for group in self.bitfield.iter().rev() {
+            write!(f, "{:0width$b}", group, width = LARGE_BIT_FIELD_GROUP_COUNT)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Defines lower case hexadecimal formatting for LargeBitField. The groups are written as a single
+/// number, highest group first, with every group padded to its full width.
+impl fmt::LowerHex for LargeBitField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+
+        for group in self.bitfield.iter().rev() {
+            write!(
+                f,
+                "{:0width$x}",
+                group,
+                width = LARGE_BIT_FIELD_GROUP_COUNT / 4
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Defines creating a LargeBitField from an iterator of bit indices to set.
+/// Indices beyond the size of the bit field are ignored.
+impl FromIterator<usize> for LargeBitField {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut large = LargeBitField::new();
+        large.extend(iter);
+        large
+    }
+}
+
+/// Defines setting the bit indices of an iterator in a LargeBitField.
+/// Indices beyond the size of the bit field are ignored.
+impl Extend<usize> for LargeBitField {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.set_bit(index);
+        }
+    }
+}
+
+/// Defines creating a LargeBitField from its raw group representation.
+impl From<[usize; LARGE_BIT_FIELD_GROUP_COUNT]> for LargeBitField {
+    fn from(bitfield: [usize; LARGE_BIT_FIELD_GROUP_COUNT]) -> Self {
+        LargeBitField::from_bits(bitfield)
+    }
+}
+
+/// Defines converting a LargeBitField into its raw group representation.
+impl From<LargeBitField> for [usize; LARGE_BIT_FIELD_GROUP_COUNT] {
+    fn from(large: LargeBitField) -> Self {
+        large.bitfield
+    }
+}
+
+/// Defines the bitwise and operation for LargeBitField.
+impl BitAnd for LargeBitField {
+    type Output = LargeBitField;
+
+    /// Gets the bits set in both bit fields.
+    fn bitand(mut self, rhs: LargeBitField) -> LargeBitField {
+        self &= &rhs;
+        self
+    }
+}
+
+/// Defines the bitwise and assignment operation for LargeBitField.
+impl BitAndAssign for LargeBitField {
+    /// Clears every bit that is not also set in `rhs`.
+    fn bitand_assign(&mut self, rhs: LargeBitField) {
+        *self &= &rhs;
+    }
+}
+
+/// Defines the bitwise and assignment operation for LargeBitField by reference.
+impl BitAndAssign<&LargeBitField> for LargeBitField {
+    /// Clears every bit that is not also set in `rhs`.
+    fn bitand_assign(&mut self, rhs: &LargeBitField) {
+        for (group, rhs_group) in self.bitfield.iter_mut().zip(rhs.bitfield.iter()) {
+            *group &= *rhs_group;
+        }
+
+        self.refresh_layer_caches();
+    }
+}
+
+/// Defines the bitwise or operation for LargeBitField.
+impl BitOr for LargeBitField {
+    type Output = LargeBitField;
+
+    /// Gets the bits set in either bit field.
+    fn bitor(mut self, rhs: LargeBitField) -> LargeBitField {
+        self |= &rhs;
+        self
+    }
+}
+
+/// Defines the bitwise or assignment operation for LargeBitField.
+impl BitOrAssign for LargeBitField {
+    /// Sets every bit that is set in `rhs`.
+    fn bitor_assign(&mut self, rhs: LargeBitField) {
+        *self |= &rhs;
+    }
+}
+
+/// Defines the bitwise or assignment operation for LargeBitField by reference.
+impl BitOrAssign<&LargeBitField> for LargeBitField {
+    /// Sets every bit that is set in `rhs`.
+    fn bitor_assign(&mut self, rhs: &LargeBitField) {
+        for (group, rhs_group) in self.bitfield.iter_mut().zip(rhs.bitfield.iter()) {
+            *group |= *rhs_group;
+        }
+
+        self.refresh_layer_caches();
+    }
+}
+
+/// Defines the bitwise exclusive or operation for LargeBitField.
+impl BitXor for LargeBitField {
+    type Output = LargeBitField;
+
+    /// Gets the bits set in exactly one of the bit fields.
+    fn bitxor(mut self, rhs: LargeBitField) -> LargeBitField {
+        self ^= &rhs;
+        self
+    }
+}
+
+/// Defines the bitwise exclusive or assignment operation for LargeBitField.
+impl BitXorAssign for LargeBitField {
+    /// Toggles every bit that is set in `rhs`.
+    fn bitxor_assign(&mut self, rhs: LargeBitField) {
+        *self ^= &rhs;
+    }
+}
+
+/// Defines the bitwise exclusive or assignment operation for LargeBitField by reference.
+impl BitXorAssign<&LargeBitField> for LargeBitField {
+    /// Toggles every bit that is set in `rhs`.
+    fn bitxor_assign(&mut self, rhs: &LargeBitField) {
+        for (group, rhs_group) in self.bitfield.iter_mut().zip(rhs.bitfield.iter()) {
+            *group ^= *rhs_group;
+        }
+
+        self.refresh_layer_caches();
+    }
+}
+
+/// Defines the bitwise not operation for LargeBitField.
+impl Not for LargeBitField {
+    type Output = LargeBitField;
+
+    /// Gets the complement of the bit field.
+    fn not(mut self) -> LargeBitField {
+        for group in self.bitfield.iter_mut() {
+            *group = !*group;
+        }
+
+        //
+        // A complemented group has bits set exactly when the original group was not full, and is
+        // full exactly when the original group was empty.
+        //
+
+        let layer_cache = self.layer_cache;
+        self.layer_cache = !self.full_layer_cache;
+        self.full_layer_cache = !layer_cache;
+        self
     }
 }
 
@@ -581,6 +1527,7 @@ mod tests {
         let large = LargeBitField::new();
 
         assert_eq!(large.layer_cache, 0);
+        assert_eq!(large.full_layer_cache, 0);
         for index in 0..LARGE_BIT_FIELD_GROUP_COUNT {
             assert_eq!(large.bitfield[index], 0);
         }
@@ -665,8 +1612,10 @@ mod tests {
         let mut expected_bitfield = [core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT];
 
         large.layer_cache = core::usize::MAX;
+        large.full_layer_cache = core::usize::MAX;
         large.bitfield = [core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT];
         large_unsafe.layer_cache = core::usize::MAX;
+        large_unsafe.full_layer_cache = core::usize::MAX;
         large_unsafe.bitfield = [core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT];
 
         for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
@@ -773,6 +1722,164 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_is_full() {
+        let mut large = LargeBitField::new();
+        let mut large_unsafe = LargeBitField::new();
+        let mut expected_full_layer = 0 as usize;
+
+        for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
+            assert!(!large.is_full());
+            assert!(!large_unsafe.is_full());
+
+            large.set_bit(i);
+            unsafe {
+                large_unsafe.set_bit_unchecked(i);
+            }
+
+            if (i % LARGE_BIT_FIELD_GROUP_COUNT) == (LARGE_BIT_FIELD_GROUP_COUNT - 1) {
+                expected_full_layer |= 1 << (i / LARGE_BIT_FIELD_GROUP_COUNT);
+            }
+
+            assert_eq!(large.full_layer_cache, expected_full_layer);
+            assert_eq!(large_unsafe.full_layer_cache, expected_full_layer);
+        }
+
+        assert!(large.is_full());
+        assert!(large_unsafe.is_full());
+
+        //
+        // Clearing any bit in a group removes it from the full layer cache.
+        //
+
+        large.clear_bit(LARGE_BIT_FIELD_GROUP_COUNT + 3);
+        assert!(!large.is_full());
+        assert_eq!(large.full_layer_cache, core::usize::MAX & !(1 << 1));
+
+        unsafe {
+            large_unsafe.clear_bit_unchecked(LARGE_BIT_FIELD_GROUP_COUNT + 3);
+        }
+
+        assert!(!large_unsafe.is_full());
+        assert_eq!(large_unsafe.full_layer_cache, core::usize::MAX & !(1 << 1));
+
+        //
+        // Group operations keep the full layer cache in sync.
+        //
+
+        large.set_group(1, 1 << 3);
+        assert!(large.is_full());
+
+        large.clear_group(2, 0);
+        assert!(large.is_full());
+
+        large.clear_group(2, 1);
+        assert_eq!(large.full_layer_cache, core::usize::MAX & !(1 << 2));
+    }
+
+    #[test]
+    fn validate_get_lowest_clear_bit() {
+        let mut large = LargeBitField::new();
+        large.set_field(&[core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT]);
+
+        //
+        // Full should result in None for checked variant
+        //
+
+        assert_eq!(large.get_lowest_clear_bit(), None);
+
+        for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
+            large.clear_bit(i);
+            assert_eq!(large.get_lowest_clear_bit(), Some(0));
+            assert_eq!(large.get_lowest_clear_bit_unchecked(), 0);
+        }
+
+        for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
+            assert_eq!(large.get_lowest_clear_bit(), Some(i));
+            assert_eq!(large.get_lowest_clear_bit_unchecked(), i);
+            large.set_bit(i);
+        }
+
+        assert_eq!(large.get_lowest_clear_bit(), None);
+    }
+
+    #[test]
+    fn validate_get_highest_clear_bit() {
+        let mut large = LargeBitField::new();
+        large.set_field(&[core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT]);
+
+        //
+        // Full should result in None for checked variant
+        //
+
+        assert_eq!(large.get_highest_clear_bit(), None);
+
+        for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
+            large.clear_bit(i);
+            assert_eq!(large.get_highest_clear_bit(), Some(i));
+            assert_eq!(large.get_highest_clear_bit_unchecked(), i);
+        }
+
+        for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
+            assert_eq!(
+                large.get_highest_clear_bit(),
+                Some(LARGE_BIT_FIELD_BIT_SIZE - 1)
+            );
+            assert_eq!(
+                large.get_highest_clear_bit_unchecked(),
+                LARGE_BIT_FIELD_BIT_SIZE - 1
+            );
+            large.set_bit(i);
+        }
+
+        assert_eq!(large.get_highest_clear_bit(), None);
+    }
+
+    #[test]
+    fn validate_count_rank_and_select() {
+        let mut large = LargeBitField::new();
+        let mut expected_count = 0;
+
+        //
+        // Empty should have no set bits to count or select.
+        //
+
+        assert_eq!(large.count_ones(), 0);
+        assert_eq!(large.rank(LARGE_BIT_FIELD_BIT_SIZE), 0);
+        assert_eq!(large.select(0), None);
+
+        //
+        // Set every third bit, validating rank and select along the way.
+        //
+
+        for i in 0..LARGE_BIT_FIELD_BIT_SIZE {
+            assert_eq!(large.rank(i), expected_count);
+
+            if (i % 3) == 0 {
+                large.set_bit(i);
+                assert_eq!(large.select(expected_count), Some(i));
+                expected_count += 1;
+            }
+
+            assert_eq!(large.count_ones(), expected_count);
+        }
+
+        assert_eq!(large.select(expected_count), None);
+        assert_eq!(large.rank(core::usize::MAX), expected_count);
+
+        for n in 0..expected_count {
+            assert_eq!(large.select(n), Some(n * 3));
+            assert_eq!(large.rank(n * 3), n);
+        }
+
+        large.set_field(&[core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT]);
+        assert_eq!(large.count_ones(), LARGE_BIT_FIELD_BIT_SIZE);
+        assert_eq!(
+            large.select(LARGE_BIT_FIELD_BIT_SIZE - 1),
+            Some(LARGE_BIT_FIELD_BIT_SIZE - 1)
+        );
+    }
+
     #[test]
     fn validate_test_bit() {
         let mut large = LargeBitField::new();
@@ -1050,6 +2157,408 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_subset_and_disjoint() {
+        let mut large = LargeBitField::new();
+        let mut other = LargeBitField::new();
+        let fives = [core::usize::MAX / 3; LARGE_BIT_FIELD_GROUP_COUNT];
+
+        let a_s = [!(core::usize::MAX / 3); LARGE_BIT_FIELD_GROUP_COUNT];
+
+        //
+        // Empty bit fields are subsets of and disjoint with each other.
+        //
+
+        assert!(large.is_subset(&other));
+        assert!(large.is_disjoint(&other));
+        assert!(!large.intersects(&other));
+
+        large.set_field(&fives);
+        other.set_field(&a_s);
+        assert!(!large.is_subset(&other));
+        assert!(large.is_disjoint(&other));
+        assert!(!large.intersects(&other));
+
+        other.set_bit(LARGE_BIT_FIELD_BIT_SIZE - 2);
+        assert!(!large.is_subset(&other));
+        assert!(!large.is_disjoint(&other));
+        assert!(large.intersects(&other));
+
+        other.set_field(&fives);
+        assert!(large.is_subset(&other));
+        assert!(!other.is_subset(&large));
+    }
+
+    #[test]
+    fn validate_range_operations() {
+        let mut large = LargeBitField::new();
+        let start = LARGE_BIT_FIELD_GROUP_COUNT - 4;
+        let end = (LARGE_BIT_FIELD_GROUP_COUNT * 3) + 4;
+
+        //
+        // Empty and out of bounds ranges result in no change.
+        //
+
+        large.set_range(3..3);
+        large.set_range(LARGE_BIT_FIELD_BIT_SIZE..);
+        assert!(large.is_empty());
+
+        //
+        // Setting a range spanning multiple groups updates both layer caches.
+        //
+
+        large.set_range(start..end);
+        assert_eq!(large.count_ones(), end - start);
+        assert_eq!(large.layer_cache, 0b1111);
+        assert_eq!(large.full_layer_cache, 0b0110);
+        assert_eq!(
+            large.bitfield[0],
+            0b1111 << (LARGE_BIT_FIELD_GROUP_COUNT - 4)
+        );
+        assert_eq!(large.bitfield[3], 0b1111);
+
+        assert!(large.all_set_in(start..end));
+        assert!(large.all_set_in(start + 1..=end - 1));
+        assert!(!large.all_set_in(start - 1..end));
+        assert!(!large.all_set_in(start..=end));
+        assert!(large.all_set_in(5..5));
+
+        assert!(large.any_set_in(0..=start));
+        assert!(large.any_set_in(end - 1..));
+        assert!(!large.any_set_in(..start));
+        assert!(!large.any_set_in(end..));
+        assert!(!large.any_set_in(5..5));
+
+        //
+        // Clearing a range spanning multiple groups updates both layer caches.
+        //
+
+        large.clear_range(LARGE_BIT_FIELD_GROUP_COUNT + 1..end - 1);
+        assert_eq!(large.count_ones(), 4 + 1 + 1);
+        assert_eq!(large.layer_cache, 0b1011);
+        assert_eq!(large.full_layer_cache, 0);
+
+        large.clear_range(..);
+        assert!(large.is_empty());
+
+        large.set_range(..);
+        assert!(large.is_full());
+        assert_eq!(large.layer_cache, core::usize::MAX);
+        assert_eq!(large.full_layer_cache, core::usize::MAX);
+
+        large.clear_range(LARGE_BIT_FIELD_BIT_SIZE - 1..);
+        assert_eq!(
+            large.get_highest_clear_bit(),
+            Some(LARGE_BIT_FIELD_BIT_SIZE - 1)
+        );
+        assert_eq!(large.full_layer_cache, core::usize::MAX >> 1);
+    }
+
+    #[test]
+    fn validate_find_clear_run() {
+        let mut large = LargeBitField::new();
+
+        assert_eq!(large.find_clear_run(0), None);
+        assert_eq!(large.find_clear_run(1), Some(0));
+        assert_eq!(large.find_clear_run(LARGE_BIT_FIELD_BIT_SIZE), Some(0));
+        assert_eq!(large.find_clear_run(LARGE_BIT_FIELD_BIT_SIZE + 1), None);
+
+        //
+        // Runs can span group boundaries.
+        //
+
+        large.set_range(0..LARGE_BIT_FIELD_GROUP_COUNT - 3);
+        large.set_bit(LARGE_BIT_FIELD_GROUP_COUNT + 2);
+        assert_eq!(
+            large.find_clear_run(5),
+            Some(LARGE_BIT_FIELD_GROUP_COUNT - 3)
+        );
+        assert_eq!(
+            large.find_clear_run(6),
+            Some(LARGE_BIT_FIELD_GROUP_COUNT + 3)
+        );
+        assert_eq!(
+            large.find_clear_run(LARGE_BIT_FIELD_BIT_SIZE - LARGE_BIT_FIELD_GROUP_COUNT - 3),
+            Some(LARGE_BIT_FIELD_GROUP_COUNT + 3)
+        );
+
+        assert_eq!(
+            large.find_clear_run(LARGE_BIT_FIELD_BIT_SIZE - LARGE_BIT_FIELD_GROUP_COUNT - 2),
+            None
+        );
+
+        //
+        // Full groups break runs.
+        //
+
+        large.set_range(..);
+        large.clear_range(10..20);
+        large.clear_range(LARGE_BIT_FIELD_BIT_SIZE - 11..);
+        assert_eq!(large.find_clear_run(10), Some(10));
+        assert_eq!(
+            large.find_clear_run(11),
+            Some(LARGE_BIT_FIELD_BIT_SIZE - 11)
+        );
+        assert_eq!(large.find_clear_run(12), None);
+    }
+
+    #[test]
+    #[allow(clippy::clone_on_copy)]
+    fn validate_standard_traits() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |large: &LargeBitField| {
+            let mut hasher = DefaultHasher::new();
+            large.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        //
+        // Default, Clone, Copy, PartialEq and Hash.
+        //
+
+        let mut large = LargeBitField::default();
+        assert!(large.is_empty());
+
+        large.set_bit(300);
+        let copy = large;
+        let clone = copy.clone();
+        assert_eq!(large, copy);
+        assert_eq!(large, clone);
+        assert_eq!(hash(&large), hash(&clone));
+
+        large.set_bit(4);
+        assert_ne!(large, copy);
+
+        //
+        // Formatting.
+        //
+
+        assert_eq!(format!("{:?}", large), "{4, 300}");
+        assert_eq!(format!("{:?}", LargeBitField::new()), "{}");
+
+        let mut groups = [0 as usize; LARGE_BIT_FIELD_GROUP_COUNT];
+        groups[0] = 0b1011;
+        groups[1] = 0xA;
+        let large = LargeBitField::from(groups);
+
+        let binary = format!("{:#b}", large);
+        let hex = format!("{:x}", large);
+        assert_eq!(binary.len(), 2 + LARGE_BIT_FIELD_BIT_SIZE);
+        assert!(binary.starts_with("0b0"));
+        assert!(binary.ends_with(&format!(
+            "1010{:0width$b}",
+            0b1011,
+            width = LARGE_BIT_FIELD_GROUP_COUNT
+        )));
+
+        assert_eq!(hex.len(), LARGE_BIT_FIELD_BIT_SIZE / 4);
+        assert!(hex.ends_with(&format!(
+            "a{:0width$x}",
+            0b1011,
+            width = LARGE_BIT_FIELD_GROUP_COUNT / 4
+        )));
+
+        //
+        // Iterators and conversions.
+        //
+
+        let large: LargeBitField = [1, 500, LARGE_BIT_FIELD_BIT_SIZE].iter().copied().collect();
+        assert_eq!(large.count_ones(), 2);
+        assert!(large.test_bit(1).unwrap());
+        assert!(large.test_bit(500).unwrap());
+
+        let mut extended = large;
+        extended.extend(LARGE_BIT_FIELD_GROUP_COUNT..LARGE_BIT_FIELD_GROUP_COUNT * 2);
+        assert_eq!(extended.full_layer_cache, 1 << 1);
+
+        let groups: [usize; LARGE_BIT_FIELD_GROUP_COUNT] = extended.into();
+        let converted = LargeBitField::from(groups);
+        assert_eq!(converted, extended);
+        assert_eq!(converted.layer_cache, extended.layer_cache);
+        assert_eq!(converted.full_layer_cache, extended.full_layer_cache);
+    }
+
+    #[test]
+    fn validate_const_construction() {
+        const fn full_second_group() -> [usize; LARGE_BIT_FIELD_GROUP_COUNT] {
+            let mut bits = [0; LARGE_BIT_FIELD_GROUP_COUNT];
+            bits[1] = core::usize::MAX;
+            bits[2] = 0b1010;
+            bits
+        }
+
+        const EMPTY: LargeBitField = LargeBitField::new();
+        const FROM_BITS: LargeBitField = LargeBitField::from_bits(full_second_group());
+        const WITH_BITS: LargeBitField = LargeBitField::new()
+            .with_bit(LARGE_BIT_FIELD_GROUP_COUNT * 2 + 1)
+            .with_bit(LARGE_BIT_FIELD_GROUP_COUNT * 2 + 3)
+            .with_bit(LARGE_BIT_FIELD_GROUP_COUNT * 3)
+            .without_bit(LARGE_BIT_FIELD_GROUP_COUNT * 3)
+            .with_bit(LARGE_BIT_FIELD_BIT_SIZE)
+            .without_bit(LARGE_BIT_FIELD_BIT_SIZE);
+
+        static STATIC_FULL: LargeBitField =
+            LargeBitField::from_bits([core::usize::MAX; LARGE_BIT_FIELD_GROUP_COUNT]);
+
+        const FIRST_SET: bool = FROM_BITS.is_bit_set(LARGE_BIT_FIELD_GROUP_COUNT);
+        const SECOND_SET: bool = FROM_BITS.is_bit_set(0);
+        const OUT_OF_BOUNDS_SET: bool = STATIC_FULL.is_bit_set(LARGE_BIT_FIELD_BIT_SIZE);
+
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.full_layer_cache, 0);
+
+        assert_eq!(FROM_BITS.layer_cache, 0b110);
+        assert_eq!(FROM_BITS.full_layer_cache, 0b010);
+        assert_eq!(FROM_BITS.to_bits(), full_second_group());
+
+        assert_eq!(WITH_BITS.layer_cache, 0b100);
+        assert_eq!(WITH_BITS.full_layer_cache, 0);
+        assert_eq!(WITH_BITS.bitfield[2], 0b1010);
+
+        assert!(STATIC_FULL.is_full());
+        assert_eq!(STATIC_FULL.layer_cache, core::usize::MAX);
+
+        //
+        // The const helpers agree with the runtime queries.
+        //
+
+        const _: () = assert!(FIRST_SET && !SECOND_SET && !OUT_OF_BOUNDS_SET);
+        assert_eq!(
+            Some(FIRST_SET),
+            FROM_BITS.test_bit(LARGE_BIT_FIELD_GROUP_COUNT)
+        );
+        assert_eq!(Some(SECOND_SET), FROM_BITS.test_bit(0));
+        assert_eq!(STATIC_FULL.test_bit(LARGE_BIT_FIELD_BIT_SIZE), None);
+
+        //
+        // Setting the last bit of a group through the const helper marks the group full.
+        //
+
+        let mut almost_full = LargeBitField::new();
+        almost_full.set_range(0..LARGE_BIT_FIELD_GROUP_COUNT - 1);
+        let full = almost_full.with_bit(LARGE_BIT_FIELD_GROUP_COUNT - 1);
+        assert_eq!(full.full_layer_cache, 1);
+        assert_eq!(full.without_bit(0).full_layer_cache, 0);
+    }
+
+    #[test]
+    fn validate_iter_set_bits() {
+        let mut large = LargeBitField::new();
+        assert_eq!(large.iter_set_bits().next(), None);
+
+        large.set_bit(LARGE_BIT_FIELD_BIT_SIZE - 1);
+        assert_eq!(
+            large.iter_set_bits().next(),
+            Some(LARGE_BIT_FIELD_BIT_SIZE - 1)
+        );
+
+        large.set_range(..);
+        for (expected, actual) in large.iter_set_bits().enumerate() {
+            assert_eq!(expected, actual);
+        }
+
+        assert_eq!(large.iter_set_bits().count(), LARGE_BIT_FIELD_BIT_SIZE);
+    }
+
+    #[test]
+    fn validate_bitwise_operations() {
+        let fives = [core::usize::MAX / 3; LARGE_BIT_FIELD_GROUP_COUNT];
+
+        let a_s = [!(core::usize::MAX / 3); LARGE_BIT_FIELD_GROUP_COUNT];
+        let make = |field: &[usize; LARGE_BIT_FIELD_GROUP_COUNT]| {
+            let mut large = LargeBitField::new();
+            large.set_field(field);
+            large
+        };
+
+        let validate_caches = |large: &LargeBitField| {
+            for index in 0..LARGE_BIT_FIELD_GROUP_COUNT {
+                let group = large.bitfield[index];
+                assert_eq!((large.layer_cache >> index) & 1, (group != 0) as usize);
+                assert_eq!(
+                    (large.full_layer_cache >> index) & 1,
+                    (group == core::usize::MAX) as usize
+                );
+            }
+        };
+
+        let mut single_group = [0 as usize; LARGE_BIT_FIELD_GROUP_COUNT];
+        single_group[3] = core::usize::MAX;
+
+        //
+        // And
+        //
+
+        let result = make(&fives) & make(&a_s);
+        assert!(result.is_empty());
+        validate_caches(&result);
+
+        let result = make(&fives) & make(&single_group);
+        assert_eq!(result.layer_cache, 1 << 3);
+        assert_eq!(result.bitfield[3], fives[3]);
+        validate_caches(&result);
+
+        //
+        // Or
+        //
+
+        let result = make(&fives) | make(&a_s);
+        assert!(result.is_full());
+        validate_caches(&result);
+
+        let result = make(&fives) | make(&single_group);
+        assert_eq!(result.full_layer_cache, 1 << 3);
+        validate_caches(&result);
+
+        //
+        // Xor
+        //
+
+        let result = make(&fives) ^ make(&fives);
+        assert!(result.is_empty());
+        validate_caches(&result);
+
+        let result = make(&single_group) ^ make(&fives);
+        assert_eq!(result.bitfield[3], a_s[3]);
+        assert_eq!(result.bitfield[4], fives[4]);
+        validate_caches(&result);
+
+        //
+        // Not
+        //
+
+        let result = !make(&fives);
+        for index in 0..LARGE_BIT_FIELD_GROUP_COUNT {
+            assert_eq!(result.bitfield[index], a_s[index]);
+        }
+
+        validate_caches(&result);
+
+        let result = !make(&single_group);
+        assert_eq!(result.layer_cache, !(1 << 3));
+        assert_eq!(result.full_layer_cache, !(1 << 3));
+        validate_caches(&result);
+
+        //
+        // Assignment variants
+        //
+
+        let mut large = make(&fives);
+        large |= make(&single_group);
+        assert_eq!(large.bitfield[3], core::usize::MAX);
+        validate_caches(&large);
+
+        large &= &make(&single_group);
+        assert_eq!(large.layer_cache, 1 << 3);
+        validate_caches(&large);
+
+        large ^= make(&single_group);
+        assert!(large.is_empty());
+        validate_caches(&large);
+    }
+
     #[test]
     fn validate_test_group() {
         let mut large = LargeBitField::new();
@@ -5,7 +5,8 @@
 //! contents of the bitfield.
 
 use crate::cpu_features::opcodes;
-use crate::algorithms::debruijin;
+use crate::algorithms::{debruijin, popcount};
+use core::ops::{Bound, RangeBounds};
 
 /// Defines the required functionality for fast bitfields
 pub trait FastBitField {
@@ -62,6 +63,59 @@ pub trait FastBitField {
     /// `true` if empty, `false` otherwise.
     fn is_empty(&self) -> bool;
 
+    /// Determines whether or not every bit in the bitfield is set.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    fn is_full(&self) -> bool;
+
+    /// Gets the lowest clear bit.
+    ///
+    /// # Returns
+    /// The lowest clear bit index or `None` if all bits are set.
+    fn get_lowest_clear_bit(&self) -> Option<usize>;
+
+    /// Gets the highest clear bit.
+    ///
+    /// # Returns
+    /// The highest clear bit index or `None` if all bits are set.
+    fn get_highest_clear_bit(&self) -> Option<usize>;
+
+    /// Gets the number of set bits in the bit field.
+    ///
+    /// # Returns
+    /// The number of set bits.
+    fn count_ones(&self) -> usize;
+
+    /// Gets the number of set bits below a specific bit in the bit field.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to count up to (exclusive).
+    ///
+    /// # Returns
+    /// The number of set bits with an index lower than `index`. If `index` is larger than the
+    /// number of bits in the bit field, all set bits are counted.
+    fn rank(&self, index: usize) -> usize;
+
+    /// Gets the index of the n-th set bit in the bit field.
+    ///
+    /// # Arguments
+    /// n - Provides the zero based position of the set bit to find, counting from the lowest
+    /// set bit.
+    ///
+    /// # Returns
+    /// The index of the n-th set bit or `None` if fewer than `n + 1` bits are set.
+    fn select(&self, n: usize) -> Option<usize>;
+
+    /// Finds the lowest run of contiguous clear bits of a given length.
+    ///
+    /// # Arguments
+    /// length - Provides the number of contiguous clear bits to find.
+    ///
+    /// # Returns
+    /// The index of the first bit of the run or `None` if no such run exists or `length` is 0.
+    fn find_clear_run(&self, length: usize) -> Option<usize>;
+
     /// Gets the lowest set bit, guaranteed to have no branches and be in constant time, completely
     /// invariant of the state of the bit field. If no bits are set, the result is undefined.
     ///
@@ -82,6 +136,28 @@ pub trait FastBitField {
     /// The highest set bit index or `UNDEFINED` if no bits are set.
     fn get_highest_set_bit_unchecked(&self) -> usize;
 
+    /// Gets the lowest clear bit, guaranteed to have no branches and be in constant time,
+    /// completely invariant of the state of the bit field. If all bits are set, the result is
+    /// undefined.
+    ///
+    /// This function should only be used if the caller can guarantee the bitfield will always
+    /// have at least one bit clear.
+    ///
+    /// # Returns
+    /// The lowest clear bit index or `UNDEFINED` if all bits are set.
+    fn get_lowest_clear_bit_unchecked(&self) -> usize;
+
+    /// Gets the highest clear bit, guaranteed to have no branches and be in constant time,
+    /// completely invariant of the state of the bit field. If all bits are set, the result is
+    /// undefined.
+    ///
+    /// This function should only be used if the caller can guarantee the bitfield will always
+    /// have at least one bit clear.
+    ///
+    /// # Returns
+    /// The highest clear bit index or `UNDEFINED` if all bits are set.
+    fn get_highest_clear_bit_unchecked(&self) -> usize;
+
     /// Sets a bit in the bit field.
     ///
     /// # Arguments
@@ -125,6 +201,57 @@ pub use small_bitfield::SmallBitField;
 mod large_bitfield;
 pub use large_bitfield::LargeBitField;
 
+/// Defines an iterator over the indices of the set bits in a fast bitfield, from lowest to
+/// highest.
+pub struct SetBitIter<'a> {
+    /// Holds the groups of the bitfield being iterated.
+    groups: &'a [usize],
+
+    /// Holds the index of the group currently being iterated.
+    group_index: usize,
+
+    /// Holds the bits of the current group that have not been visited yet.
+    remaining: usize,
+}
+
+/// Defines functionality unique to SetBitIter.
+impl<'a> SetBitIter<'a> {
+    /// Creates a new SetBitIter.
+    ///
+    /// # Arguments
+    /// groups - Provides the groups of the bitfield to iterate, lowest bits first.
+    ///
+    /// # Returns
+    /// A SetBitIter.
+    fn new(groups: &'a [usize]) -> Self {
+        SetBitIter {
+            groups,
+            group_index: 0,
+            remaining: groups.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Defines the Iterator interface for SetBitIter.
+impl<'a> Iterator for SetBitIter<'a> {
+    type Item = usize;
+
+    /// Gets the next set bit.
+    ///
+    /// # Returns
+    /// The index of the next set bit or `None` if no set bits remain.
+    fn next(&mut self) -> Option<usize> {
+        while self.remaining == 0 {
+            self.group_index += 1;
+            self.remaining = *self.groups.get(self.group_index)?;
+        }
+
+        let bit = find_lowest_set_bit(self.remaining);
+        self.remaining &= self.remaining - 1;
+        Some((self.group_index * core::mem::size_of::<usize>() * 8) + bit)
+    }
+}
+
 /// Gets the lowest set bit of a usize value.
 ///
 /// # Arguments
@@ -154,3 +281,175 @@ fn find_highest_set_bit(value: usize) -> usize {
         debruijin::get_highest_set_bit(value)
     }
 }
+
+/// Gets the number of set bits of a usize value.
+///
+/// # Arguments
+/// value - The value to count the set bits of.
+///
+/// # Returns
+/// The number of set bits.
+fn count_set_bits(value: usize) -> usize {
+    if opcodes::population_count_exists() {
+        value.count_ones() as usize
+    } else {
+        popcount::count_ones(value)
+    }
+}
+
+/// Gets the index of the n-th set bit of a usize value.
+///
+/// # Arguments
+/// value - The value to search.
+/// n - The zero based position of the set bit to find, counting from the lowest set bit.
+///
+/// # Returns
+/// The index of the n-th set bit or `None` if fewer than `n + 1` bits are set.
+fn find_nth_set_bit(value: usize, n: usize) -> Option<usize> {
+    if n >= count_set_bits(value) {
+        return None;
+    }
+
+    //
+    // Strip off the n lowest set bits, leaving the desired bit as the lowest.
+    //
+
+    let mut value = value;
+    for _ in 0..n {
+        value &= value - 1;
+    }
+
+    Some(find_lowest_set_bit(value))
+}
+
+/// Converts a range of bit indices into start (inclusive) and end (exclusive) indices, clamped to
+/// the size of a bit field.
+///
+/// # Arguments
+/// range - The range to convert.
+/// number_of_bits - The number of bits in the bit field.
+///
+/// # Returns
+/// A tuple of the start and end indices. If the range is empty, start will be greater than or
+/// equal to end.
+fn clamp_range<R: RangeBounds<usize>>(range: R, number_of_bits: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(end) => end.saturating_add(1),
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => number_of_bits,
+    };
+
+    (start, core::cmp::min(end, number_of_bits))
+}
+
+/// Creates a usize mask with a contiguous range of bits set.
+///
+/// # Arguments
+/// start - The lowest bit to set. Must be less than the number of bits in a usize.
+/// end - The bit after the highest bit to set. Must be greater than `start` and no larger than the
+/// number of bits in a usize.
+///
+/// # Returns
+/// A mask with bits `start` through `end - 1` set.
+fn bit_range_mask(start: usize, end: usize) -> usize {
+    let length = end - start;
+    let mask = if length == core::mem::size_of::<usize>() * 8 {
+        core::usize::MAX
+    } else {
+        (1 << length) - 1
+    };
+
+    mask << start
+}
+
+/// Finds the first run of contiguous clear bits across a series of usize groups, a group at a
+/// time.
+///
+/// # Arguments
+/// groups - The groups to search, lowest bits first.
+/// length - The number of contiguous clear bits to find.
+///
+/// # Returns
+/// The index of the first bit of the run or `None` if no such run exists.
+fn find_clear_run_in_groups(groups: &[usize], length: usize) -> Option<usize> {
+    let bits_of = core::mem::size_of::<usize>() * 8;
+    let mut run_start = 0;
+    let mut run_length = 0;
+
+    if length == 0 {
+        return None;
+    }
+
+    for (group_index, group) in groups.iter().enumerate() {
+        let group_base = group_index * bits_of;
+
+        //
+        // Fully set and fully clear groups can be handled without looking at individual bits.
+        //
+
+        if *group == core::usize::MAX {
+            run_length = 0;
+            continue;
+        }
+
+        if *group == 0 {
+            if run_length == 0 {
+                run_start = group_base;
+            }
+
+            run_length += bits_of;
+            if run_length >= length {
+                return Some(run_start);
+            }
+
+            continue;
+        }
+
+        //
+        // Walk the alternating runs of clear and set bits in a mixed group.
+        //
+
+        let mut bit = 0;
+        while bit < bits_of {
+            let remaining = *group >> bit;
+            let clear_bits = if remaining == 0 {
+                bits_of - bit
+            } else {
+                find_lowest_set_bit(remaining)
+            };
+
+            if clear_bits != 0 {
+                if run_length == 0 {
+                    run_start = group_base + bit;
+                }
+
+                run_length += clear_bits;
+                if run_length >= length {
+                    return Some(run_start);
+                }
+
+                bit += clear_bits;
+                if bit >= bits_of {
+                    break;
+                }
+            }
+
+            //
+            // The shift fills the top with zeros, so the inverted value always has a set bit.
+            //
+
+            let set_bits = find_lowest_set_bit(!(*group >> bit));
+
+            run_length = 0;
+            bit += set_bits;
+        }
+    }
+
+    None
+}
@@ -1,10 +1,17 @@
-use super::{find_highest_set_bit, find_lowest_set_bit, FastBitField};
+use super::{
+    bit_range_mask, clamp_range, count_set_bits, find_clear_run_in_groups, find_highest_set_bit,
+    find_lowest_set_bit, find_nth_set_bit, FastBitField, SetBitIter,
+};
+use core::fmt;
+use core::iter::FromIterator;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, RangeBounds};
 
 /// Defines the maximum number of bits in a small bitfield.
 const SMALL_BIT_FIELD_BIT_SIZE: usize = core::mem::size_of::<usize>() * 8;
 
 /// Defines the structure and fast_bitfield interface for Small Bitfieds.
 /// A Small Bitfield is a wrapper type that holds a `usize` bitfield.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SmallBitField {
     /// Holds the bitfield state.
     bitfield: usize,
@@ -12,6 +19,113 @@ pub struct SmallBitField {
 
 /// Defines functionality unique to SmallBitField.
 impl SmallBitField {
+    /// Creates a new, empty SmallBitField. Usable in `const` and `static` items.
+    ///
+    /// # Returns
+    /// A SmallBitField.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// static EMPTY: SmallBitField = SmallBitField::new();
+    /// assert!(EMPTY.is_empty());
+    /// ```
+    pub const fn new() -> Self {
+        SmallBitField { bitfield: 0 }
+    }
+
+    /// Creates a new SmallBitField from its raw `usize` representation. Usable in `const` and
+    /// `static` items.
+    ///
+    /// # Arguments
+    /// bits - Provides the initial state of the bit field.
+    ///
+    /// # Returns
+    /// A SmallBitField.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// const READY: SmallBitField = SmallBitField::from_bits(0b1001);
+    /// assert_eq!(READY.count_ones(), 2);
+    /// ```
+    pub const fn from_bits(bits: usize) -> Self {
+        SmallBitField { bitfield: bits }
+    }
+
+    /// Gets the raw `usize` representation of the bit field.
+    ///
+    /// # Returns
+    /// The state of the bit field.
+    pub const fn to_bits(&self) -> usize {
+        self.bitfield
+    }
+
+    /// Creates a copy of the bit field with an additional bit set. Usable in `const` and `static`
+    /// items.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to set.
+    ///
+    /// # Returns
+    /// The updated SmallBitField. If the index is invalid, the bit field is unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// const MASK: SmallBitField = SmallBitField::new().with_bit(1).with_bit(4);
+    /// assert_eq!(MASK.to_bits(), 0b10010);
+    /// ```
+    pub const fn with_bit(self, index: usize) -> Self {
+        if index < SMALL_BIT_FIELD_BIT_SIZE {
+            SmallBitField {
+                bitfield: self.bitfield | (1 << index),
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Creates a copy of the bit field with a bit cleared. Usable in `const` and `static` items.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to clear.
+    ///
+    /// # Returns
+    /// The updated SmallBitField. If the index is invalid, the bit field is unchanged.
+    pub const fn without_bit(self, index: usize) -> Self {
+        if index < SMALL_BIT_FIELD_BIT_SIZE {
+            SmallBitField {
+                bitfield: self.bitfield & !(1 << index),
+            }
+        } else {
+            self
+        }
+    }
+
+    /// Gets the value of a specific bit in the bit field. Usable in `const` and `static` items.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to test.
+    ///
+    /// # Returns
+    /// `true` if the bit is set, `false` if it is cleared or the index is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::SmallBitField;
+    ///
+    /// const MASK: SmallBitField = SmallBitField::from_bits(0b100);
+    /// const IS_SET: bool = MASK.is_bit_set(2);
+    /// assert!(IS_SET);
+    /// ```
+    pub const fn is_bit_set(&self, index: usize) -> bool {
+        index < SMALL_BIT_FIELD_BIT_SIZE && (self.bitfield & (1 << index)) != 0
+    }
+
     /// Sets bits in the bit field.
     ///
     /// # Arguments
@@ -27,6 +141,322 @@ impl SmallBitField {
     pub fn clear_field(&mut self, field: usize) {
         self.bitfield &= !field;
     }
+
+    /// Determines whether every bit set in this bit field is also set in another.
+    ///
+    /// # Arguments
+    /// other - Provides the bit field to compare against.
+    ///
+    /// # Returns
+    /// `true` if this bit field is a subset of `other`, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// let mut other = SmallBitField::new();
+    /// small.set_field(0b0101);
+    /// other.set_field(0b0111);
+    ///
+    /// assert!(small.is_subset(&other));
+    /// assert!(!other.is_subset(&small));
+    /// ```
+    pub fn is_subset(&self, other: &SmallBitField) -> bool {
+        (self.bitfield & !other.bitfield) == 0
+    }
+
+    /// Determines whether this bit field and another have no set bits in common.
+    ///
+    /// # Arguments
+    /// other - Provides the bit field to compare against.
+    ///
+    /// # Returns
+    /// `true` if no bit is set in both bit fields, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// let mut other = SmallBitField::new();
+    /// small.set_field(0b0101);
+    /// other.set_field(0b1010);
+    ///
+    /// assert!(small.is_disjoint(&other));
+    ///
+    /// other.set_bit(0);
+    /// assert!(!small.is_disjoint(&other));
+    /// ```
+    pub fn is_disjoint(&self, other: &SmallBitField) -> bool {
+        (self.bitfield & other.bitfield) == 0
+    }
+
+    /// Determines whether this bit field and another have any set bits in common.
+    ///
+    /// # Arguments
+    /// other - Provides the bit field to compare against.
+    ///
+    /// # Returns
+    /// `true` if at least one bit is set in both bit fields, `false` otherwise.
+    pub fn intersects(&self, other: &SmallBitField) -> bool {
+        !self.is_disjoint(other)
+    }
+
+    /// Sets a contiguous range of bits in the bit field.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to set.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_range(2..5);
+    ///
+    /// assert_eq!(small.count_ones(), 3);
+    /// assert_eq!(small.get_lowest_set_bit(), Some(2));
+    /// assert_eq!(small.get_highest_set_bit(), Some(4));
+    /// ```
+    pub fn set_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = clamp_range(range, SMALL_BIT_FIELD_BIT_SIZE);
+        if start < end {
+            self.bitfield |= bit_range_mask(start, end);
+        }
+    }
+
+    /// Clears a contiguous range of bits in the bit field.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to clear.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    pub fn clear_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = clamp_range(range, SMALL_BIT_FIELD_BIT_SIZE);
+        if start < end {
+            self.bitfield &= !bit_range_mask(start, end);
+        }
+    }
+
+    /// Determines whether every bit in a contiguous range is set.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to test.
+    ///
+    /// # Returns
+    /// `true` if every bit in the range is set or the range is empty, `false` otherwise.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_range(2..5);
+    ///
+    /// assert!(small.all_set_in(2..5));
+    /// assert!(!small.all_set_in(1..5));
+    /// ```
+    pub fn all_set_in<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        let (start, end) = clamp_range(range, SMALL_BIT_FIELD_BIT_SIZE);
+        if start >= end {
+            return true;
+        }
+
+        let mask = bit_range_mask(start, end);
+        (self.bitfield & mask) == mask
+    }
+
+    /// Determines whether any bit in a contiguous range is set.
+    ///
+    /// # Arguments
+    /// range - Provides the range of bits to test.
+    ///
+    /// # Returns
+    /// `true` if at least one bit in the range is set, `false` otherwise.
+    ///
+    /// # Note
+    /// Bits in the range that are beyond the size of the bit field are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_bit(4);
+    ///
+    /// assert!(small.any_set_in(0..5));
+    /// assert!(!small.any_set_in(0..4));
+    /// ```
+    pub fn any_set_in<R: RangeBounds<usize>>(&self, range: R) -> bool {
+        let (start, end) = clamp_range(range, SMALL_BIT_FIELD_BIT_SIZE);
+        if start >= end {
+            return false;
+        }
+
+        (self.bitfield & bit_range_mask(start, end)) != 0
+    }
+
+    /// Gets an iterator over the indices of the set bits, from lowest to highest.
+    ///
+    /// # Returns
+    /// A SetBitIter.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_field(0b1010_0001);
+    ///
+    /// let mut iter = small.iter_set_bits();
+    /// assert_eq!(iter.next(), Some(0));
+    /// assert_eq!(iter.next(), Some(5));
+    /// assert_eq!(iter.next(), Some(7));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_set_bits(&self) -> SetBitIter<'_> {
+        SetBitIter::new(core::slice::from_ref(&self.bitfield))
+    }
+}
+
+/// Defines the default value for SmallBitField.
+impl Default for SmallBitField {
+    /// Creates a new, empty SmallBitField
+    fn default() -> Self {
+        SmallBitField::new()
+    }
+}
+
+/// Defines debug formatting for SmallBitField as the set of its set bit indices.
+impl fmt::Debug for SmallBitField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter_set_bits()).finish()
+    }
+}
+
+/// Defines binary formatting for SmallBitField.
+impl fmt::Binary for SmallBitField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Binary::fmt(&self.bitfield, f)
+    }
+}
+
+/// Defines lower case hexadecimal formatting for SmallBitField.
+impl fmt::LowerHex for SmallBitField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.bitfield, f)
+    }
+}
+
+/// Defines creating a SmallBitField from an iterator of bit indices to set.
+/// Indices beyond the size of the bit field are ignored.
+impl FromIterator<usize> for SmallBitField {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut small = SmallBitField::new();
+        small.extend(iter);
+        small
+    }
+}
+
+/// Defines setting the bit indices of an iterator in a SmallBitField.
+/// Indices beyond the size of the bit field are ignored.
+impl Extend<usize> for SmallBitField {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.set_bit(index);
+        }
+    }
+}
+
+/// Defines creating a SmallBitField from its raw `usize` representation.
+impl From<usize> for SmallBitField {
+    fn from(bitfield: usize) -> Self {
+        SmallBitField::from_bits(bitfield)
+    }
+}
+
+/// Defines converting a SmallBitField into its raw `usize` representation.
+impl From<SmallBitField> for usize {
+    fn from(small: SmallBitField) -> Self {
+        small.bitfield
+    }
+}
+
+/// Defines the bitwise and operation for SmallBitField.
+impl BitAnd for SmallBitField {
+    type Output = SmallBitField;
+
+    /// Gets the bits set in both bit fields.
+    fn bitand(mut self, rhs: SmallBitField) -> SmallBitField {
+        self &= rhs;
+        self
+    }
+}
+
+/// Defines the bitwise and assignment operation for SmallBitField.
+impl BitAndAssign for SmallBitField {
+    /// Clears every bit that is not also set in `rhs`.
+    fn bitand_assign(&mut self, rhs: SmallBitField) {
+        self.bitfield &= rhs.bitfield;
+    }
+}
+
+/// Defines the bitwise or operation for SmallBitField.
+impl BitOr for SmallBitField {
+    type Output = SmallBitField;
+
+    /// Gets the bits set in either bit field.
+    fn bitor(mut self, rhs: SmallBitField) -> SmallBitField {
+        self |= rhs;
+        self
+    }
+}
+
+/// Defines the bitwise or assignment operation for SmallBitField.
+impl BitOrAssign for SmallBitField {
+    /// Sets every bit that is set in `rhs`.
+    fn bitor_assign(&mut self, rhs: SmallBitField) {
+        self.bitfield |= rhs.bitfield;
+    }
+}
+
+/// Defines the bitwise exclusive or operation for SmallBitField.
+impl BitXor for SmallBitField {
+    type Output = SmallBitField;
+
+    /// Gets the bits set in exactly one of the bit fields.
+    fn bitxor(mut self, rhs: SmallBitField) -> SmallBitField {
+        self ^= rhs;
+        self
+    }
+}
+
+/// Defines the bitwise exclusive or assignment operation for SmallBitField.
+impl BitXorAssign for SmallBitField {
+    /// Toggles every bit that is set in `rhs`.
+    fn bitxor_assign(&mut self, rhs: SmallBitField) {
+        self.bitfield ^= rhs.bitfield;
+    }
+}
+
+/// Defines the bitwise not operation for SmallBitField.
+impl Not for SmallBitField {
+    type Output = SmallBitField;
+
+    /// Gets the complement of the bit field.
+    fn not(self) -> SmallBitField {
+        SmallBitField {
+            bitfield: !self.bitfield,
+        }
+    }
 }
 
 /// Defines the FastBitField interface for SmallBitField.
@@ -36,7 +466,7 @@ impl FastBitField for SmallBitField {
     /// # Returns
     /// A SmallBitField.
     fn new() -> Self {
-        SmallBitField { bitfield: 0 }
+        SmallBitField::new()
     }
 
     /// Gets the number of bits available in the bitfield type.
@@ -188,6 +618,176 @@ impl FastBitField for SmallBitField {
         self.bitfield == 0
     }
 
+    /// Determines whether or not every bit in the bitfield is set.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// assert!(!small.is_full());
+    ///
+    /// small.set_field(core::usize::MAX);
+    /// assert!(small.is_full());
+    /// ```
+    fn is_full(&self) -> bool {
+        self.bitfield == core::usize::MAX
+    }
+
+    /// Gets the lowest clear bit.
+    ///
+    /// # Returns
+    /// The lowest clear bit index or `None` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_field(core::usize::MAX);
+    ///
+    /// assert_eq!(small.get_lowest_clear_bit(), None);
+    ///
+    /// small.clear_bit(4);
+    /// assert_eq!(small.get_lowest_clear_bit(), Some(4));
+    ///
+    /// small.clear_bit(6);
+    /// assert_eq!(small.get_lowest_clear_bit(), Some(4));
+    /// ```
+    fn get_lowest_clear_bit(&self) -> Option<usize> {
+        if self.is_full() {
+            return None;
+        }
+
+        Some(self.get_lowest_clear_bit_unchecked())
+    }
+
+    /// Gets the highest clear bit.
+    ///
+    /// # Returns
+    /// The highest clear bit index or `None` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_field(core::usize::MAX);
+    ///
+    /// assert_eq!(small.get_highest_clear_bit(), None);
+    ///
+    /// small.clear_bit(4);
+    /// assert_eq!(small.get_highest_clear_bit(), Some(4));
+    ///
+    /// small.clear_bit(6);
+    /// assert_eq!(small.get_highest_clear_bit(), Some(6));
+    /// ```
+    fn get_highest_clear_bit(&self) -> Option<usize> {
+        if self.is_full() {
+            return None;
+        }
+
+        Some(self.get_highest_clear_bit_unchecked())
+    }
+
+    /// Gets the number of set bits in the bit field.
+    ///
+    /// # Returns
+    /// The number of set bits.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// assert_eq!(small.count_ones(), 0);
+    ///
+    /// small.set_field(0b1011);
+    /// assert_eq!(small.count_ones(), 3);
+    /// ```
+    fn count_ones(&self) -> usize {
+        count_set_bits(self.bitfield)
+    }
+
+    /// Gets the number of set bits below a specific bit in the bit field.
+    ///
+    /// # Arguments
+    /// index - Provides the bit to count up to (exclusive).
+    ///
+    /// # Returns
+    /// The number of set bits with an index lower than `index`. If `index` is larger than the
+    /// number of bits in the bit field, all set bits are counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_field(0b1011);
+    ///
+    /// assert_eq!(small.rank(0), 0);
+    /// assert_eq!(small.rank(1), 1);
+    /// assert_eq!(small.rank(3), 2);
+    /// assert_eq!(small.rank(1000), 3);
+    /// ```
+    fn rank(&self, index: usize) -> usize {
+        if index >= SMALL_BIT_FIELD_BIT_SIZE {
+            return self.count_ones();
+        }
+
+        count_set_bits(self.bitfield & ((1 << index) - 1))
+    }
+
+    /// Gets the index of the n-th set bit in the bit field.
+    ///
+    /// # Arguments
+    /// n - Provides the zero based position of the set bit to find, counting from the lowest
+    /// set bit.
+    ///
+    /// # Returns
+    /// The index of the n-th set bit or `None` if fewer than `n + 1` bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_field(0b1011);
+    ///
+    /// assert_eq!(small.select(0), Some(0));
+    /// assert_eq!(small.select(2), Some(3));
+    /// assert_eq!(small.select(3), None);
+    /// ```
+    fn select(&self, n: usize) -> Option<usize> {
+        find_nth_set_bit(self.bitfield, n)
+    }
+
+    /// Finds the lowest run of contiguous clear bits of a given length.
+    ///
+    /// # Arguments
+    /// length - Provides the number of contiguous clear bits to find.
+    ///
+    /// # Returns
+    /// The index of the first bit of the run or `None` if no such run exists or `length` is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// small.set_field(0b1001_0110);
+    ///
+    /// assert_eq!(small.find_clear_run(1), Some(0));
+    /// assert_eq!(small.find_clear_run(2), Some(5));
+    /// assert_eq!(small.find_clear_run(3), Some(8));
+    /// ```
+    fn find_clear_run(&self, length: usize) -> Option<usize> {
+        find_clear_run_in_groups(core::slice::from_ref(&self.bitfield), length)
+    }
+
     /// Gets the lowest set bit, guaranteed to have no branches and be in constant time, completely
     /// invariant of the state of the bit field. If no bits are set, the result is undefined.
     ///
@@ -240,6 +840,55 @@ impl FastBitField for SmallBitField {
         find_highest_set_bit(self.bitfield)
     }
 
+    /// Gets the lowest clear bit, guaranteed to have no branches and be in constant time,
+    /// completely invariant of the state of the bit field. If all bits are set, the result is
+    /// undefined.
+    ///
+    /// This function should only be used if the caller can guarantee the bitfield will always
+    /// have at least one bit clear.
+    ///
+    /// # Returns
+    /// The lowest clear bit index or `UNDEFINED` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let mut small = SmallBitField::new();
+    /// assert_eq!(small.get_lowest_clear_bit_unchecked(), 0);
+    ///
+    /// small.set_bit(0);
+    /// assert_eq!(small.get_lowest_clear_bit_unchecked(), 1);
+    /// ```
+    fn get_lowest_clear_bit_unchecked(&self) -> usize {
+        find_lowest_set_bit(!self.bitfield)
+    }
+
+    /// Gets the highest clear bit, guaranteed to have no branches and be in constant time,
+    /// completely invariant of the state of the bit field. If all bits are set, the result is
+    /// undefined.
+    ///
+    /// This function should only be used if the caller can guarantee the bitfield will always
+    /// have at least one bit clear.
+    ///
+    /// # Returns
+    /// The highest clear bit index or `UNDEFINED` if all bits are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::fast_bitfield::{FastBitField, SmallBitField};
+    ///
+    /// let bits_of = core::mem::size_of::<usize>() * 8;
+    /// let mut small = SmallBitField::new();
+    /// assert_eq!(small.get_highest_clear_bit_unchecked(), bits_of - 1);
+    ///
+    /// small.set_bit(bits_of - 1);
+    /// assert_eq!(small.get_highest_clear_bit_unchecked(), bits_of - 2);
+    /// ```
+    fn get_highest_clear_bit_unchecked(&self) -> usize {
+        find_highest_set_bit(!self.bitfield)
+    }
+
     /// Sets a bit in the bit field.
     ///
     /// # Arguments
@@ -460,6 +1109,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_is_full() {
+        let mut small = SmallBitField::new();
+        assert!(!small.is_full());
+
+        for i in 0..SMALL_BIT_FIELD_BIT_SIZE {
+            assert!(!small.is_full());
+            small.set_bit(i);
+        }
+
+        assert!(small.is_full());
+
+        small.clear_bit(SMALL_BIT_FIELD_BIT_SIZE / 2);
+        assert!(!small.is_full());
+    }
+
+    #[test]
+    fn validate_get_lowest_clear_bit() {
+        let mut small = SmallBitField::new();
+        small.bitfield = core::usize::MAX;
+
+        //
+        // Full should result in None for checked variant
+        //
+
+        assert_eq!(small.get_lowest_clear_bit(), None);
+
+        for i in 0..SMALL_BIT_FIELD_BIT_SIZE {
+            small.clear_bit(i);
+            assert_eq!(small.get_lowest_clear_bit(), Some(0));
+            assert_eq!(small.get_lowest_clear_bit_unchecked(), 0);
+        }
+
+        for i in 0..SMALL_BIT_FIELD_BIT_SIZE {
+            assert_eq!(small.get_lowest_clear_bit(), Some(i));
+            assert_eq!(small.get_lowest_clear_bit_unchecked(), i);
+            small.set_bit(i);
+        }
+
+        assert_eq!(small.get_lowest_clear_bit(), None);
+    }
+
+    #[test]
+    fn validate_get_highest_clear_bit() {
+        let mut small = SmallBitField::new();
+        small.bitfield = core::usize::MAX;
+
+        //
+        // Full should result in None for checked variant
+        //
+
+        assert_eq!(small.get_highest_clear_bit(), None);
+
+        for i in 0..SMALL_BIT_FIELD_BIT_SIZE {
+            small.clear_bit(i);
+            assert_eq!(small.get_highest_clear_bit(), Some(i));
+            assert_eq!(small.get_highest_clear_bit_unchecked(), i);
+        }
+
+        for i in 0..SMALL_BIT_FIELD_BIT_SIZE {
+            assert_eq!(
+                small.get_highest_clear_bit(),
+                Some(SMALL_BIT_FIELD_BIT_SIZE - 1)
+            );
+            assert_eq!(
+                small.get_highest_clear_bit_unchecked(),
+                SMALL_BIT_FIELD_BIT_SIZE - 1
+            );
+            small.set_bit(i);
+        }
+
+        assert_eq!(small.get_highest_clear_bit(), None);
+    }
+
+    #[test]
+    fn validate_count_rank_and_select() {
+        let mut small = SmallBitField::new();
+        let fives = core::usize::MAX / 3;
+
+        //
+        // Empty should have no set bits to count or select.
+        //
+
+        assert_eq!(small.count_ones(), 0);
+        assert_eq!(small.rank(SMALL_BIT_FIELD_BIT_SIZE), 0);
+        assert_eq!(small.select(0), None);
+
+        small.set_field(fives);
+        assert_eq!(small.count_ones(), SMALL_BIT_FIELD_BIT_SIZE / 2);
+
+        for i in 0..SMALL_BIT_FIELD_BIT_SIZE {
+            assert_eq!(small.rank(i), i.div_ceil(2));
+        }
+
+        for n in 0..(SMALL_BIT_FIELD_BIT_SIZE / 2) {
+            assert_eq!(small.select(n), Some(n * 2));
+            assert_eq!(small.rank(n * 2), n);
+        }
+
+        assert_eq!(small.select(SMALL_BIT_FIELD_BIT_SIZE / 2), None);
+        assert_eq!(small.rank(core::usize::MAX), SMALL_BIT_FIELD_BIT_SIZE / 2);
+
+        small.set_field(core::usize::MAX);
+        assert_eq!(small.count_ones(), SMALL_BIT_FIELD_BIT_SIZE);
+        assert_eq!(
+            small.select(SMALL_BIT_FIELD_BIT_SIZE - 1),
+            Some(SMALL_BIT_FIELD_BIT_SIZE - 1)
+        );
+    }
+
     #[test]
     fn validate_test_bit() {
         let mut small = SmallBitField::new();
@@ -575,4 +1334,232 @@ mod tests {
         small.clear_field(0);
         assert_eq!(small.bitfield, a_s);
     }
+
+    #[test]
+    fn validate_subset_and_disjoint() {
+        let mut small = SmallBitField::new();
+        let mut other = SmallBitField::new();
+        let fives = core::usize::MAX / 3;
+        let a_s = !(core::usize::MAX / 3);
+
+        //
+        // Empty bit fields are subsets of and disjoint with each other.
+        //
+
+        assert!(small.is_subset(&other));
+        assert!(small.is_disjoint(&other));
+        assert!(!small.intersects(&other));
+
+        small.set_field(fives);
+        other.set_field(a_s);
+        assert!(!small.is_subset(&other));
+        assert!(small.is_disjoint(&other));
+        assert!(!small.intersects(&other));
+
+        other.set_bit(0);
+        assert!(!small.is_subset(&other));
+        assert!(!small.is_disjoint(&other));
+        assert!(small.intersects(&other));
+
+        other.set_field(fives);
+        assert!(small.is_subset(&other));
+        assert!(!other.is_subset(&small));
+    }
+
+    #[test]
+    fn validate_range_operations() {
+        let mut small = SmallBitField::new();
+
+        //
+        // Empty and out of bounds ranges result in no change.
+        //
+
+        small.set_range(3..3);
+        small.set_range(SMALL_BIT_FIELD_BIT_SIZE..);
+        assert!(small.is_empty());
+
+        small.set_range(1..4);
+        assert_eq!(small.bitfield, 0b1110);
+
+        small.set_range(SMALL_BIT_FIELD_BIT_SIZE - 2..=SMALL_BIT_FIELD_BIT_SIZE);
+        assert_eq!(
+            small.bitfield,
+            0b1110 | (0b11 << (SMALL_BIT_FIELD_BIT_SIZE - 2))
+        );
+
+        assert!(small.all_set_in(1..4));
+        assert!(small.all_set_in(2..=3));
+        assert!(!small.all_set_in(0..4));
+        assert!(small.all_set_in(SMALL_BIT_FIELD_BIT_SIZE - 2..));
+        assert!(small.all_set_in(5..5));
+
+        assert!(small.any_set_in(0..2));
+        assert!(!small.any_set_in(4..SMALL_BIT_FIELD_BIT_SIZE - 2));
+        assert!(!small.any_set_in(5..5));
+
+        small.clear_range(..);
+        assert!(small.is_empty());
+
+        small.set_range(..);
+        assert!(small.is_full());
+
+        small.clear_range(2..=2);
+        assert_eq!(small.bitfield, !(1 << 2));
+    }
+
+    #[test]
+    fn validate_find_clear_run() {
+        let mut small = SmallBitField::new();
+
+        assert_eq!(small.find_clear_run(0), None);
+        assert_eq!(small.find_clear_run(1), Some(0));
+        assert_eq!(small.find_clear_run(SMALL_BIT_FIELD_BIT_SIZE), Some(0));
+
+        assert_eq!(small.find_clear_run(SMALL_BIT_FIELD_BIT_SIZE + 1), None);
+
+        small.set_field(0b1001_0110);
+        assert_eq!(small.find_clear_run(1), Some(0));
+        assert_eq!(small.find_clear_run(2), Some(5));
+        assert_eq!(small.find_clear_run(3), Some(8));
+        assert_eq!(small.find_clear_run(SMALL_BIT_FIELD_BIT_SIZE - 8), Some(8));
+
+        assert_eq!(small.find_clear_run(SMALL_BIT_FIELD_BIT_SIZE - 7), None);
+
+        small.set_field(core::usize::MAX);
+        assert_eq!(small.find_clear_run(1), None);
+    }
+
+    #[test]
+    #[allow(clippy::clone_on_copy)]
+    fn validate_standard_traits() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let hash = |small: &SmallBitField| {
+            let mut hasher = DefaultHasher::new();
+            small.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        //
+        // Default, Clone, Copy, PartialEq and Hash.
+        //
+
+        let mut small = SmallBitField::default();
+        assert!(small.is_empty());
+
+        small.set_bit(3);
+        let copy = small;
+        let clone = copy.clone();
+        assert_eq!(small, copy);
+        assert_eq!(small, clone);
+        assert_eq!(hash(&small), hash(&clone));
+
+        small.set_bit(4);
+        assert_ne!(small, copy);
+
+        //
+        // Formatting.
+        //
+
+        small.set_bit(SMALL_BIT_FIELD_BIT_SIZE - 1);
+        assert_eq!(
+            format!("{:?}", small),
+            format!("{{3, 4, {}}}", SMALL_BIT_FIELD_BIT_SIZE - 1)
+        );
+
+        assert_eq!(format!("{:?}", SmallBitField::new()), "{}");
+        assert_eq!(format!("{:#b}", SmallBitField::from(0b1011)), "0b1011");
+        assert_eq!(format!("{:x}", SmallBitField::from(0xBEEF)), "beef");
+
+        //
+        // Iterators and conversions.
+        //
+
+        let small: SmallBitField = [1, 5, 9, SMALL_BIT_FIELD_BIT_SIZE]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(small.bitfield, (1 << 1) | (1 << 5) | (1 << 9));
+
+        let mut extended = small;
+        extended.extend(0..2);
+        assert_eq!(usize::from(extended), 0b11 | (1 << 5) | (1 << 9));
+        assert_eq!(SmallBitField::from(0b11 | (1 << 5) | (1 << 9)), extended);
+    }
+
+    #[test]
+    fn validate_const_construction() {
+        const EMPTY: SmallBitField = SmallBitField::new();
+        const FROM_BITS: SmallBitField = SmallBitField::from_bits(0b1010);
+        const WITH_BITS: SmallBitField = SmallBitField::new()
+            .with_bit(1)
+            .with_bit(3)
+            .with_bit(5)
+            .without_bit(5)
+            .with_bit(SMALL_BIT_FIELD_BIT_SIZE)
+            .without_bit(SMALL_BIT_FIELD_BIT_SIZE);
+
+        static STATIC_MASK: SmallBitField = SmallBitField::from_bits(core::usize::MAX);
+
+        const FIRST_SET: bool = FROM_BITS.is_bit_set(1);
+        const SECOND_SET: bool = FROM_BITS.is_bit_set(2);
+        const OUT_OF_BOUNDS_SET: bool = STATIC_MASK.is_bit_set(SMALL_BIT_FIELD_BIT_SIZE);
+
+        assert!(EMPTY.is_empty());
+        assert_eq!(FROM_BITS.bitfield, 0b1010);
+        assert_eq!(WITH_BITS, FROM_BITS);
+        assert_eq!(WITH_BITS.to_bits(), 0b1010);
+        assert!(STATIC_MASK.is_full());
+
+        //
+        // The const helpers agree with the runtime queries.
+        //
+
+        const _: () = assert!(FIRST_SET && !SECOND_SET && !OUT_OF_BOUNDS_SET);
+        assert_eq!(Some(FIRST_SET), FROM_BITS.test_bit(1));
+        assert_eq!(Some(SECOND_SET), FROM_BITS.test_bit(2));
+        assert_eq!(STATIC_MASK.test_bit(SMALL_BIT_FIELD_BIT_SIZE), None);
+    }
+
+    #[test]
+    fn validate_iter_set_bits() {
+        let mut small = SmallBitField::new();
+        assert_eq!(small.iter_set_bits().next(), None);
+
+        small.set_field(core::usize::MAX);
+        for (expected, actual) in small.iter_set_bits().enumerate() {
+            assert_eq!(expected, actual);
+        }
+
+        assert_eq!(small.iter_set_bits().count(), SMALL_BIT_FIELD_BIT_SIZE);
+    }
+
+    #[test]
+    fn validate_bitwise_operations() {
+        let fives = core::usize::MAX / 3;
+        let a_s = !(core::usize::MAX / 3);
+        let make = |field: usize| {
+            let mut small = SmallBitField::new();
+            small.set_field(field);
+            small
+        };
+
+        assert_eq!((make(fives) & make(fives | 1 << 1)).bitfield, fives);
+        assert_eq!((make(fives) & make(a_s)).bitfield, 0);
+        assert_eq!((make(fives) | make(a_s)).bitfield, core::usize::MAX);
+        assert_eq!((make(fives) ^ make(fives | 1 << 1)).bitfield, 1 << 1);
+        assert_eq!((!make(fives)).bitfield, a_s);
+        assert!((!make(0)).is_full());
+
+        let mut small = make(fives);
+        small |= make(1 << 1);
+        assert_eq!(small.bitfield, fives | 1 << 1);
+
+        small &= make(0b11);
+        assert_eq!(small.bitfield, 0b11);
+
+        small ^= make(0b110);
+        assert_eq!(small.bitfield, 0b101);
+    }
 }
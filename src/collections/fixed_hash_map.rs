@@ -0,0 +1,941 @@
+//! # Fixed Hash Map
+//!
+//! `fixed_hash_map` contains a hash map with a fixed number of slots that stores its entries
+//! inline, without any allocation.
+//!
+//! The map uses open addressing with linear probing: an entry lives in the first free slot at or
+//! after the slot its hash selects (its "home" slot). A fast bitfield records which slots are
+//! occupied, so empty slots are found without touching the entries, and iteration visits only
+//! occupied slots by repeatedly taking the lowest set bit. Removing an entry shifts later entries
+//! of the same probe sequence back into the hole, so no tombstones are needed and entries only
+//! ever move closer to their home slot.
+//!
+//! # Bounded probing
+//!
+//! Every map has a maximum probe length `P`. An entry is only ever stored within `P` slots of its
+//! home slot, and an insert that cannot find room within that distance fails instead of
+//! searching further. A lookup therefore hashes the key once and examines at most `P` slots,
+//! giving a worst-case lookup time of `O(P)` hash comparisons and key comparisons, independent
+//! of `N` and of the contents of the map. The default `P` is `DEFAULT_MAX_PROBE_LENGTH`, or `N`
+//! if smaller.
+//!
+//! # Hashers
+//!
+//! The hashing algorithm is chosen through the `S: BuildHasher` parameter, defaulting to FNV-1a.
+//! Any `core::hash::BuildHasher` may be used, e.g. an identity hash for keys that are already
+//! well distributed handles.
+
+use crate::algorithms::fnv::FnvBuildHasher;
+use crate::collections::fast_bitfield::{FastBitField, LargeBitField};
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+
+/// Defines the maximum probe length used when none is given.
+pub const DEFAULT_MAX_PROBE_LENGTH: usize = 16;
+
+/// Defines an entry in a slot of a FixedHashMap.
+struct Entry<K, V> {
+    /// Holds the hash of the key, used to find the home slot and to skip key comparisons.
+    hash: usize,
+
+    /// Holds the key.
+    key: K,
+
+    /// Holds the value.
+    value: V,
+}
+
+/// Defines the structure for the Fixed Hash Map.
+///
+/// The map holds up to `N` entries. `S` builds the hashers used for keys and `B` is the fast
+/// bitfield recording occupied slots, which must hold at least `N` bits; a `SmallBitField` is
+/// sufficient for up to `sizeof(usize) * 8` slots.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::fixed_hash_map::FixedHashMap;
+///
+/// let mut devices: FixedHashMap<u32, &str, 16> = FixedHashMap::new();
+///
+/// devices.insert(7, "uart").unwrap();
+/// devices.insert(3, "spi").unwrap();
+///
+/// assert_eq!(devices.get(&7), Some(&"uart"));
+/// assert_eq!(devices.insert(3, "i2c"), Ok(Some("spi")));
+/// assert_eq!(devices.remove(&7), Some("uart"));
+/// assert_eq!(devices.len(), 1);
+/// ```
+pub struct FixedHashMap<K, V, const N: usize, S = FnvBuildHasher, B = LargeBitField>
+where
+    B: FastBitField,
+{
+    /// Holds the storage for the entries.
+    slots: [MaybeUninit<Entry<K, V>>; N],
+
+    /// Holds a bitfield describing which slots are occupied.
+    occupied: B,
+
+    /// Holds the number of entries in the map.
+    length: usize,
+
+    /// Holds the maximum distance of an entry from its home slot.
+    max_probe_length: usize,
+
+    /// Holds the builder for the hashers of the keys.
+    hash_builder: S,
+}
+
+/// Implements the default hasher construction of `FixedHashMap`.
+impl<K, V, S, B, const N: usize> FixedHashMap<K, V, N, S, B>
+where
+    S: Default,
+    B: FastBitField,
+{
+    /// Creates a new, empty FixedHashMap using the default hasher and maximum probe length.
+    ///
+    /// # Returns
+    /// A FixedHashMap.
+    ///
+    /// # Panics
+    /// Panics if `N` is larger than the number of bits in `B`.
+    pub fn new() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+/// Implements the functionality unique to `FixedHashMap`.
+impl<K, V, S, B, const N: usize> FixedHashMap<K, V, N, S, B>
+where
+    B: FastBitField,
+{
+    /// Creates a new, empty FixedHashMap using the default maximum probe length.
+    ///
+    /// # Arguments
+    /// hash_builder - Provides the builder for the hashers of the keys.
+    ///
+    /// # Returns
+    /// A FixedHashMap.
+    ///
+    /// # Panics
+    /// Panics if `N` is larger than the number of bits in `B`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_and_max_probe_length(hash_builder, DEFAULT_MAX_PROBE_LENGTH)
+    }
+
+    /// Creates a new, empty FixedHashMap.
+    ///
+    /// # Arguments
+    /// hash_builder - Provides the builder for the hashers of the keys.
+    /// max_probe_length - Provides the maximum distance of an entry from its home slot. Values
+    /// larger than `N` are reduced to `N`.
+    ///
+    /// # Returns
+    /// A FixedHashMap.
+    ///
+    /// # Panics
+    /// Panics if `N` is larger than the number of bits in `B`.
+    pub fn with_hasher_and_max_probe_length(hash_builder: S, max_probe_length: usize) -> Self {
+        assert!(N <= B::get_number_of_bits());
+
+        FixedHashMap {
+            //
+            // UNSAFE: An array of `MaybeUninit` does not require initialization.
+            //
+            slots: unsafe { MaybeUninit::<[MaybeUninit<Entry<K, V>>; N]>::uninit().assume_init() },
+            occupied: B::new(),
+            length: 0,
+            max_probe_length: core::cmp::min(max_probe_length, N),
+            hash_builder,
+        }
+    }
+
+    /// Gets the maximum number of entries the map can hold.
+    ///
+    /// Inserts may fail before the map is full if too many keys share nearby home slots.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the maximum distance of an entry from its home slot.
+    ///
+    /// # Returns
+    /// The maximum probe length.
+    pub fn max_probe_length(&self) -> usize {
+        self.max_probe_length
+    }
+
+    /// Gets the builder for the hashers of the keys.
+    ///
+    /// # Returns
+    /// The hash builder.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Gets the number of entries in the map.
+    ///
+    /// # Returns
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not the map is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.occupied.is_empty()
+    }
+
+    /// Determines whether or not every slot of the map is occupied.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    pub fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    /// Removes and drops every entry of the map.
+    pub fn clear(&mut self) {
+        while let Some(slot) = self.occupied.get_lowest_set_bit() {
+            self.occupied.clear_bit(slot);
+            self.length -= 1;
+
+            //
+            // UNSAFE: The slot was occupied and is no longer considered part of the map.
+            //
+
+            unsafe { self.slots[slot].as_mut_ptr().drop_in_place() };
+        }
+    }
+
+    /// Creates an iterator over the entries, in slot order.
+    ///
+    /// # Returns
+    /// An iterator yielding references to each key and value.
+    pub fn iter(&self) -> Iter<'_, K, V, B>
+    where
+        B: Clone,
+    {
+        Iter {
+            slots: self.slots.as_ptr() as *const Entry<K, V>,
+            remaining: self.occupied.clone(),
+            length: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates an iterator over the entries with mutable values, in slot order.
+    ///
+    /// # Returns
+    /// An iterator yielding references to each key and mutable references to each value.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V, B>
+    where
+        B: Clone,
+    {
+        IterMut {
+            slots: self.slots.as_mut_ptr() as *mut Entry<K, V>,
+            remaining: self.occupied.clone(),
+            length: self.length,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates an iterator over the keys, in slot order.
+    ///
+    /// # Returns
+    /// An iterator yielding references to each key.
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_
+    where
+        B: Clone,
+    {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Creates an iterator over the values, in slot order.
+    ///
+    /// # Returns
+    /// An iterator yielding references to each value.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_
+    where
+        B: Clone,
+    {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Gets the slot a position of a probe sequence refers to.
+    fn wrap(slot: usize) -> usize {
+        if slot >= N {
+            slot - N
+        } else {
+            slot
+        }
+    }
+
+    /// Gets the distance from one slot to another, moving forwards.
+    fn distance(from: usize, to: usize) -> usize {
+        if to >= from {
+            to - from
+        } else {
+            to + N - from
+        }
+    }
+
+    /// Gets the home slot of a hash.
+    fn home(hash: usize) -> usize {
+        hash % N
+    }
+
+    /// Gets the entry of an occupied slot.
+    ///
+    /// # Unsafe
+    /// The slot must be occupied.
+    unsafe fn entry(&self, slot: usize) -> &Entry<K, V> {
+        &*self.slots[slot].as_ptr()
+    }
+
+    /// Gets the mutable entry of an occupied slot.
+    ///
+    /// # Unsafe
+    /// The slot must be occupied.
+    unsafe fn entry_mut(&mut self, slot: usize) -> &mut Entry<K, V> {
+        &mut *self.slots[slot].as_mut_ptr()
+    }
+
+    /// Removes the entry of an occupied slot, shifting later entries of its probe sequence back.
+    ///
+    /// # Arguments
+    /// slot - Provides the slot.
+    /// on_move - Provides a callback invoked with the old and new slot of every shifted entry.
+    ///
+    /// # Unsafe
+    /// The slot must be occupied.
+    unsafe fn take<F: FnMut(usize, usize)>(&mut self, slot: usize, mut on_move: F) -> Entry<K, V> {
+        let entry = self.slots[slot].as_ptr().read();
+        self.occupied.clear_bit_unchecked(slot);
+        self.length -= 1;
+
+        //
+        // Any entry after the hole whose home slot is at or before the hole can move into it,
+        // since that brings it closer to home. Stop at the first free slot, which ends every
+        // probe sequence passing through the hole.
+        //
+
+        let mut hole = slot;
+        let mut next = Self::wrap(slot + 1);
+
+        while self.occupied.test_bit_unchecked(next) {
+            let home = Self::home(self.entry(next).hash);
+
+            if Self::distance(home, hole) < Self::distance(home, next) {
+                let moved = self.slots[next].as_ptr().read();
+                self.slots[hole] = MaybeUninit::new(moved);
+                self.occupied.set_bit_unchecked(hole);
+                self.occupied.clear_bit_unchecked(next);
+                on_move(next, hole);
+                hole = next;
+            }
+
+            next = Self::wrap(next + 1);
+        }
+
+        entry
+    }
+}
+
+/// Implements the keyed functionality of `FixedHashMap`.
+impl<K, V, S, B, const N: usize> FixedHashMap<K, V, N, S, B>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    B: FastBitField,
+{
+    /// Inserts an entry, replacing the value of an existing entry with the same key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    /// value - Provides the value.
+    ///
+    /// # Returns
+    /// `Ok` holding the replaced value, or `None` if the key is new. The key and value are handed
+    /// back if the key is new and no slot is free within the maximum probe length of its home.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        if N == 0 {
+            return Err((key, value));
+        }
+
+        let hash = self.hash(&key);
+        let home = Self::home(hash);
+
+        for distance in 0..self.max_probe_length {
+            let slot = Self::wrap(home + distance);
+
+            //
+            // UNSAFE: `slot` is less than `N`, which is no larger than the bitfield, and entries
+            //         are only read from occupied slots.
+            //
+
+            unsafe {
+                if !self.occupied.test_bit_unchecked(slot) {
+                    self.slots[slot] = MaybeUninit::new(Entry { hash, key, value });
+                    self.occupied.set_bit_unchecked(slot);
+                    self.length += 1;
+                    return Ok(None);
+                }
+
+                let entry = self.entry_mut(slot);
+                if entry.hash == hash && entry.key == key {
+                    return Ok(Some(mem::replace(&mut entry.value, value)));
+                }
+            }
+        }
+
+        Err((key, value))
+    }
+
+    /// Gets the value of a key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The value or `None` if the key is not in the map.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Gets the stored key and the value of a key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The stored key and value or `None` if the key is not in the map.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(key)?;
+
+        //
+        // UNSAFE: `find` only returns occupied slots.
+        //
+
+        let entry = unsafe { self.entry(slot) };
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Gets the mutable value of a key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The value or `None` if the key is not in the map.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(key)?;
+
+        //
+        // UNSAFE: `find` only returns occupied slots.
+        //
+
+        Some(unsafe { &mut self.entry_mut(slot).value })
+    }
+
+    /// Determines whether or not a key is in the map.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// `true` if present, `false` otherwise.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes the entry of a key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The value or `None` if the key is not in the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the entry of a key, returning the stored key as well.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The stored key and value or `None` if the key is not in the map.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slot = self.find(key)?;
+
+        //
+        // UNSAFE: `find` only returns occupied slots.
+        //
+
+        let entry = unsafe { self.take(slot, |_, _| {}) };
+        Some((entry.key, entry.value))
+    }
+
+    /// Keeps only the entries for which a predicate returns `true`.
+    ///
+    /// # Arguments
+    /// keep - Provides the predicate.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        //
+        // Removing an entry may shift a later entry into the current slot, so the slot is checked
+        // again before moving on. An entry that wrapped around the end may also shift into a slot
+        // that was already visited, so visited entries are tracked and carry their mark with them
+        // when shifted.
+        //
+
+        let mut visited = B::new();
+        let mut slot = 0;
+
+        while slot < N {
+            //
+            // UNSAFE: `slot` is less than `N`, and entries are only read from occupied slots.
+            //
+
+            unsafe {
+                if self.occupied.test_bit_unchecked(slot) && !visited.test_bit_unchecked(slot) {
+                    visited.set_bit_unchecked(slot);
+
+                    let entry = self.entry_mut(slot);
+                    if !keep(&entry.key, &mut entry.value) {
+                        visited.clear_bit_unchecked(slot);
+                        drop(self.take(slot, |from, to| {
+                            if visited.test_bit_unchecked(from) {
+                                visited.clear_bit_unchecked(from);
+                                visited.set_bit_unchecked(to);
+                            }
+                        }));
+
+                        continue;
+                    }
+                }
+            }
+
+            slot += 1;
+        }
+    }
+
+    /// Hashes a key.
+    fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        self.hash_builder.hash_one(key) as usize
+    }
+
+    /// Finds the slot holding a key.
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if N == 0 {
+            return None;
+        }
+
+        let hash = self.hash(key);
+        let home = Self::home(hash);
+
+        for distance in 0..self.max_probe_length {
+            let slot = Self::wrap(home + distance);
+
+            //
+            // UNSAFE: `slot` is less than `N`, which is no larger than the bitfield, and entries
+            //         are only read from occupied slots.
+            //
+
+            unsafe {
+                if !self.occupied.test_bit_unchecked(slot) {
+                    return None;
+                }
+
+                let entry = self.entry(slot);
+                if entry.hash == hash && entry.key.borrow() == key {
+                    return Some(slot);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Drops the entries of the map.
+impl<K, V, S, B, const N: usize> Drop for FixedHashMap<K, V, N, S, B>
+where
+    B: FastBitField,
+{
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Defines the default value for FixedHashMap.
+impl<K, V, S, B, const N: usize> Default for FixedHashMap<K, V, N, S, B>
+where
+    S: Default,
+    B: FastBitField,
+{
+    fn default() -> Self {
+        FixedHashMap::new()
+    }
+}
+
+/// Formats a FixedHashMap as the map of its entries.
+impl<K, V, S, B, const N: usize> fmt::Debug for FixedHashMap<K, V, N, S, B>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+    B: FastBitField + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V, S, B, const N: usize> IntoIterator for &'a FixedHashMap<K, V, N, S, B>
+where
+    B: FastBitField + Clone,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S, B, const N: usize> IntoIterator for &'a mut FixedHashMap<K, V, N, S, B>
+where
+    B: FastBitField + Clone,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Defines an iterator over the entries of a FixedHashMap.
+pub struct Iter<'a, K, V, B> {
+    /// Holds the first slot of the map.
+    slots: *const Entry<K, V>,
+
+    /// Holds the occupied slots that have not been visited yet.
+    remaining: B,
+
+    /// Holds the number of entries that have not been visited yet.
+    length: usize,
+
+    /// Ties the iterator to the lifetime of the map.
+    _marker: PhantomData<&'a Entry<K, V>>,
+}
+
+impl<'a, K, V, B: FastBitField> Iterator for Iter<'a, K, V, B> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = self.remaining.get_lowest_set_bit()?;
+        self.remaining.clear_bit(slot);
+        self.length -= 1;
+
+        //
+        // UNSAFE: Every slot in `remaining` was occupied when the iterator was created, and the
+        //         map cannot change while it is borrowed.
+        //
+
+        let entry = unsafe { &*self.slots.add(slot) };
+        Some((&entry.key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, K, V, B: FastBitField> ExactSizeIterator for Iter<'a, K, V, B> {}
+
+impl<'a, K, V, B: FastBitField> FusedIterator for Iter<'a, K, V, B> {}
+
+/// Defines an iterator over the entries of a FixedHashMap with mutable values.
+pub struct IterMut<'a, K, V, B> {
+    /// Holds the first slot of the map.
+    slots: *mut Entry<K, V>,
+
+    /// Holds the occupied slots that have not been visited yet.
+    remaining: B,
+
+    /// Holds the number of entries that have not been visited yet.
+    length: usize,
+
+    /// Ties the iterator to the lifetime of the mutable borrow of the map.
+    _marker: PhantomData<&'a mut Entry<K, V>>,
+}
+
+impl<'a, K, V, B: FastBitField> Iterator for IterMut<'a, K, V, B> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = self.remaining.get_lowest_set_bit()?;
+        self.remaining.clear_bit(slot);
+        self.length -= 1;
+
+        //
+        // UNSAFE: Every slot in `remaining` was occupied when the iterator was created, the map
+        //         is mutably borrowed, and each slot is visited once.
+        //
+
+        let entry = unsafe { &mut *self.slots.add(slot) };
+        Some((&entry.key, &mut entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, K, V, B: FastBitField> ExactSizeIterator for IterMut<'a, K, V, B> {}
+
+impl<'a, K, V, B: FastBitField> FusedIterator for IterMut<'a, K, V, B> {}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::fast_bitfield::SmallBitField;
+    use crate::collections::test_helpers::DropCounter;
+    use core::cell::Cell;
+    use core::hash::{BuildHasherDefault, Hasher};
+    use std::vec::Vec;
+
+    //
+    // Hashes integers to themselves so tests control the home slots.
+    //
+
+    #[derive(Default)]
+    struct IdentityHasher(u64);
+
+    impl Hasher for IdentityHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 << 8) | *byte as u64;
+            }
+        }
+
+        fn write_u32(&mut self, value: u32) {
+            self.0 = value as u64;
+        }
+
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+
+    type Identity = BuildHasherDefault<IdentityHasher>;
+
+    type SmallMap<V> = FixedHashMap<u32, V, 8, Identity, SmallBitField>;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map: FixedHashMap<&str, u32, 32> = FixedHashMap::new();
+
+        assert!(map.is_empty());
+        assert_eq!(map.insert("uart", 1), Ok(None));
+        assert_eq!(map.insert("spi", 2), Ok(None));
+        assert_eq!(map.insert("uart", 3), Ok(Some(1)));
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get("uart"), Some(&3));
+        assert_eq!(map.get_key_value("spi"), Some((&"spi", &2)));
+        assert_eq!(map.get("i2c"), None);
+
+        *map.get_mut("spi").unwrap() += 10;
+        assert_eq!(map.remove("spi"), Some(12));
+        assert_eq!(map.remove("spi"), None);
+        assert!(!map.contains_key("spi"));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn probe_length_is_bounded() {
+        let mut map: SmallMap<u32> =
+            FixedHashMap::with_hasher_and_max_probe_length(Identity::default(), 2);
+
+        //
+        // Keys 1, 9 and 17 share home slot 1. Only two fit within the probe length.
+        //
+
+        assert_eq!(map.insert(1, 1), Ok(None));
+        assert_eq!(map.insert(9, 9), Ok(None));
+        assert_eq!(map.insert(17, 17), Err((17, 17)));
+
+        //
+        // Key 2 has lost its home to key 9 but still fits in the slot after it.
+        //
+
+        assert_eq!(map.insert(2, 2), Ok(None));
+        assert_eq!(map.get(&2), Some(&2));
+        assert_eq!(map.max_probe_length(), 2);
+    }
+
+    #[test]
+    fn removal_shifts_back() {
+        let mut map: SmallMap<u32> = FixedHashMap::new();
+
+        //
+        // Slots 6, 7, 0, 1 hold keys 6, 14, 22, 1 after wrapping around the end.
+        //
+
+        for key in [6, 14, 22, 1].iter() {
+            map.insert(*key, *key).unwrap();
+        }
+
+        assert_eq!(map.remove(&6), Some(6));
+        assert_eq!(map.get(&14), Some(&14));
+        assert_eq!(map.get(&22), Some(&22));
+        assert_eq!(map.get(&1), Some(&1));
+
+        assert_eq!(map.remove(&14), Some(14));
+        assert_eq!(map.remove(&22), Some(22));
+        assert_eq!(map.get(&1), Some(&1));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn fills_every_slot() {
+        let mut map: SmallMap<u32> = FixedHashMap::new();
+
+        for key in 0..8 {
+            map.insert(key * 8, key).unwrap();
+        }
+
+        assert!(map.is_full());
+        assert_eq!(map.insert(64, 8), Err((64, 8)));
+
+        for key in 0..8 {
+            assert_eq!(map.get(&(key * 8)), Some(&key));
+        }
+
+        for key in 0..8 {
+            assert_eq!(map.remove(&(key * 8)), Some(key));
+            for other in key + 1..8 {
+                assert_eq!(map.get(&(other * 8)), Some(&other));
+            }
+        }
+
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn iteration_and_retain() {
+        let mut map: FixedHashMap<u32, u32, 64> = FixedHashMap::new();
+
+        for key in 0..20 {
+            map.insert(key, key * 10).unwrap();
+        }
+
+        for (_, value) in map.iter_mut() {
+            *value += 1;
+        }
+
+        map.retain(|key, _| key % 2 == 0);
+        assert_eq!(map.iter().len(), 10);
+
+        let mut entries: Vec<_> = map.iter().map(|(key, value)| (*key, *value)).collect();
+        entries.sort_unstable();
+        assert_eq!(
+            entries,
+            (0..20)
+                .filter(|key| key % 2 == 0)
+                .map(|key| (key, key * 10 + 1))
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(map.keys().count(), 10);
+        assert_eq!(map.values().sum::<u32>(), 910);
+    }
+
+    #[test]
+    fn retain_visits_each_entry_once() {
+        let mut map: SmallMap<u32> = FixedHashMap::new();
+
+        //
+        // Every key has home slot 7, so keys 23 and 7 wrap around to slots 0 and 1 and are
+        // visited first. Removing key 15 from slot 7 shifts both back over visited slots.
+        //
+
+        for key in [15, 23, 7].iter() {
+            map.insert(*key, 0).unwrap();
+        }
+
+        map.retain(|key, visits| {
+            *visits += 1;
+            *key != 15
+        });
+
+        assert_eq!(map.get(&23), Some(&1));
+        assert_eq!(map.get(&7), Some(&1));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn drops_entries() {
+        let drops = Cell::new(0);
+
+        {
+            let mut map: SmallMap<DropCounter> = FixedHashMap::new();
+
+            for key in 0..5 {
+                let _ = map.insert(key, DropCounter(&drops));
+            }
+
+            let _ = map.insert(0, DropCounter(&drops));
+            assert_eq!(drops.get(), 1);
+
+            drop(map.remove(&1));
+            assert_eq!(drops.get(), 2);
+        }
+
+        assert_eq!(drops.get(), 6);
+    }
+}
@@ -0,0 +1,376 @@
+//! # Fixed Hash Set
+//!
+//! `fixed_hash_set` contains a hash set with a fixed number of slots that stores its keys inline,
+//! without any allocation.
+//!
+//! The set is a `FixedHashMap` with no values, so it shares the same open addressing scheme,
+//! occupancy bitfield, pluggable hashers and bounded probe length. See `fixed_hash_map` for the
+//! worst-case lookup time.
+
+use crate::algorithms::fnv::FnvBuildHasher;
+use crate::collections::fast_bitfield::{FastBitField, LargeBitField};
+use crate::collections::fixed_hash_map::{self, FixedHashMap};
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::iter::FusedIterator;
+
+/// Defines the structure for the Fixed Hash Set.
+///
+/// The set holds up to `N` keys. `S` builds the hashers used for keys and `B` is the fast
+/// bitfield recording occupied slots, which must hold at least `N` bits.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::fixed_hash_set::FixedHashSet;
+///
+/// let mut handles: FixedHashSet<u32, 16> = FixedHashSet::new();
+///
+/// assert_eq!(handles.insert(4), Ok(true));
+/// assert_eq!(handles.insert(4), Ok(false));
+/// assert!(handles.contains(&4));
+///
+/// assert!(handles.remove(&4));
+/// assert!(handles.is_empty());
+/// ```
+pub struct FixedHashSet<K, const N: usize, S = FnvBuildHasher, B = LargeBitField>
+where
+    B: FastBitField,
+{
+    /// Holds the keys as a map without values.
+    map: FixedHashMap<K, (), N, S, B>,
+}
+
+/// Implements the default hasher construction of `FixedHashSet`.
+impl<K, S, B, const N: usize> FixedHashSet<K, N, S, B>
+where
+    S: Default,
+    B: FastBitField,
+{
+    /// Creates a new, empty FixedHashSet using the default hasher and maximum probe length.
+    ///
+    /// # Returns
+    /// A FixedHashSet.
+    ///
+    /// # Panics
+    /// Panics if `N` is larger than the number of bits in `B`.
+    pub fn new() -> Self {
+        FixedHashSet {
+            map: FixedHashMap::new(),
+        }
+    }
+}
+
+/// Implements the functionality unique to `FixedHashSet`.
+impl<K, S, B, const N: usize> FixedHashSet<K, N, S, B>
+where
+    B: FastBitField,
+{
+    /// Creates a new, empty FixedHashSet using the default maximum probe length.
+    ///
+    /// # Arguments
+    /// hash_builder - Provides the builder for the hashers of the keys.
+    ///
+    /// # Returns
+    /// A FixedHashSet.
+    ///
+    /// # Panics
+    /// Panics if `N` is larger than the number of bits in `B`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_and_max_probe_length(
+            hash_builder,
+            fixed_hash_map::DEFAULT_MAX_PROBE_LENGTH,
+        )
+    }
+
+    /// Creates a new, empty FixedHashSet.
+    ///
+    /// # Arguments
+    /// hash_builder - Provides the builder for the hashers of the keys.
+    /// max_probe_length - Provides the maximum distance of a key from its home slot. Values
+    /// larger than `N` are reduced to `N`.
+    ///
+    /// # Returns
+    /// A FixedHashSet.
+    ///
+    /// # Panics
+    /// Panics if `N` is larger than the number of bits in `B`.
+    pub fn with_hasher_and_max_probe_length(hash_builder: S, max_probe_length: usize) -> Self {
+        FixedHashSet {
+            map: FixedHashMap::with_hasher_and_max_probe_length(hash_builder, max_probe_length),
+        }
+    }
+
+    /// Gets the maximum number of keys the set can hold.
+    ///
+    /// Inserts may fail before the set is full if too many keys share nearby home slots.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the maximum distance of a key from its home slot.
+    ///
+    /// # Returns
+    /// The maximum probe length.
+    pub fn max_probe_length(&self) -> usize {
+        self.map.max_probe_length()
+    }
+
+    /// Gets the builder for the hashers of the keys.
+    ///
+    /// # Returns
+    /// The hash builder.
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    /// Gets the number of keys in the set.
+    ///
+    /// # Returns
+    /// The number of keys.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Determines whether or not the set is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Determines whether or not every slot of the set is occupied.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    pub fn is_full(&self) -> bool {
+        self.map.is_full()
+    }
+
+    /// Removes and drops every key of the set.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Creates an iterator over the keys, in slot order.
+    ///
+    /// # Returns
+    /// An iterator yielding references to each key.
+    pub fn iter(&self) -> Iter<'_, K, B>
+    where
+        B: Clone,
+    {
+        Iter {
+            entries: self.map.iter(),
+        }
+    }
+}
+
+/// Implements the keyed functionality of `FixedHashSet`.
+impl<K, S, B, const N: usize> FixedHashSet<K, N, S, B>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    B: FastBitField,
+{
+    /// Adds a key to the set.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// `Ok(true)` if the key was added, or `Ok(false)` if it was already present. The key is
+    /// handed back if no slot is free within the maximum probe length of its home.
+    pub fn insert(&mut self, key: K) -> Result<bool, K> {
+        match self.map.insert(key, ()) {
+            Ok(replaced) => Ok(replaced.is_none()),
+            Err((key, _)) => Err(key),
+        }
+    }
+
+    /// Determines whether or not a key is in the set.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// `true` if present, `false` otherwise.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Gets the stored key equal to a key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The stored key or `None` if the key is not in the set.
+    pub fn get<Q>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get_key_value(key).map(|(key, _)| key)
+    }
+
+    /// Removes a key from the set.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// `true` if the key was present, `false` otherwise.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(key).is_some()
+    }
+
+    /// Removes a key from the set, returning the stored key.
+    ///
+    /// # Arguments
+    /// key - Provides the key.
+    ///
+    /// # Returns
+    /// The stored key or `None` if the key is not in the set.
+    pub fn take<Q>(&mut self, key: &Q) -> Option<K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(key).map(|(key, _)| key)
+    }
+
+    /// Keeps only the keys for which a predicate returns `true`.
+    ///
+    /// # Arguments
+    /// keep - Provides the predicate.
+    pub fn retain<F: FnMut(&K) -> bool>(&mut self, mut keep: F) {
+        self.map.retain(|key, _| keep(key));
+    }
+}
+
+/// Defines the default value for FixedHashSet.
+impl<K, S, B, const N: usize> Default for FixedHashSet<K, N, S, B>
+where
+    S: Default,
+    B: FastBitField,
+{
+    fn default() -> Self {
+        FixedHashSet::new()
+    }
+}
+
+/// Formats a FixedHashSet as the set of its keys.
+impl<K, S, B, const N: usize> fmt::Debug for FixedHashSet<K, N, S, B>
+where
+    K: fmt::Debug,
+    B: FastBitField + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, S, B, const N: usize> IntoIterator for &'a FixedHashSet<K, N, S, B>
+where
+    B: FastBitField + Clone,
+{
+    type Item = &'a K;
+    type IntoIter = Iter<'a, K, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Defines an iterator over the keys of a FixedHashSet.
+pub struct Iter<'a, K, B> {
+    /// Holds the iterator over the entries of the underlying map.
+    entries: fixed_hash_map::Iter<'a, K, (), B>,
+}
+
+impl<'a, K, B: FastBitField> Iterator for Iter<'a, K, B> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, B: FastBitField> ExactSizeIterator for Iter<'a, K, B> {}
+
+impl<'a, K, B: FastBitField> FusedIterator for Iter<'a, K, B> {}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::fast_bitfield::SmallBitField;
+    use std::vec::Vec;
+
+    #[test]
+    fn insert_contains_remove() {
+        let mut set: FixedHashSet<&str, 8, FnvBuildHasher, SmallBitField> = FixedHashSet::new();
+
+        assert_eq!(set.insert("uart"), Ok(true));
+        assert_eq!(set.insert("spi"), Ok(true));
+        assert_eq!(set.insert("uart"), Ok(false));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.contains("spi"));
+        assert_eq!(set.get("uart"), Some(&"uart"));
+        assert!(!set.contains("i2c"));
+
+        assert!(set.remove("spi"));
+        assert!(!set.remove("spi"));
+        assert_eq!(set.take("uart"), Some("uart"));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn fills_and_rejects() {
+        let mut set: FixedHashSet<u32, 4, FnvBuildHasher, SmallBitField> = FixedHashSet::new();
+
+        for key in 0..4 {
+            assert_eq!(set.insert(key), Ok(true));
+        }
+
+        assert!(set.is_full());
+        assert_eq!(set.insert(4), Err(4));
+    }
+
+    #[test]
+    fn iteration_and_retain() {
+        let mut set: FixedHashSet<u32, 64> = FixedHashSet::new();
+
+        for key in 0..16 {
+            set.insert(key).unwrap();
+        }
+
+        set.retain(|key| key % 4 == 0);
+
+        let mut keys: Vec<_> = set.iter().copied().collect();
+        keys.sort_unstable();
+        assert_eq!(keys, [0, 4, 8, 12]);
+        assert_eq!((&set).into_iter().len(), 4);
+    }
+}
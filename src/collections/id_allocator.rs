@@ -0,0 +1,527 @@
+//! # ID Allocator
+//!
+//! `id_allocator` contains an allocator handing out small integer IDs, such as task, handle or
+//! DMA channel numbers, always returning the lowest free ID.
+//!
+//! # How does it work?
+//!
+//! Each ID is a bit in a fast bitfield, set while the ID is allocated. Finding the lowest free ID
+//! is then a single lowest-clear-bit search.
+//!
+//! When more IDs are needed than a single bitfield holds, the allocator uses `GROUPS` bitfields,
+//! one after another. A `SmallBitField` summary records which groups still have a free ID, so an
+//! allocation finds the lowest group with room and then the lowest free ID inside it, without
+//! scanning full groups. With a `LargeBitField` per group, the two layers cover `bits(usize)^3`
+//! IDs in constant time.
+//!
+//! An allocator may also be limited to fewer IDs than its groups hold. The IDs past the limit are
+//! permanently marked as allocated, so they are never handed out.
+
+use crate::collections::fast_bitfield::{FastBitField, LargeBitField, SmallBitField};
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Defines the structure for the ID Allocator.
+///
+/// The allocator holds `GROUPS` bitfields of type `B`, with `GROUPS` being at most the number of
+/// bits in a `SmallBitField`.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::fast_bitfield::SmallBitField;
+/// use raztos_util::collections::id_allocator::IdAllocator;
+///
+/// let mut channels: IdAllocator<SmallBitField> = IdAllocator::with_capacity(8);
+///
+/// assert_eq!(channels.alloc(), Some(0));
+/// assert_eq!(channels.alloc(), Some(1));
+/// assert!(channels.alloc_specific(5));
+/// assert_eq!(channels.alloc_range(3), Some(2));
+///
+/// assert!(channels.free(1));
+/// assert_eq!(channels.alloc(), Some(1));
+/// ```
+pub struct IdAllocator<B = LargeBitField, const GROUPS: usize = 1>
+where
+    B: FastBitField,
+{
+    /// Holds the allocated IDs, one bit per ID.
+    groups: [B; GROUPS],
+
+    /// Holds the groups that have at least one free ID.
+    available: SmallBitField,
+
+    /// Holds the number of IDs that can be allocated.
+    capacity: usize,
+
+    /// Holds the number of allocated IDs.
+    length: usize,
+}
+
+/// Implements the functionality unique to `IdAllocator`.
+impl<B, const GROUPS: usize> IdAllocator<B, GROUPS>
+where
+    B: FastBitField,
+{
+    /// Creates a new IdAllocator with every ID of its groups free.
+    ///
+    /// # Returns
+    /// An IdAllocator.
+    ///
+    /// # Panics
+    /// Panics if `GROUPS` is larger than the number of bits in a `SmallBitField`.
+    pub fn new() -> Self {
+        Self::with_capacity(GROUPS * B::get_number_of_bits())
+    }
+
+    /// Creates a new IdAllocator handing out the IDs below a given limit.
+    ///
+    /// # Arguments
+    /// capacity - Provides the number of IDs that can be allocated.
+    ///
+    /// # Returns
+    /// An IdAllocator.
+    ///
+    /// # Panics
+    /// Panics if `GROUPS` is larger than the number of bits in a `SmallBitField`, or if `capacity`
+    /// is larger than the number of bits in the groups.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(GROUPS <= SmallBitField::get_number_of_bits());
+        assert!(capacity <= GROUPS * B::get_number_of_bits());
+
+        let mut allocator = IdAllocator {
+            groups: core::array::from_fn(|_| B::new()),
+            available: SmallBitField::new(),
+            capacity,
+            length: 0,
+        };
+
+        for id in capacity..(GROUPS * B::get_number_of_bits()) {
+            let (group, bit) = Self::locate(id);
+            allocator.groups[group].set_bit(bit);
+        }
+
+        for group in 0..GROUPS {
+            if !allocator.groups[group].is_full() {
+                allocator.available.set_bit(group);
+            }
+        }
+
+        allocator
+    }
+
+    /// Gets the number of IDs that can be allocated.
+    ///
+    /// # Returns
+    /// The capacity.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Gets the number of allocated IDs.
+    ///
+    /// # Returns
+    /// The number of allocated IDs.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not no IDs are allocated.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Determines whether or not every ID is allocated.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    pub fn is_full(&self) -> bool {
+        self.available.is_empty()
+    }
+
+    /// Determines whether or not an ID is allocated.
+    ///
+    /// # Arguments
+    /// id - Provides the ID.
+    ///
+    /// # Returns
+    /// `true` if allocated, `false` if free or not below the capacity.
+    pub fn is_allocated(&self, id: usize) -> bool {
+        if id >= self.capacity {
+            return false;
+        }
+
+        let (group, bit) = Self::locate(id);
+        self.groups[group].test_bit(bit) == Some(true)
+    }
+
+    /// Allocates the lowest free ID.
+    ///
+    /// # Returns
+    /// The ID or `None` if every ID is allocated.
+    pub fn alloc(&mut self) -> Option<usize> {
+        let group = self.available.get_lowest_set_bit()?;
+
+        //
+        // Groups in the summary always have a clear bit.
+        //
+
+        let bit = self.groups[group].get_lowest_clear_bit_unchecked();
+        self.mark(group, bit);
+        Some(group * B::get_number_of_bits() + bit)
+    }
+
+    /// Allocates a specific ID.
+    ///
+    /// # Arguments
+    /// id - Provides the ID.
+    ///
+    /// # Returns
+    /// `true` if the ID was allocated, `false` if it was already allocated or is not below the
+    /// capacity.
+    pub fn alloc_specific(&mut self, id: usize) -> bool {
+        if id >= self.capacity {
+            return false;
+        }
+
+        let (group, bit) = Self::locate(id);
+        if self.groups[group].test_bit(bit) == Some(true) {
+            return false;
+        }
+
+        self.mark(group, bit);
+        true
+    }
+
+    /// Allocates the lowest run of contiguous free IDs.
+    ///
+    /// Runs may span groups.
+    ///
+    /// # Arguments
+    /// count - Provides the number of IDs to allocate.
+    ///
+    /// # Returns
+    /// The first ID of the run or `None` if no such run exists or `count` is 0.
+    pub fn alloc_range(&mut self, count: usize) -> Option<usize> {
+        let start = self.find_free_run(count)?;
+
+        for id in start..(start + count) {
+            let (group, bit) = Self::locate(id);
+            self.mark(group, bit);
+        }
+
+        Some(start)
+    }
+
+    /// Frees an allocated ID.
+    ///
+    /// # Arguments
+    /// id - Provides the ID.
+    ///
+    /// # Returns
+    /// `true` if the ID was freed, `false` if it was not allocated.
+    pub fn free(&mut self, id: usize) -> bool {
+        if !self.is_allocated(id) {
+            return false;
+        }
+
+        let (group, bit) = Self::locate(id);
+        self.groups[group].clear_bit(bit);
+        self.available.set_bit(group);
+        self.length -= 1;
+        true
+    }
+
+    /// Creates an iterator over the allocated IDs, from lowest to highest.
+    ///
+    /// # Returns
+    /// An iterator yielding each allocated ID.
+    pub fn iter(&self) -> Iter<'_, B>
+    where
+        B: Clone,
+    {
+        Iter {
+            groups: &self.groups,
+            group: 0,
+            remaining: self.groups.first().cloned().unwrap_or_else(B::new),
+            length: self.length,
+        }
+    }
+
+    /// Splits an ID into its group and the bit within the group.
+    ///
+    /// # Arguments
+    /// id - Provides the ID.
+    ///
+    /// # Returns
+    /// The group and bit of the ID.
+    fn locate(id: usize) -> (usize, usize) {
+        (id / B::get_number_of_bits(), id % B::get_number_of_bits())
+    }
+
+    /// Marks a free ID as allocated.
+    ///
+    /// # Arguments
+    /// group - Provides the group of the ID.
+    /// bit - Provides the bit of the ID within the group.
+    fn mark(&mut self, group: usize, bit: usize) {
+        self.groups[group].set_bit(bit);
+        if self.groups[group].is_full() {
+            self.available.clear_bit(group);
+        }
+
+        self.length += 1;
+    }
+
+    /// Finds the lowest run of contiguous free IDs.
+    ///
+    /// A run crossing into a group is made of the free IDs at the top of the groups before it and
+    /// the free IDs below the lowest allocated ID of the group. Runs inside a group are found by
+    /// the group itself.
+    ///
+    /// # Arguments
+    /// count - Provides the number of IDs to find.
+    ///
+    /// # Returns
+    /// The first ID of the run or `None` if no such run exists or `count` is 0.
+    fn find_free_run(&self, count: usize) -> Option<usize> {
+        if count == 0 {
+            return None;
+        }
+
+        let bits = B::get_number_of_bits();
+        let mut run_start = 0;
+        let mut run_length = 0;
+
+        for (index, group) in self.groups.iter().enumerate() {
+            let base = index * bits;
+            if run_length == 0 {
+                run_start = base;
+            }
+
+            let lowest = match group.get_lowest_set_bit() {
+                Some(lowest) => lowest,
+                None => {
+                    run_length += bits;
+                    if run_length >= count {
+                        return Some(run_start);
+                    }
+
+                    continue;
+                }
+            };
+
+            if run_length + lowest >= count {
+                return Some(run_start);
+            }
+
+            if let Some(start) = group.find_clear_run(count) {
+                return Some(base + start);
+            }
+
+            let highest = group.get_highest_set_bit_unchecked();
+            run_start = base + highest + 1;
+            run_length = bits - highest - 1;
+        }
+
+        None
+    }
+}
+
+/// Defines the default value for IdAllocator.
+impl<B, const GROUPS: usize> Default for IdAllocator<B, GROUPS>
+where
+    B: FastBitField,
+{
+    fn default() -> Self {
+        IdAllocator::new()
+    }
+}
+
+/// Formats an IdAllocator as the set of its allocated IDs.
+impl<B, const GROUPS: usize> fmt::Debug for IdAllocator<B, GROUPS>
+where
+    B: FastBitField + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, B, const GROUPS: usize> IntoIterator for &'a IdAllocator<B, GROUPS>
+where
+    B: FastBitField + Clone,
+{
+    type Item = usize;
+    type IntoIter = Iter<'a, B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Defines an iterator over the allocated IDs of an IdAllocator.
+pub struct Iter<'a, B> {
+    /// Holds the groups of the allocator.
+    groups: &'a [B],
+
+    /// Holds the group being visited.
+    group: usize,
+
+    /// Holds the allocated IDs of the group being visited that have not been visited yet.
+    remaining: B,
+
+    /// Holds the number of allocated IDs that have not been visited yet.
+    length: usize,
+}
+
+impl<'a, B: FastBitField + Clone> Iterator for Iter<'a, B> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        //
+        // Reserved IDs past the capacity all follow the allocated IDs, so stopping once every
+        // allocated ID has been visited skips them.
+        //
+
+        if self.length == 0 {
+            return None;
+        }
+
+        loop {
+            if let Some(bit) = self.remaining.get_lowest_set_bit() {
+                self.remaining.clear_bit(bit);
+                self.length -= 1;
+                return Some(self.group * B::get_number_of_bits() + bit);
+            }
+
+            self.group += 1;
+            self.remaining = self.groups[self.group].clone();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.length, Some(self.length))
+    }
+}
+
+impl<'a, B: FastBitField + Clone> ExactSizeIterator for Iter<'a, B> {}
+
+impl<'a, B: FastBitField + Clone> FusedIterator for Iter<'a, B> {}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    const SMALL_BITS: usize = core::mem::size_of::<usize>() * 8;
+
+    #[test]
+    fn alloc_lowest_and_free() {
+        let mut ids: IdAllocator<SmallBitField> = IdAllocator::new();
+
+        assert_eq!(ids.capacity(), SMALL_BITS);
+        for expected in 0..SMALL_BITS {
+            assert_eq!(ids.alloc(), Some(expected));
+        }
+
+        assert!(ids.is_full());
+        assert_eq!(ids.alloc(), None);
+
+        assert!(ids.free(7));
+        assert!(!ids.free(7));
+        assert!(ids.free(3));
+        assert_eq!(ids.alloc(), Some(3));
+        assert_eq!(ids.alloc(), Some(7));
+        assert_eq!(ids.len(), SMALL_BITS);
+    }
+
+    #[test]
+    fn limited_capacity() {
+        let mut ids: IdAllocator<SmallBitField> = IdAllocator::with_capacity(3);
+
+        assert_eq!(ids.alloc(), Some(0));
+        assert!(ids.alloc_specific(2));
+        assert!(!ids.alloc_specific(2));
+        assert!(!ids.alloc_specific(3));
+        assert!(!ids.is_allocated(3));
+        assert!(!ids.free(3));
+        assert_eq!(ids.alloc_range(2), None);
+        assert_eq!(ids.alloc(), Some(1));
+        assert_eq!(ids.alloc(), None);
+        assert!(ids.is_full());
+
+        assert_eq!(ids.iter().collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn multiple_groups() {
+        let mut ids: IdAllocator<SmallBitField, 3> = IdAllocator::with_capacity(2 * SMALL_BITS + 4);
+
+        assert!(ids.alloc_specific(SMALL_BITS + 1));
+        for expected in 0..SMALL_BITS {
+            assert_eq!(ids.alloc(), Some(expected));
+        }
+
+        assert_eq!(ids.alloc(), Some(SMALL_BITS));
+        assert_eq!(ids.alloc(), Some(SMALL_BITS + 2));
+
+        assert!(ids.free(5));
+        assert_eq!(ids.alloc(), Some(5));
+        assert_eq!(ids.len(), SMALL_BITS + 3);
+    }
+
+    #[test]
+    fn ranges_span_groups() {
+        let mut ids: IdAllocator<SmallBitField, 4> = IdAllocator::new();
+
+        assert_eq!(ids.alloc_range(0), None);
+        assert!(ids.alloc_specific(SMALL_BITS - 3));
+        assert!(ids.alloc_specific(SMALL_BITS + 2));
+
+        assert_eq!(ids.alloc_range(4), Some(0));
+        assert_eq!(ids.alloc_range(SMALL_BITS - 7), Some(4));
+        assert_eq!(ids.alloc_range(4), Some(SMALL_BITS - 2));
+        assert_eq!(ids.alloc_range(3), Some(SMALL_BITS + 3));
+        assert_eq!(ids.alloc_range(SMALL_BITS * 2), Some(SMALL_BITS + 6));
+
+        assert_eq!(ids.alloc_range(1), Some(SMALL_BITS * 3 + 6));
+        assert_eq!(ids.alloc_range(SMALL_BITS), None);
+        assert_eq!(ids.len(), SMALL_BITS * 3 + 7);
+    }
+
+    #[test]
+    fn large_groups() {
+        let mut ids: IdAllocator<LargeBitField, 2> = IdAllocator::new();
+        let bits = LargeBitField::get_number_of_bits();
+
+        assert_eq!(ids.alloc_range(bits - 1), Some(0));
+        assert_eq!(ids.alloc_range(2), Some(bits - 1));
+        assert_eq!(ids.alloc(), Some(bits + 1));
+        assert_eq!(ids.len(), bits + 2);
+    }
+
+    #[test]
+    fn iteration() {
+        let mut ids: IdAllocator<SmallBitField, 3> = IdAllocator::with_capacity(SMALL_BITS * 2 + 1);
+
+        for id in [2, SMALL_BITS, SMALL_BITS * 2] {
+            assert!(ids.alloc_specific(id));
+        }
+
+        let iter = ids.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<Vec<_>>(), [2, SMALL_BITS, SMALL_BITS * 2]);
+        assert_eq!((&ids).into_iter().count(), 3);
+
+        let empty: IdAllocator<SmallBitField, 0> = IdAllocator::new();
+        assert_eq!(empty.iter().next(), None);
+    }
+}
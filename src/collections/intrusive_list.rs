@@ -0,0 +1,818 @@
+//! # Intrusive List
+//!
+//! `intrusive_list` contains a doubly-linked list whose links live inside the items themselves,
+//! allowing items to be queued without any allocation.
+//!
+//! A structure that wants to be placed in a list embeds a `Link` field. An `Adapter` describes how
+//! to find that field, which also allows one structure to embed several links and be placed in
+//! several lists at once (e.g. a ready list and a timeout list). The `intrusive_adapter!` macro
+//! generates an adapter for a field.
+//!
+//! `Link` is `!Unpin`, so items embedding one must be pinned before they can be inserted, either
+//! on the stack with `core::pin::pin!` or as statics with `Pin::static_ref`. Items are inserted as
+//! `Pin<&'a T>`, so the borrow checker also guarantees every linked item outlives the list. Links
+//! use interior mutability, so items can be linked and unlinked through shared references.
+//!
+//! # Examples
+//! ```
+//! use core::pin::pin;
+//! use raztos_util::collections::intrusive_list::{Link, List};
+//! use raztos_util::intrusive_adapter;
+//!
+//! #[derive(Debug)]
+//! struct Task {
+//!     id: u32,
+//!     ready_link: Link,
+//! }
+//!
+//! intrusive_adapter!(ReadyAdapter = Task { ready_link });
+//!
+//! let first = pin!(Task { id: 1, ready_link: Link::new() });
+//! let second = pin!(Task { id: 2, ready_link: Link::new() });
+//!
+//! let mut ready: List<ReadyAdapter> = List::new();
+//! ready.push_back(first.into_ref()).unwrap();
+//! ready.push_back(second.into_ref()).unwrap();
+//!
+//! assert_eq!(ready.pop_front().map(|task| task.id), Some(1));
+//! assert_eq!(ready.pop_front().map(|task| task.id), Some(2));
+//! assert!(ready.is_empty());
+//! ```
+
+use core::cell::Cell;
+use core::fmt;
+use core::marker::{PhantomData, PhantomPinned};
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+/// Defines the link embedded in items that can be placed in an intrusive list.
+pub struct Link {
+    /// Holds a pointer to the next item in the list.
+    next: Cell<Option<NonNull<()>>>,
+
+    /// Holds a pointer to the previous item in the list.
+    prev: Cell<Option<NonNull<()>>>,
+
+    /// Holds whether or not the link is currently in a list.
+    linked: Cell<bool>,
+
+    /// Prevents items holding a link from being moved once pinned.
+    _pinned: PhantomPinned,
+}
+
+/// Implements the functionality unique to `Link`.
+impl Link {
+    /// Creates a new, unlinked Link.
+    ///
+    /// # Returns
+    /// A Link.
+    pub const fn new() -> Self {
+        Link {
+            next: Cell::new(None),
+            prev: Cell::new(None),
+            linked: Cell::new(false),
+            _pinned: PhantomPinned,
+        }
+    }
+
+    /// Determines whether or not the link is currently in a list.
+    ///
+    /// # Returns
+    /// `true` if linked, `false` otherwise.
+    pub fn is_linked(&self) -> bool {
+        self.linked.get()
+    }
+
+    /// Resets the link to the unlinked state.
+    fn reset(&self) {
+        self.next.set(None);
+        self.prev.set(None);
+        self.linked.set(false);
+    }
+}
+
+/// Defines the default value for Link.
+impl Default for Link {
+    fn default() -> Self {
+        Link::new()
+    }
+}
+
+/// Formats a Link by whether or not it is linked, since its pointers are meaningless to print.
+impl fmt::Debug for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Link")
+            .field("linked", &self.is_linked())
+            .finish()
+    }
+}
+
+/// Defines how to find the `Link` of an item for a specific list.
+///
+/// # Unsafe
+/// Implementations must always return the same `Link`, and that `Link` must be a field of the
+/// item passed in. Use the `intrusive_adapter!` macro to generate a correct implementation.
+pub unsafe trait Adapter {
+    /// The type of the items in the list.
+    type Item;
+
+    /// Gets the link of an item.
+    ///
+    /// # Arguments
+    /// item - Provides the item.
+    ///
+    /// # Returns
+    /// The link used by this adapter.
+    fn get_link(item: &Self::Item) -> &Link;
+}
+
+/// Generates an `Adapter` that places items in a list through one of their `Link` fields.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::intrusive_list::Link;
+/// use raztos_util::intrusive_adapter;
+///
+/// pub struct Task {
+///     ready_link: Link,
+///     timeout_link: Link,
+/// }
+///
+/// intrusive_adapter!(pub ReadyAdapter = Task { ready_link });
+/// intrusive_adapter!(TimeoutAdapter = Task { timeout_link });
+/// ```
+#[macro_export]
+macro_rules! intrusive_adapter {
+    ($vis:vis $name:ident = $item:ty { $field:ident }) => {
+        $vis struct $name;
+
+        unsafe impl $crate::collections::intrusive_list::Adapter for $name {
+            type Item = $item;
+
+            fn get_link(item: &$item) -> &$crate::collections::intrusive_list::Link {
+                &item.$field
+            }
+        }
+    };
+}
+
+/// Defines the structure for the Intrusive List.
+///
+/// The list borrows every item it holds for the lifetime `'a`, so items can neither move nor be
+/// dropped while linked. Dropping the list unlinks all remaining items.
+pub struct List<'a, A: Adapter> {
+    /// Holds the first item in the list.
+    head: Option<NonNull<A::Item>>,
+
+    /// Holds the last item in the list.
+    tail: Option<NonNull<A::Item>>,
+
+    /// Holds the number of items in the list.
+    length: usize,
+
+    /// Ties the list to the lifetime of its items.
+    _marker: PhantomData<(&'a A::Item, A)>,
+}
+
+/// Implements the functionality unique to `List`.
+impl<'a, A: Adapter> List<'a, A> {
+    /// Creates a new, empty List.
+    ///
+    /// # Returns
+    /// A List.
+    pub const fn new() -> Self {
+        List {
+            head: None,
+            tail: None,
+            length: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gets the number of items in the list.
+    ///
+    /// # Returns
+    /// The number of items.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not the list is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Gets the first item in the list.
+    ///
+    /// # Returns
+    /// The first item or `None` if the list is empty.
+    pub fn front(&self) -> Option<Pin<&'a A::Item>> {
+        //
+        // UNSAFE: Every pointer in the list was created from a pinned reference that lives for 'a.
+        //
+
+        self.head.map(|item| unsafe { Self::to_item(item) })
+    }
+
+    /// Gets the last item in the list.
+    ///
+    /// # Returns
+    /// The last item or `None` if the list is empty.
+    pub fn back(&self) -> Option<Pin<&'a A::Item>> {
+        //
+        // UNSAFE: Every pointer in the list was created from a pinned reference that lives for 'a.
+        //
+
+        self.tail.map(|item| unsafe { Self::to_item(item) })
+    }
+
+    /// Inserts an item at the front of the list.
+    ///
+    /// # Arguments
+    /// item - Provides the item to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the item back if it is already in a list.
+    pub fn push_front(&mut self, item: Pin<&'a A::Item>) -> Result<(), Pin<&'a A::Item>> {
+        let head = self.head;
+        self.insert_between(item, None, head)
+    }
+
+    /// Inserts an item at the back of the list.
+    ///
+    /// # Arguments
+    /// item - Provides the item to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the item back if it is already in a list.
+    pub fn push_back(&mut self, item: Pin<&'a A::Item>) -> Result<(), Pin<&'a A::Item>> {
+        let tail = self.tail;
+        self.insert_between(item, tail, None)
+    }
+
+    /// Removes the first item in the list.
+    ///
+    /// # Returns
+    /// The first item or `None` if the list is empty.
+    pub fn pop_front(&mut self) -> Option<Pin<&'a A::Item>> {
+        let head = self.head?;
+
+        //
+        // UNSAFE: head is in this list.
+        //
+
+        unsafe {
+            self.unlink(head);
+            Some(Self::to_item(head))
+        }
+    }
+
+    /// Removes the last item in the list.
+    ///
+    /// # Returns
+    /// The last item or `None` if the list is empty.
+    pub fn pop_back(&mut self) -> Option<Pin<&'a A::Item>> {
+        let tail = self.tail?;
+
+        //
+        // UNSAFE: tail is in this list.
+        //
+
+        unsafe {
+            self.unlink(tail);
+            Some(Self::to_item(tail))
+        }
+    }
+
+    /// Removes a specific item from the list in constant time.
+    ///
+    /// # Arguments
+    /// item - Provides the item to remove.
+    ///
+    /// # Returns
+    /// `true` if the item was removed, `false` if it was not linked.
+    ///
+    /// # Unsafe
+    /// The list cannot verify membership in constant time. The caller must guarantee that the
+    /// item, if linked, is linked into this list and not another list using the same adapter.
+    pub unsafe fn remove(&mut self, item: Pin<&'a A::Item>) -> bool {
+        if !A::get_link(&item).is_linked() {
+            return false;
+        }
+
+        self.unlink(Self::to_pointer(item));
+        true
+    }
+
+    /// Gets a cursor positioned at the first item in the list.
+    ///
+    /// # Returns
+    /// A CursorMut.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, 'a, A> {
+        CursorMut {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Gets a cursor positioned at the last item in the list.
+    ///
+    /// # Returns
+    /// A CursorMut.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, 'a, A> {
+        CursorMut {
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    /// Gets an iterator over the items in the list, from front to back.
+    ///
+    /// # Returns
+    /// An Iter.
+    pub fn iter(&self) -> Iter<'_, 'a, A> {
+        Iter {
+            next: self.head,
+            _list: PhantomData,
+        }
+    }
+
+    /// Converts a pinned item reference into a list pointer.
+    fn to_pointer(item: Pin<&'a A::Item>) -> NonNull<A::Item> {
+        NonNull::from(item.get_ref())
+    }
+
+    /// Converts a list pointer back into a pinned item reference.
+    ///
+    /// # Unsafe
+    /// The pointer must have been created by `to_pointer`.
+    unsafe fn to_item(item: NonNull<A::Item>) -> Pin<&'a A::Item> {
+        Pin::new_unchecked(&*item.as_ptr())
+    }
+
+    /// Gets the link of an item in the list.
+    ///
+    /// # Unsafe
+    /// The pointer must have been created by `to_pointer`.
+    unsafe fn link<'b>(item: NonNull<A::Item>) -> &'b Link
+    where
+        A::Item: 'b,
+    {
+        A::get_link(&*item.as_ptr())
+    }
+
+    /// Links an item between two neighbouring items of the list.
+    ///
+    /// # Arguments
+    /// item - Provides the item to insert.
+    /// prev - Provides the item that will precede the inserted item, or `None` for the front.
+    /// next - Provides the item that will follow the inserted item, or `None` for the back.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the item back if it is already in a list.
+    fn insert_between(
+        &mut self,
+        item: Pin<&'a A::Item>,
+        prev: Option<NonNull<A::Item>>,
+        next: Option<NonNull<A::Item>>,
+    ) -> Result<(), Pin<&'a A::Item>> {
+        let link = A::get_link(&item);
+        if link.is_linked() {
+            return Err(item);
+        }
+
+        let pointer = Self::to_pointer(item);
+        link.prev.set(prev.map(NonNull::cast));
+        link.next.set(next.map(NonNull::cast));
+        link.linked.set(true);
+
+        //
+        // UNSAFE: prev and next are neighbouring items in this list.
+        //
+
+        unsafe {
+            match prev {
+                Some(prev) => Self::link(prev).next.set(Some(pointer.cast())),
+                None => self.head = Some(pointer),
+            }
+
+            match next {
+                Some(next) => Self::link(next).prev.set(Some(pointer.cast())),
+                None => self.tail = Some(pointer),
+            }
+        }
+
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Unlinks an item from the list.
+    ///
+    /// # Arguments
+    /// item - Provides the item to unlink.
+    ///
+    /// # Unsafe
+    /// The item must be in this list.
+    unsafe fn unlink(&mut self, item: NonNull<A::Item>) {
+        let link = Self::link(item);
+        let prev = link.prev.get().map(NonNull::cast::<A::Item>);
+        let next = link.next.get().map(NonNull::cast::<A::Item>);
+
+        match prev {
+            Some(prev) => Self::link(prev).next.set(next.map(NonNull::cast)),
+            None => self.head = next,
+        }
+
+        match next {
+            Some(next) => Self::link(next).prev.set(prev.map(NonNull::cast)),
+            None => self.tail = prev,
+        }
+
+        link.reset();
+        self.length -= 1;
+    }
+}
+
+/// Defines the default value for List.
+impl<'a, A: Adapter> Default for List<'a, A> {
+    fn default() -> Self {
+        List::new()
+    }
+}
+
+/// Unlinks all remaining items when the list is dropped so they can be placed in another list.
+impl<'a, A: Adapter> Drop for List<'a, A> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/// Defines an iterator over the items of an intrusive list.
+pub struct Iter<'l, 'a, A: Adapter> {
+    /// Holds the next item to return.
+    next: Option<NonNull<A::Item>>,
+
+    /// Ties the iterator to a borrow of the list.
+    _list: PhantomData<&'l List<'a, A>>,
+}
+
+/// Defines the Iterator interface for Iter.
+impl<'l, 'a, A: Adapter> Iterator for Iter<'l, 'a, A> {
+    type Item = Pin<&'a A::Item>;
+
+    fn next(&mut self) -> Option<Pin<&'a A::Item>> {
+        let item = self.next?;
+
+        //
+        // UNSAFE: The list is borrowed, so item and its neighbours remain in the list.
+        //
+
+        unsafe {
+            self.next = List::<A>::link(item).next.get().map(NonNull::cast);
+            Some(List::<A>::to_item(item))
+        }
+    }
+}
+
+/// Defines a cursor that can walk an intrusive list and insert or remove items around its
+/// position.
+///
+/// Besides pointing at an item, the cursor can point at a "ghost" position between the back and
+/// the front of the list. Moving past either end of the list moves the cursor to the ghost
+/// position, and moving from the ghost position wraps to the other end.
+pub struct CursorMut<'l, 'a, A: Adapter> {
+    /// Holds the item the cursor points at, or `None` for the ghost position.
+    current: Option<NonNull<A::Item>>,
+
+    /// Holds the list being walked.
+    list: &'l mut List<'a, A>,
+}
+
+/// Implements the functionality unique to `CursorMut`.
+impl<'l, 'a, A: Adapter> CursorMut<'l, 'a, A> {
+    /// Gets the item the cursor points at.
+    ///
+    /// # Returns
+    /// The item or `None` if the cursor is at the ghost position.
+    pub fn current(&self) -> Option<Pin<&'a A::Item>> {
+        //
+        // UNSAFE: current is in the list.
+        //
+
+        self.current.map(|item| unsafe { List::<A>::to_item(item) })
+    }
+
+    /// Moves the cursor to the next item, or to the ghost position if at the back of the list.
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            //
+            // UNSAFE: current is in the list.
+            //
+            Some(item) => unsafe { List::<A>::link(item).next.get().map(NonNull::cast) },
+            None => self.list.head,
+        };
+    }
+
+    /// Moves the cursor to the previous item, or to the ghost position if at the front of the
+    /// list.
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            //
+            // UNSAFE: current is in the list.
+            //
+            Some(item) => unsafe { List::<A>::link(item).prev.get().map(NonNull::cast) },
+            None => self.list.tail,
+        };
+    }
+
+    /// Inserts an item before the cursor. At the ghost position, the item is inserted at the back
+    /// of the list.
+    ///
+    /// # Arguments
+    /// item - Provides the item to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the item back if it is already in a list.
+    pub fn insert_before(&mut self, item: Pin<&'a A::Item>) -> Result<(), Pin<&'a A::Item>> {
+        let prev = match self.current {
+            //
+            // UNSAFE: current is in the list.
+            //
+            Some(current) => unsafe { List::<A>::link(current).prev.get().map(NonNull::cast) },
+            None => self.list.tail,
+        };
+
+        self.list.insert_between(item, prev, self.current)
+    }
+
+    /// Inserts an item after the cursor. At the ghost position, the item is inserted at the front
+    /// of the list.
+    ///
+    /// # Arguments
+    /// item - Provides the item to insert.
+    ///
+    /// # Returns
+    /// `Ok` if inserted, or the item back if it is already in a list.
+    pub fn insert_after(&mut self, item: Pin<&'a A::Item>) -> Result<(), Pin<&'a A::Item>> {
+        let next = match self.current {
+            //
+            // UNSAFE: current is in the list.
+            //
+            Some(current) => unsafe { List::<A>::link(current).next.get().map(NonNull::cast) },
+            None => self.list.head,
+        };
+
+        self.list.insert_between(item, self.current, next)
+    }
+
+    /// Removes the item the cursor points at and moves the cursor to the next item.
+    ///
+    /// # Returns
+    /// The removed item or `None` if the cursor is at the ghost position.
+    pub fn remove_current(&mut self) -> Option<Pin<&'a A::Item>> {
+        let item = self.current?;
+
+        //
+        // UNSAFE: current is in the list.
+        //
+
+        unsafe {
+            self.current = List::<A>::link(item).next.get().map(NonNull::cast);
+            self.list.unlink(item);
+            Some(List::<A>::to_item(item))
+        }
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[derive(Debug)]
+    struct Task {
+        id: usize,
+        ready_link: Link,
+        wait_link: Link,
+    }
+
+    impl Task {
+        fn new(id: usize) -> Self {
+            Task {
+                id,
+                ready_link: Link::new(),
+                wait_link: Link::new(),
+            }
+        }
+    }
+
+    intrusive_adapter!(ReadyAdapter = Task { ready_link });
+    intrusive_adapter!(WaitAdapter = Task { wait_link });
+
+    //
+    // The tests never move a task after creating it, so pinning through a shared reference is
+    // sound here.
+    //
+
+    fn pinned(task: &Task) -> Pin<&Task> {
+        unsafe { Pin::new_unchecked(task) }
+    }
+
+    fn ids<A: Adapter<Item = Task>>(list: &List<A>) -> Vec<usize> {
+        list.iter().map(|task| task.id).collect()
+    }
+
+    #[test]
+    fn create_defaults_to_empty() {
+        let list: List<ReadyAdapter> = List::new();
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+        assert!(list.front().is_none());
+        assert!(list.back().is_none());
+    }
+
+    #[test]
+    fn push_and_pop_both_ends() {
+        let tasks = [Task::new(0), Task::new(1), Task::new(2), Task::new(3)];
+        let mut list: List<ReadyAdapter> = List::new();
+
+        list.push_back(pinned(&tasks[1])).unwrap();
+        list.push_back(pinned(&tasks[2])).unwrap();
+        list.push_front(pinned(&tasks[0])).unwrap();
+        list.push_back(pinned(&tasks[3])).unwrap();
+
+        assert_eq!(ids(&list), [0, 1, 2, 3]);
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.front().unwrap().id, 0);
+        assert_eq!(list.back().unwrap().id, 3);
+
+        assert_eq!(list.pop_back().unwrap().id, 3);
+        assert_eq!(list.pop_front().unwrap().id, 0);
+        assert!(!tasks[0].ready_link.is_linked());
+        assert!(!tasks[3].ready_link.is_linked());
+        assert_eq!(ids(&list), [1, 2]);
+
+        assert_eq!(list.pop_front().unwrap().id, 1);
+        assert_eq!(list.pop_front().unwrap().id, 2);
+        assert!(list.pop_front().is_none());
+        assert!(list.pop_back().is_none());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn linked_items_are_rejected() {
+        let task = Task::new(0);
+        let mut list: List<ReadyAdapter> = List::new();
+        let mut other: List<ReadyAdapter> = List::new();
+
+        list.push_back(pinned(&task)).unwrap();
+        assert!(list.push_back(pinned(&task)).is_err());
+        assert!(other.push_front(pinned(&task)).is_err());
+        assert_eq!(list.len(), 1);
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn items_can_be_in_lists_with_different_adapters() {
+        let tasks = [Task::new(0), Task::new(1), Task::new(2)];
+        let mut ready: List<ReadyAdapter> = List::new();
+        let mut waiting: List<WaitAdapter> = List::new();
+
+        for task in tasks.iter() {
+            ready.push_back(pinned(task)).unwrap();
+            waiting.push_front(pinned(task)).unwrap();
+        }
+
+        assert_eq!(ids(&ready), [0, 1, 2]);
+        assert_eq!(ids(&waiting), [2, 1, 0]);
+
+        ready.pop_front();
+        assert!(!tasks[0].ready_link.is_linked());
+        assert!(tasks[0].wait_link.is_linked());
+        assert_eq!(ids(&waiting), [2, 1, 0]);
+    }
+
+    #[test]
+    fn remove_unlinks_arbitrary_items() {
+        let tasks = [Task::new(0), Task::new(1), Task::new(2), Task::new(3)];
+        let mut list: List<ReadyAdapter> = List::new();
+
+        for task in tasks.iter() {
+            list.push_back(pinned(task)).unwrap();
+        }
+
+        unsafe {
+            assert!(list.remove(pinned(&tasks[2])));
+            assert!(!list.remove(pinned(&tasks[2])));
+            assert_eq!(ids(&list), [0, 1, 3]);
+
+            assert!(list.remove(pinned(&tasks[0])));
+            assert!(list.remove(pinned(&tasks[3])));
+            assert_eq!(ids(&list), [1]);
+            assert_eq!(list.front().unwrap().id, 1);
+            assert_eq!(list.back().unwrap().id, 1);
+
+            assert!(list.remove(pinned(&tasks[1])));
+        }
+
+        assert!(list.is_empty());
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn cursor_walks_and_edits() {
+        let tasks = [
+            Task::new(0),
+            Task::new(1),
+            Task::new(2),
+            Task::new(3),
+            Task::new(4),
+        ];
+
+        let mut list: List<ReadyAdapter> = List::new();
+        list.push_back(pinned(&tasks[1])).unwrap();
+        list.push_back(pinned(&tasks[3])).unwrap();
+
+        {
+            let mut cursor = list.cursor_front_mut();
+            assert_eq!(cursor.current().unwrap().id, 1);
+
+            cursor.insert_before(pinned(&tasks[0])).unwrap();
+            cursor.insert_after(pinned(&tasks[2])).unwrap();
+            assert!(cursor.insert_after(pinned(&tasks[2])).is_err());
+
+            //
+            // Walk off the back to the ghost position and wrap around.
+            //
+
+            cursor.move_next();
+            cursor.move_next();
+            assert_eq!(cursor.current().unwrap().id, 3);
+            cursor.move_next();
+            assert!(cursor.current().is_none());
+
+            cursor.insert_before(pinned(&tasks[4])).unwrap();
+            cursor.move_next();
+            assert_eq!(cursor.current().unwrap().id, 0);
+            cursor.move_prev();
+            assert!(cursor.current().is_none());
+            cursor.move_prev();
+            assert_eq!(cursor.current().unwrap().id, 4);
+        }
+
+        assert_eq!(ids(&list), [0, 1, 2, 3, 4]);
+
+        {
+            //
+            // Remove every odd task while walking the list.
+            //
+
+            let mut cursor = list.cursor_front_mut();
+            while let Some(task) = cursor.current() {
+                if (task.id % 2) == 1 {
+                    assert_eq!(cursor.remove_current().unwrap().id, task.id);
+                } else {
+                    cursor.move_next();
+                }
+            }
+
+            assert!(cursor.remove_current().is_none());
+        }
+
+        assert_eq!(ids(&list), [0, 2, 4]);
+        assert_eq!(list.len(), 3);
+
+        {
+            let mut cursor = list.cursor_back_mut();
+            assert_eq!(cursor.remove_current().unwrap().id, 4);
+            assert!(cursor.current().is_none());
+        }
+
+        assert_eq!(list.back().unwrap().id, 2);
+    }
+
+    #[test]
+    fn dropping_list_unlinks_items() {
+        let tasks = [Task::new(0), Task::new(1)];
+
+        {
+            let mut list: List<ReadyAdapter> = List::new();
+            list.push_back(pinned(&tasks[0])).unwrap();
+            list.push_back(pinned(&tasks[1])).unwrap();
+        }
+
+        assert!(!tasks[0].ready_link.is_linked());
+        assert!(!tasks[1].ready_link.is_linked());
+
+        let mut list: List<ReadyAdapter> = List::new();
+        list.push_back(pinned(&tasks[1])).unwrap();
+        assert_eq!(ids(&list), [1]);
+    }
+}
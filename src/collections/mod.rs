@@ -3,4 +3,78 @@
 //! `collections` contains common structures used to hold collections of items.
 //!
 
-pub mod fast_bitfield;
\ No newline at end of file
+pub mod array_string;
+
+pub mod array_vec;
+
+pub mod fast_bitfield;
+
+pub mod fixed_hash_map;
+
+pub mod fixed_hash_set;
+
+pub mod id_allocator;
+
+pub mod intrusive_list;
+
+#[cfg(target_has_atomic = "ptr")]
+pub mod mpmc_queue;
+
+pub mod priority_queue;
+
+pub mod ring_buffer;
+
+pub mod spsc_queue;
+
+pub mod timer_wheel;
+
+use core::ops::{Bound, RangeBounds};
+
+/// Converts a range of positions into start (inclusive) and end (exclusive) positions within a
+/// collection.
+///
+/// # Arguments
+/// range - The range to convert.
+/// length - The number of elements in the collection.
+///
+/// # Returns
+/// A tuple of the start and end positions.
+///
+/// # Panics
+/// Panics if the range is decreasing or extends past `length`.
+fn resolve_range<R: RangeBounds<usize>>(range: R, length: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start.checked_add(1).expect("range start overflowed"),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(end) => end.checked_add(1).expect("range end overflowed"),
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => length,
+    };
+
+    assert!(start <= end, "range start is greater than range end");
+    assert!(end <= length, "range end is out of bounds");
+
+    (start, end)
+}
+
+//
+// Test Helpers
+//
+
+#[cfg(test)]
+mod test_helpers {
+    use core::cell::Cell;
+
+    /// Counts how many times values holding it are dropped.
+    pub struct DropCounter<'a>(pub &'a Cell<usize>);
+
+    impl<'a> Drop for DropCounter<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! # Multi-Producer Multi-Consumer Queue
+//!
+//! `mpmc_queue` contains a fixed capacity, lock-free queue that any number of producers and
+//! consumers may use at the same time, such as for distributing work across cores.
+//!
+//! Each slot holds a sequence number describing which lap of the queue it is ready for. A producer
+//! claims the slot at the enqueue position by advancing the position with a compare-and-swap, then
+//! writes its element and publishes it by bumping the sequence. Consumers do the same from the
+//! dequeue position. Producers and consumers therefore only contend with their own kind, and never
+//! with each other, except when the queue is full or empty.
+//!
+//! # Targets without compare-and-swap
+//!
+//! The queue requires a pointer sized compare-and-swap, so this module is only compiled for
+//! targets where `target_has_atomic = "ptr"`. On cores without one, such as the Cortex-M0, it is
+//! not available at all rather than silently falling back to a lock. Use the `spsc_queue` for a
+//! single producer and consumer on those targets, or guard a `RingBuffer` with a critical section.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Defines a slot of a MpmcQueue.
+struct Slot<T> {
+    /// Holds the sequence number of the slot, relative to the start of the current lap.
+    sequence: AtomicUsize,
+
+    /// Holds the element, when the sequence says one is present.
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Implements the functionality unique to `Slot`.
+impl<T> Slot<T> {
+    /// Defines an empty slot ready for the first lap. Only used to initialize the slot array,
+    /// where each repetition is a fresh slot.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Slot<T> = Slot {
+        sequence: AtomicUsize::new(0),
+        value: UnsafeCell::new(MaybeUninit::uninit()),
+    };
+}
+
+/// Defines the structure for the Multi-Producer Multi-Consumer Queue.
+///
+/// The queue holds up to `N` elements of type `T`, where `N` must be a power of two no smaller
+/// than 2.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::mpmc_queue::MpmcQueue;
+///
+/// let queue: MpmcQueue<u32, 4> = MpmcQueue::new();
+///
+/// queue.try_push(1).unwrap();
+/// queue.try_push(2).unwrap();
+///
+/// assert_eq!(queue.try_pop(), Some(1));
+/// assert_eq!(queue.try_pop(), Some(2));
+/// assert_eq!(queue.try_pop(), None);
+/// ```
+pub struct MpmcQueue<T, const N: usize> {
+    /// Holds the slots of the queue.
+    slots: [Slot<T>; N],
+
+    /// Holds the position of the next element to add.
+    enqueue_position: AtomicUsize,
+
+    /// Holds the position of the next element to remove.
+    dequeue_position: AtomicUsize,
+}
+
+//
+// UNSAFE: A slot is only ever accessed by the single producer or consumer that claimed it, so
+//         sharing the queue is safe whenever `T` can be sent.
+//
+
+unsafe impl<T: Send, const N: usize> Sync for MpmcQueue<T, N> {}
+
+/// Implements the functionality unique to `MpmcQueue`.
+impl<T, const N: usize> MpmcQueue<T, N> {
+    /// Creates a new, empty MpmcQueue.
+    ///
+    /// # Returns
+    /// A MpmcQueue.
+    ///
+    /// # Panics
+    /// Panics if `N` is not a power of two, or is smaller than 2.
+    pub const fn new() -> Self {
+        assert!(
+            N >= 2 && N.is_power_of_two(),
+            "MpmcQueue capacity must be a power of two no smaller than 2"
+        );
+
+        MpmcQueue {
+            slots: [Slot::EMPTY; N],
+            enqueue_position: AtomicUsize::new(0),
+            dequeue_position: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gets the maximum number of elements the queue can hold.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the number of elements in the queue.
+    ///
+    /// # Returns
+    /// An approximation of the number of elements, which may be stale as soon as it is returned.
+    pub fn len(&self) -> usize {
+        let dequeue_position = self.dequeue_position.load(Ordering::Relaxed);
+        let enqueue_position = self.enqueue_position.load(Ordering::Relaxed);
+        let length = enqueue_position.wrapping_sub(dequeue_position) as isize;
+
+        core::cmp::min(core::cmp::max(length, 0) as usize, N)
+    }
+
+    /// Determines whether or not the queue is empty.
+    ///
+    /// # Returns
+    /// `true` if empty at the time of the call, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds an element to the back of the queue.
+    ///
+    /// # Arguments
+    /// item - Provides the element to add.
+    ///
+    /// # Returns
+    /// `Ok` if added, or the element back if the queue is full.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let mut position = self.enqueue_position.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[position & (N - 1)];
+            let lap = position & !(N - 1);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let difference = sequence.wrapping_sub(lap) as isize;
+
+            if difference == 0 {
+                //
+                // The slot is free for this lap, try to claim it.
+                //
+
+                match self.enqueue_position.compare_exchange_weak(
+                    position,
+                    position.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        //
+                        // UNSAFE: The slot was claimed above, so no one else accesses it until
+                        //         the sequence is published.
+                        //
+
+                        unsafe { (*slot.value.get()).as_mut_ptr().write(item) };
+                        slot.sequence.store(lap.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => position = current,
+                }
+            } else if difference < 0 {
+                //
+                // The slot still holds an element from the previous lap, so the queue is full.
+                //
+
+                return Err(item);
+            } else {
+                //
+                // Another producer claimed the slot, catch up with the enqueue position.
+                //
+
+                position = self.enqueue_position.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Removes the element at the front of the queue.
+    ///
+    /// # Returns
+    /// The first element or `None` if the queue is empty.
+    pub fn try_pop(&self) -> Option<T> {
+        let mut position = self.dequeue_position.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[position & (N - 1)];
+            let lap = position & !(N - 1);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let difference = sequence.wrapping_sub(lap.wrapping_add(1)) as isize;
+
+            if difference == 0 {
+                //
+                // The slot holds an element for this lap, try to claim it.
+                //
+
+                match self.dequeue_position.compare_exchange_weak(
+                    position,
+                    position.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        //
+                        // UNSAFE: The slot was claimed above and its element was published
+                        //         before the sequence was.
+                        //
+
+                        let item = unsafe { (*slot.value.get()).as_ptr().read() };
+                        slot.sequence.store(lap.wrapping_add(N), Ordering::Release);
+                        return Some(item);
+                    }
+                    Err(current) => position = current,
+                }
+            } else if difference < 0 {
+                //
+                // The slot has not been filled for this lap yet, so the queue is empty.
+                //
+
+                return None;
+            } else {
+                //
+                // Another consumer claimed the slot, catch up with the dequeue position.
+                //
+
+                position = self.dequeue_position.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Drops the elements remaining in the queue.
+impl<T, const N: usize> Drop for MpmcQueue<T, N> {
+    fn drop(&mut self) {
+        while self.try_pop().is_some() {}
+    }
+}
+
+/// Defines the default value for MpmcQueue.
+impl<T, const N: usize> Default for MpmcQueue<T, N> {
+    fn default() -> Self {
+        MpmcQueue::new()
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::test_helpers::DropCounter;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn push_pop_laps() {
+        let queue: MpmcQueue<u32, 4> = MpmcQueue::new();
+
+        for lap in 0..10 {
+            assert!(queue.is_empty());
+            assert_eq!(queue.try_pop(), None);
+
+            for value in 0..4 {
+                queue.try_push(lap * 4 + value).unwrap();
+            }
+
+            assert_eq!(queue.len(), 4);
+            assert_eq!(queue.try_push(99), Err(99));
+
+            assert_eq!(queue.try_pop(), Some(lap * 4));
+            queue.try_push(98).unwrap();
+
+            for value in 1..4 {
+                assert_eq!(queue.try_pop(), Some(lap * 4 + value));
+            }
+
+            assert_eq!(queue.try_pop(), Some(98));
+        }
+    }
+
+    #[test]
+    fn positions_wrap() {
+        let queue: MpmcQueue<u32, 2> = MpmcQueue::new();
+        let start = usize::MAX - 2;
+
+        queue.enqueue_position.store(start, Ordering::Relaxed);
+        queue.dequeue_position.store(start, Ordering::Relaxed);
+        for offset in 0..2 {
+            let position = start.wrapping_add(offset);
+            queue.slots[position & 1]
+                .sequence
+                .store(position & !1, Ordering::Relaxed);
+        }
+
+        for value in 0..8 {
+            queue.try_push(value).unwrap();
+            queue.try_push(value + 100).unwrap();
+            assert_eq!(queue.try_push(0), Err(0));
+            assert_eq!(queue.try_pop(), Some(value));
+            assert_eq!(queue.try_pop(), Some(value + 100));
+            assert_eq!(queue.try_pop(), None);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_non_power_of_two() {
+        let _queue: MpmcQueue<u32, 3> = MpmcQueue::new();
+    }
+
+    #[test]
+    fn drops_remaining() {
+        let drops = Cell::new(0);
+
+        {
+            let queue: MpmcQueue<DropCounter, 4> = MpmcQueue::new();
+
+            for _ in 0..3 {
+                let _ = queue.try_push(DropCounter(&drops));
+            }
+
+            drop(queue.try_pop());
+            assert_eq!(drops.get(), 1);
+        }
+
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn threads() {
+        const PRODUCERS: usize = 4;
+        const CONSUMERS: usize = 4;
+        const COUNT: usize = 5_000;
+
+        let queue: MpmcQueue<usize, 8> = MpmcQueue::new();
+        let received = AtomicUsize::new(0);
+
+        let results = std::thread::scope(|scope| {
+            for producer in 0..PRODUCERS {
+                let queue = &queue;
+                scope.spawn(move || {
+                    for value in 0..COUNT {
+                        let mut item = producer * COUNT + value;
+                        while let Err(returned) = queue.try_push(item) {
+                            item = returned;
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+
+            let consumers: Vec<_> = (0..CONSUMERS)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut values = Vec::new();
+                        while received.load(Ordering::Relaxed) < PRODUCERS * COUNT {
+                            match queue.try_pop() {
+                                Some(value) => {
+                                    values.push(value);
+                                    received.fetch_add(1, Ordering::Relaxed);
+                                }
+                                None => std::thread::yield_now(),
+                            }
+                        }
+                        values
+                    })
+                })
+                .collect();
+
+            consumers
+                .into_iter()
+                .map(|consumer| consumer.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        //
+        // Every value arrives exactly once, and each producer's values arrive in order at any
+        // single consumer.
+        //
+
+        let mut all: Vec<usize> = results.iter().flatten().copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..PRODUCERS * COUNT).collect::<Vec<_>>());
+
+        for values in results.iter() {
+            for producer in 0..PRODUCERS {
+                let range = producer * COUNT..(producer + 1) * COUNT;
+                let own: Vec<_> = values
+                    .iter()
+                    .filter(|value| range.contains(value))
+                    .collect();
+                assert!(own.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+        }
+    }
+}
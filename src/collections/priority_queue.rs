@@ -0,0 +1,469 @@
+//! # Bitmap Priority Queue
+//!
+//! `priority_queue` contains a constant time priority queue built on top of the fast bitfields.
+//!
+//! Items are stored in a fixed size pool of nodes. Each priority level holds a FIFO of the nodes
+//! pushed at that level, linked together by index, and a fast bitfield tracks which levels
+//! currently hold any items. Finding the highest or lowest non-empty level is therefore a single
+//! `get_highest_set_bit`/`get_lowest_set_bit` call, making every operation constant time
+//! regardless of the number of levels or items in the queue.
+
+use crate::collections::fast_bitfield::{FastBitField, LargeBitField};
+
+/// Defines the index used to mark the end of a FIFO or the free list.
+const NIL: usize = core::usize::MAX;
+
+/// Defines a handle to an item in a BitmapPriorityQueue, used to remove it before it is popped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PriorityQueueHandle {
+    /// Holds the index of the node holding the item.
+    index: usize,
+
+    /// Holds the generation of the node when the item was pushed. Guards against using a handle
+    /// after its item has left the queue and the node has been reused.
+    generation: usize,
+}
+
+/// Defines a node in the pool of a BitmapPriorityQueue.
+struct Node<T> {
+    /// Holds the item, or `None` if the node is free.
+    item: Option<T>,
+
+    /// Holds the priority level the node is queued at.
+    priority: usize,
+
+    /// Holds the index of the next node in the FIFO or free list.
+    next: usize,
+
+    /// Holds the index of the previous node in the FIFO.
+    prev: usize,
+
+    /// Holds the number of times the node has been freed.
+    generation: usize,
+}
+
+/// Defines the head and tail of the FIFO for a single priority level.
+#[derive(Clone, Copy)]
+struct Level {
+    /// Holds the index of the oldest node in the level.
+    head: usize,
+
+    /// Holds the index of the newest node in the level.
+    tail: usize,
+}
+
+/// Defines the structure for the Bitmap Priority Queue.
+///
+/// The queue holds up to `CAPACITY` items across `LEVELS` priority levels, with level
+/// `LEVELS - 1` being the highest priority. Items at the same level are kept in FIFO order.
+/// `B` is the fast bitfield used to track non-empty levels and must hold at least `LEVELS` bits;
+/// a `SmallBitField` is sufficient for up to `sizeof(usize) * 8` levels.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::priority_queue::BitmapPriorityQueue;
+///
+/// let mut queue: BitmapPriorityQueue<&str, 32, 8> = BitmapPriorityQueue::new();
+///
+/// queue.push(3, "first").unwrap();
+/// queue.push(10, "urgent").unwrap();
+/// queue.push(3, "second").unwrap();
+///
+/// assert_eq!(queue.pop_highest(), Some("urgent"));
+/// assert_eq!(queue.pop_highest(), Some("first"));
+/// assert_eq!(queue.pop_highest(), Some("second"));
+/// assert_eq!(queue.pop_highest(), None);
+/// ```
+pub struct BitmapPriorityQueue<T, const LEVELS: usize, const CAPACITY: usize, B = LargeBitField>
+where
+    B: FastBitField,
+{
+    /// Holds a bitfield describing which levels currently have any items.
+    ready_levels: B,
+
+    /// Holds the FIFO for each priority level.
+    levels: [Level; LEVELS],
+
+    /// Holds the pool of nodes.
+    nodes: [Node<T>; CAPACITY],
+
+    /// Holds the index of the first free node.
+    free_head: usize,
+
+    /// Holds the number of items in the queue.
+    length: usize,
+}
+
+/// Implements the functionality unique to `BitmapPriorityQueue`.
+impl<T, B, const LEVELS: usize, const CAPACITY: usize> BitmapPriorityQueue<T, LEVELS, CAPACITY, B>
+where
+    B: FastBitField,
+{
+    /// Creates a new, empty BitmapPriorityQueue.
+    ///
+    /// # Returns
+    /// A BitmapPriorityQueue.
+    ///
+    /// # Panics
+    /// Panics if `LEVELS` is larger than the number of bits in `B`.
+    pub fn new() -> Self {
+        assert!(LEVELS <= B::get_number_of_bits());
+
+        let mut index = 0;
+        let nodes = [(); CAPACITY].map(|_| {
+            index += 1;
+            Node {
+                item: None,
+                priority: 0,
+                next: if index < CAPACITY { index } else { NIL },
+                prev: NIL,
+                generation: 0,
+            }
+        });
+
+        BitmapPriorityQueue {
+            ready_levels: B::new(),
+            levels: [Level {
+                head: NIL,
+                tail: NIL,
+            }; LEVELS],
+            nodes,
+            free_head: if CAPACITY > 0 { 0 } else { NIL },
+            length: 0,
+        }
+    }
+
+    /// Gets the number of items in the queue.
+    ///
+    /// # Returns
+    /// The number of items.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not the queue is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.ready_levels.is_empty()
+    }
+
+    /// Determines whether or not the queue is full.
+    ///
+    /// # Returns
+    /// `true` if no more items can be pushed, `false` otherwise.
+    pub fn is_full(&self) -> bool {
+        self.free_head == NIL
+    }
+
+    /// Gets the highest priority level that currently holds any items.
+    ///
+    /// # Returns
+    /// The highest non-empty priority level or `None` if the queue is empty.
+    pub fn highest_priority(&self) -> Option<usize> {
+        self.ready_levels.get_highest_set_bit()
+    }
+
+    /// Gets the lowest priority level that currently holds any items.
+    ///
+    /// # Returns
+    /// The lowest non-empty priority level or `None` if the queue is empty.
+    pub fn lowest_priority(&self) -> Option<usize> {
+        self.ready_levels.get_lowest_set_bit()
+    }
+
+    /// Pushes an item to the back of the FIFO for a priority level.
+    ///
+    /// # Arguments
+    /// priority - Provides the priority level of the item. Must be less than `LEVELS`.
+    /// item - Provides the item to push.
+    ///
+    /// # Returns
+    /// A handle that can be used to remove the item, or the item back if the queue is full or the
+    /// priority is invalid.
+    pub fn push(&mut self, priority: usize, item: T) -> Result<PriorityQueueHandle, T> {
+        if priority >= LEVELS || self.free_head == NIL {
+            return Err(item);
+        }
+
+        let index = self.free_head;
+        let tail = self.levels[priority].tail;
+
+        let node = &mut self.nodes[index];
+        self.free_head = node.next;
+        node.item = Some(item);
+        node.priority = priority;
+        node.next = NIL;
+        node.prev = tail;
+        let generation = node.generation;
+
+        if tail == NIL {
+            self.levels[priority].head = index;
+        } else {
+            self.nodes[tail].next = index;
+        }
+
+        self.levels[priority].tail = index;
+        self.ready_levels.set_bit(priority);
+        self.length += 1;
+
+        Ok(PriorityQueueHandle { index, generation })
+    }
+
+    /// Pops the oldest item from the highest non-empty priority level.
+    ///
+    /// # Returns
+    /// The item or `None` if the queue is empty.
+    pub fn pop_highest(&mut self) -> Option<T> {
+        let priority = self.highest_priority()?;
+        let head = self.levels[priority].head;
+        self.unlink(head)
+    }
+
+    /// Pops the oldest item from the lowest non-empty priority level.
+    ///
+    /// # Returns
+    /// The item or `None` if the queue is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::priority_queue::BitmapPriorityQueue;
+    ///
+    /// let mut queue: BitmapPriorityQueue<u32, 8, 4> = BitmapPriorityQueue::new();
+    /// queue.push(5, 50).unwrap();
+    /// queue.push(1, 10).unwrap();
+    ///
+    /// assert_eq!(queue.pop_lowest(), Some(10));
+    /// assert_eq!(queue.pop_lowest(), Some(50));
+    /// ```
+    pub fn pop_lowest(&mut self) -> Option<T> {
+        let priority = self.lowest_priority()?;
+        let head = self.levels[priority].head;
+        self.unlink(head)
+    }
+
+    /// Gets the item that `pop_highest` would return, without removing it.
+    ///
+    /// # Returns
+    /// A reference to the item or `None` if the queue is empty.
+    pub fn peek(&self) -> Option<&T> {
+        let priority = self.highest_priority()?;
+        self.nodes[self.levels[priority].head].item.as_ref()
+    }
+
+    /// Gets the item that `pop_lowest` would return, without removing it.
+    ///
+    /// # Returns
+    /// A reference to the item or `None` if the queue is empty.
+    pub fn peek_lowest(&self) -> Option<&T> {
+        let priority = self.lowest_priority()?;
+        self.nodes[self.levels[priority].head].item.as_ref()
+    }
+
+    /// Removes a specific item from the queue, regardless of its position.
+    ///
+    /// # Arguments
+    /// handle - Provides the handle returned when the item was pushed.
+    ///
+    /// # Returns
+    /// The item or `None` if the item has already left the queue.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::priority_queue::BitmapPriorityQueue;
+    ///
+    /// let mut queue: BitmapPriorityQueue<u32, 8, 4> = BitmapPriorityQueue::new();
+    /// queue.push(2, 20).unwrap();
+    /// let handle = queue.push(2, 21).unwrap();
+    /// queue.push(2, 22).unwrap();
+    ///
+    /// assert_eq!(queue.remove(handle), Some(21));
+    /// assert_eq!(queue.remove(handle), None);
+    /// assert_eq!(queue.pop_highest(), Some(20));
+    /// assert_eq!(queue.pop_highest(), Some(22));
+    /// ```
+    pub fn remove(&mut self, handle: PriorityQueueHandle) -> Option<T> {
+        let node = self.nodes.get(handle.index)?;
+        if node.generation != handle.generation || node.item.is_none() {
+            return None;
+        }
+
+        self.unlink(handle.index)
+    }
+
+    /// Unlinks a queued node from its level and returns it to the free list.
+    ///
+    /// # Arguments
+    /// index - Provides the index of a queued node.
+    ///
+    /// # Returns
+    /// The item held by the node.
+    fn unlink(&mut self, index: usize) -> Option<T> {
+        let node = &mut self.nodes[index];
+        let item = node.item.take();
+        let priority = node.priority;
+        let next = node.next;
+        let prev = node.prev;
+
+        node.generation = node.generation.wrapping_add(1);
+        node.next = self.free_head;
+        node.prev = NIL;
+        self.free_head = index;
+
+        if prev == NIL {
+            self.levels[priority].head = next;
+        } else {
+            self.nodes[prev].next = next;
+        }
+
+        if next == NIL {
+            self.levels[priority].tail = prev;
+        } else {
+            self.nodes[next].prev = prev;
+        }
+
+        if self.levels[priority].head == NIL {
+            self.ready_levels.clear_bit(priority);
+        }
+
+        self.length -= 1;
+        item
+    }
+}
+
+/// Defines the default value for BitmapPriorityQueue.
+impl<T, B, const LEVELS: usize, const CAPACITY: usize> Default
+    for BitmapPriorityQueue<T, LEVELS, CAPACITY, B>
+where
+    B: FastBitField,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::fast_bitfield::SmallBitField;
+
+    #[test]
+    fn create_defaults_to_empty() {
+        let queue: BitmapPriorityQueue<u32, 16, 4> = BitmapPriorityQueue::new();
+        assert!(queue.is_empty());
+        assert!(!queue.is_full());
+        assert_eq!(queue.len(), 0);
+        assert_eq!(queue.highest_priority(), None);
+        assert_eq!(queue.peek(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_levels_for_bitfield_panics() {
+        let _queue: BitmapPriorityQueue<u32, 1000, 4, SmallBitField> = BitmapPriorityQueue::new();
+    }
+
+    #[test]
+    fn push_rejects_invalid_priority_and_full_queue() {
+        let mut queue: BitmapPriorityQueue<u32, 4, 2, SmallBitField> = BitmapPriorityQueue::new();
+
+        assert_eq!(queue.push(4, 1), Err(1));
+
+        assert!(queue.push(0, 1).is_ok());
+        assert!(queue.push(3, 2).is_ok());
+        assert!(queue.is_full());
+        assert_eq!(queue.push(1, 3), Err(3));
+        assert_eq!(queue.len(), 2);
+
+        //
+        // Popping frees a node for reuse.
+        //
+
+        assert_eq!(queue.pop_highest(), Some(2));
+        assert!(queue.push(1, 3).is_ok());
+    }
+
+    #[test]
+    fn zero_capacity_queue_is_always_full() {
+        let mut queue: BitmapPriorityQueue<u32, 4, 0, SmallBitField> = BitmapPriorityQueue::new();
+        assert!(queue.is_full());
+        assert_eq!(queue.push(0, 1), Err(1));
+        assert_eq!(queue.pop_lowest(), None);
+    }
+
+    #[test]
+    fn pops_follow_priority_then_fifo_order() {
+        let mut queue: BitmapPriorityQueue<(usize, usize), 200, 32> = BitmapPriorityQueue::new();
+        let priorities = [7, 150, 0, 7, 199, 150, 0, 64];
+
+        for (order, priority) in priorities.iter().enumerate() {
+            queue.push(*priority, (*priority, order)).unwrap();
+        }
+
+        assert_eq!(queue.highest_priority(), Some(199));
+        assert_eq!(queue.lowest_priority(), Some(0));
+        assert_eq!(queue.peek(), Some(&(199, 4)));
+        assert_eq!(queue.peek_lowest(), Some(&(0, 2)));
+
+        assert_eq!(queue.pop_highest(), Some((199, 4)));
+        assert_eq!(queue.pop_highest(), Some((150, 1)));
+        assert_eq!(queue.pop_lowest(), Some((0, 2)));
+        assert_eq!(queue.pop_lowest(), Some((0, 6)));
+        assert_eq!(queue.pop_highest(), Some((150, 5)));
+        assert_eq!(queue.pop_highest(), Some((64, 7)));
+        assert_eq!(queue.pop_highest(), Some((7, 0)));
+        assert_eq!(queue.pop_lowest(), Some((7, 3)));
+        assert_eq!(queue.pop_highest(), None);
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn remove_unlinks_from_any_position() {
+        let mut queue: BitmapPriorityQueue<usize, 8, 8, SmallBitField> = BitmapPriorityQueue::new();
+
+        let first = queue.push(5, 0).unwrap();
+        let middle = queue.push(5, 1).unwrap();
+        let last = queue.push(5, 2).unwrap();
+        let only = queue.push(2, 3).unwrap();
+
+        assert_eq!(queue.remove(middle), Some(1));
+        assert_eq!(queue.remove(last), Some(2));
+        assert_eq!(queue.remove(first), Some(0));
+        assert_eq!(queue.highest_priority(), Some(2));
+
+        assert_eq!(queue.remove(only), Some(3));
+        assert!(queue.is_empty());
+
+        //
+        // Stale handles do not affect items pushed into reused nodes.
+        //
+
+        queue.push(5, 4).unwrap();
+        assert_eq!(queue.remove(first), None);
+        assert_eq!(queue.remove(only), None);
+        assert_eq!(queue.pop_highest(), Some(4));
+    }
+
+    #[test]
+    fn dropping_queue_drops_items() {
+        use std::rc::Rc;
+
+        let item = Rc::new(0);
+        {
+            let mut queue: BitmapPriorityQueue<Rc<u32>, 4, 4, SmallBitField> =
+                BitmapPriorityQueue::new();
+
+            queue.push(1, item.clone()).unwrap();
+            queue.push(2, item.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&item), 3);
+        }
+
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+}
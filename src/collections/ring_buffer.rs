@@ -0,0 +1,861 @@
+//! # Ring Buffer
+//!
+//! `ring_buffer` contains a fixed capacity double-ended ring buffer that never allocates.
+//!
+//! Elements are stored in place inside the buffer, so a `RingBuffer` can live in a `static` or on
+//! the stack. When full, the buffer either rejects new elements or, in overwrite mode, discards
+//! the element at the opposite end to make room. This makes it suitable for UART and log buffers
+//! where the newest data matters more than the oldest.
+//!
+//! The stored elements occupy at most two contiguous regions of the backing storage, which are
+//! exposed through `as_slices` for zero-copy access (e.g. handing a region to a DMA engine).
+//! Likewise, `free_slice_mut` exposes the contiguous free region after the back of the buffer for
+//! a DMA engine to receive into, and `commit` appends the elements it wrote. `write_slice` and
+//! `read_slice` copy whole slices in and out with at most two memory copies.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::slice;
+
+/// Defines the structure for the Ring Buffer.
+///
+/// The buffer holds up to `N` elements of type `T`.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::ring_buffer::RingBuffer;
+///
+/// let mut buffer: RingBuffer<u8, 4> = RingBuffer::new();
+///
+/// assert_eq!(buffer.write_slice(b"hello"), 4);
+/// assert!(buffer.is_full());
+///
+/// let mut out = [0; 2];
+/// assert_eq!(buffer.read_slice(&mut out), 2);
+/// assert_eq!(&out, b"he");
+///
+/// buffer.push_back(b'!').unwrap();
+/// assert_eq!(buffer.as_slices(), (&b"ll"[..], &b"!"[..]));
+/// ```
+pub struct RingBuffer<T, const N: usize> {
+    /// Holds the storage for the elements.
+    buffer: [MaybeUninit<T>; N],
+
+    /// Holds the index of the first element.
+    head: usize,
+
+    /// Holds the number of elements in the buffer.
+    length: usize,
+
+    /// Holds whether or not pushing to a full buffer overwrites the element at the opposite end.
+    overwrite: bool,
+}
+
+/// Implements the functionality unique to `RingBuffer`.
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Creates a new, empty RingBuffer that rejects elements when full.
+    ///
+    /// # Returns
+    /// A RingBuffer.
+    pub const fn new() -> Self {
+        RingBuffer {
+            //
+            // UNSAFE: An array of `MaybeUninit` does not require initialization.
+            //
+            buffer: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+            head: 0,
+            length: 0,
+            overwrite: false,
+        }
+    }
+
+    /// Creates a new, empty RingBuffer that overwrites elements when full.
+    ///
+    /// # Returns
+    /// A RingBuffer in overwrite mode.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::ring_buffer::RingBuffer;
+    ///
+    /// let mut buffer: RingBuffer<u32, 2> = RingBuffer::with_overwrite();
+    ///
+    /// buffer.push_back(1).unwrap();
+    /// buffer.push_back(2).unwrap();
+    /// buffer.push_back(3).unwrap();
+    ///
+    /// assert_eq!(buffer.pop_front(), Some(2));
+    /// assert_eq!(buffer.pop_front(), Some(3));
+    /// ```
+    pub const fn with_overwrite() -> Self {
+        let mut buffer = Self::new();
+        buffer.overwrite = true;
+        buffer
+    }
+
+    /// Sets whether or not pushing to a full buffer overwrites the element at the opposite end.
+    ///
+    /// # Arguments
+    /// overwrite - Provides whether or not to overwrite.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    /// Determines whether or not the buffer is in overwrite mode.
+    ///
+    /// # Returns
+    /// `true` if overwriting, `false` otherwise.
+    pub fn is_overwriting(&self) -> bool {
+        self.overwrite
+    }
+
+    /// Gets the maximum number of elements the buffer can hold.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the number of elements in the buffer.
+    ///
+    /// # Returns
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not the buffer is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Determines whether or not the buffer is full.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise.
+    pub fn is_full(&self) -> bool {
+        self.length == N
+    }
+
+    /// Gets an element of the buffer.
+    ///
+    /// # Arguments
+    /// index - Provides the position of the element, where 0 is the front.
+    ///
+    /// # Returns
+    /// The element or `None` if `index` is out of range.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.length {
+            //
+            // UNSAFE: Every slot within `length` of the head is initialized.
+            //
+            Some(unsafe { &*self.buffer[self.wrap(self.head + index)].as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable element of the buffer.
+    ///
+    /// # Arguments
+    /// index - Provides the position of the element, where 0 is the front.
+    ///
+    /// # Returns
+    /// The element or `None` if `index` is out of range.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.length {
+            let slot = self.wrap(self.head + index);
+
+            //
+            // UNSAFE: Every slot within `length` of the head is initialized.
+            //
+            Some(unsafe { &mut *self.buffer[slot].as_mut_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the first element of the buffer.
+    ///
+    /// # Returns
+    /// The first element or `None` if the buffer is empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Gets the last element of the buffer.
+    ///
+    /// # Returns
+    /// The last element or `None` if the buffer is empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.length.wrapping_sub(1))
+    }
+
+    /// Appends an element to the back of the buffer.
+    ///
+    /// In overwrite mode a full buffer drops its first element to make room.
+    ///
+    /// # Arguments
+    /// item - Provides the element to append.
+    ///
+    /// # Returns
+    /// `Ok` if appended, or the element back if the buffer is full and not overwriting.
+    pub fn push_back(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            if !self.overwrite || N == 0 {
+                return Err(item);
+            }
+
+            self.pop_front();
+        }
+
+        let slot = self.wrap(self.head + self.length);
+        self.buffer[slot] = MaybeUninit::new(item);
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Prepends an element to the front of the buffer.
+    ///
+    /// In overwrite mode a full buffer drops its last element to make room.
+    ///
+    /// # Arguments
+    /// item - Provides the element to prepend.
+    ///
+    /// # Returns
+    /// `Ok` if prepended, or the element back if the buffer is full and not overwriting.
+    pub fn push_front(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            if !self.overwrite || N == 0 {
+                return Err(item);
+            }
+
+            self.pop_back();
+        }
+
+        self.head = self.wrap(self.head + N - 1);
+        self.buffer[self.head] = MaybeUninit::new(item);
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Removes the first element of the buffer.
+    ///
+    /// # Returns
+    /// The first element or `None` if the buffer is empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        //
+        // UNSAFE: The head slot is initialized and is no longer considered part of the buffer
+        //         once read.
+        //
+
+        let item = unsafe { self.buffer[self.head].as_ptr().read() };
+        self.head = self.wrap(self.head + 1);
+        self.length -= 1;
+        Some(item)
+    }
+
+    /// Removes the last element of the buffer.
+    ///
+    /// # Returns
+    /// The last element or `None` if the buffer is empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.length -= 1;
+
+        //
+        // UNSAFE: The tail slot is initialized and is no longer considered part of the buffer
+        //         once read.
+        //
+
+        let slot = self.wrap(self.head + self.length);
+        Some(unsafe { self.buffer[slot].as_ptr().read() })
+    }
+
+    /// Removes and drops every element of the buffer.
+    pub fn clear(&mut self) {
+        let (front, back) = self.as_mut_slices();
+        let (front, back) = (front as *mut [T], back as *mut [T]);
+
+        //
+        // Forget the elements first so a panicking destructor cannot cause a double drop.
+        //
+
+        self.head = 0;
+        self.length = 0;
+
+        //
+        // UNSAFE: Both slices cover exactly the initialized elements, which are no longer
+        //         considered part of the buffer.
+        //
+
+        unsafe {
+            ptr::drop_in_place(front);
+            ptr::drop_in_place(back);
+        }
+    }
+
+    /// Gets the elements of the buffer as two contiguous slices.
+    ///
+    /// The first slice holds the front of the buffer and the second holds the remainder that
+    /// wrapped around to the start of the storage. The second slice is empty when the elements do
+    /// not wrap.
+    ///
+    /// # Returns
+    /// The front and back slices, in order.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (front_length, back_length) = self.split_lengths();
+        let base = self.buffer.as_ptr() as *const T;
+
+        //
+        // UNSAFE: The split lengths cover exactly the initialized slots.
+        //
+
+        unsafe {
+            (
+                slice::from_raw_parts(base.add(self.head), front_length),
+                slice::from_raw_parts(base, back_length),
+            )
+        }
+    }
+
+    /// Gets the elements of the buffer as two contiguous mutable slices.
+    ///
+    /// # Returns
+    /// The front and back slices, in order. See `as_slices`.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (front_length, back_length) = self.split_lengths();
+        let base = self.buffer.as_mut_ptr() as *mut T;
+
+        //
+        // UNSAFE: The split lengths cover exactly the initialized slots, and the two slices never
+        //         overlap.
+        //
+
+        unsafe {
+            (
+                slice::from_raw_parts_mut(base.add(self.head), front_length),
+                slice::from_raw_parts_mut(base, back_length),
+            )
+        }
+    }
+
+    /// Gets the contiguous free region of the storage that follows the back of the buffer.
+    ///
+    /// Elements written to the start of the region are appended by a following `commit`, e.g.
+    /// after a DMA engine received into it. The region ends at the end of the storage, so a second
+    /// call after committing yields the free slots that wrapped around to its start. An empty
+    /// buffer is first moved to the start of the storage, making the whole storage one region.
+    /// The region never discards elements, even in overwrite mode.
+    ///
+    /// # Returns
+    /// The free slots, empty if the buffer is full.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::collections::ring_buffer::RingBuffer;
+    ///
+    /// let mut buffer: RingBuffer<u8, 4> = RingBuffer::new();
+    ///
+    /// let free = buffer.free_slice_mut();
+    /// assert_eq!(free.len(), 4);
+    /// free[0].write(b'o');
+    /// free[1].write(b'k');
+    ///
+    /// //
+    /// // UNSAFE: The first two free slots were written above.
+    /// //
+    ///
+    /// unsafe { buffer.commit(2) };
+    /// assert_eq!(buffer.as_slices(), (&b"ok"[..], &b""[..]));
+    /// ```
+    pub fn free_slice_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        if self.length == 0 {
+            self.head = 0;
+        }
+
+        let (tail, count) = self.free_region();
+        &mut self.buffer[tail..tail + count]
+    }
+
+    /// Appends elements that were written to the start of the region from `free_slice_mut`.
+    ///
+    /// # Arguments
+    /// count - Provides the number of elements written.
+    ///
+    /// # Panics
+    /// Panics if `count` is larger than the region from `free_slice_mut`.
+    ///
+    /// # Unsafe
+    /// The first `count` slots of the region most recently returned by `free_slice_mut` must be
+    /// initialized.
+    pub unsafe fn commit(&mut self, count: usize) {
+        assert!(count <= self.free_region().1);
+        self.length += count;
+    }
+
+    /// Creates an iterator over the elements, from front to back.
+    ///
+    /// # Returns
+    /// An iterator.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    /// Creates an iterator over mutable elements, from front to back.
+    ///
+    /// # Returns
+    /// An iterator.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+
+    /// Wraps a storage index that is less than `2 * N` back into the storage.
+    fn wrap(&self, index: usize) -> usize {
+        if index >= N {
+            index - N
+        } else {
+            index
+        }
+    }
+
+    /// Gets the start and length of the contiguous free region after the back of the elements.
+    fn free_region(&self) -> (usize, usize) {
+        let tail = self.wrap(self.head + self.length);
+        (tail, core::cmp::min(N - self.length, N - tail))
+    }
+
+    /// Gets the lengths of the front and back regions of the elements.
+    fn split_lengths(&self) -> (usize, usize) {
+        let front_length = core::cmp::min(self.length, N - self.head);
+        (front_length, self.length - front_length)
+    }
+}
+
+/// Implements the slice copy functionality of `RingBuffer`.
+impl<T: Copy, const N: usize> RingBuffer<T, N> {
+    /// Appends a slice of elements to the back of the buffer.
+    ///
+    /// Without overwrite mode only the elements that fit are written. In overwrite mode every
+    /// element is written, discarding the oldest elements as needed, so only the last `N` elements
+    /// of `data` are guaranteed to remain.
+    ///
+    /// # Arguments
+    /// data - Provides the elements to append.
+    ///
+    /// # Returns
+    /// The number of elements of `data` written.
+    pub fn write_slice(&mut self, data: &[T]) -> usize {
+        let offered = data.len();
+        let mut data = data;
+
+        if self.overwrite {
+            if data.len() > N {
+                data = &data[data.len() - N..];
+            }
+
+            let excess = (self.length + data.len()).saturating_sub(N);
+            self.head = self.wrap(self.head + excess);
+            self.length -= excess;
+        }
+
+        let count = core::cmp::min(data.len(), N - self.length);
+        let tail = self.wrap(self.head + self.length);
+        let first = core::cmp::min(count, N - tail);
+        let base = self.buffer.as_mut_ptr() as *mut T;
+
+        //
+        // UNSAFE: Both regions lie within the free slots of the storage. `T: Copy`, so the
+        //         discarded elements never need dropping.
+        //
+
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), base.add(tail), first);
+            ptr::copy_nonoverlapping(data.as_ptr().add(first), base, count - first);
+        }
+
+        self.length += count;
+
+        if self.overwrite {
+            offered
+        } else {
+            count
+        }
+    }
+
+    /// Removes elements from the front of the buffer into a slice.
+    ///
+    /// # Arguments
+    /// data - Provides the slice to fill.
+    ///
+    /// # Returns
+    /// The number of elements read, which is the smaller of `data.len()` and `len()`.
+    pub fn read_slice(&mut self, data: &mut [T]) -> usize {
+        let count = core::cmp::min(data.len(), self.length);
+        let first = core::cmp::min(count, N - self.head);
+        let base = self.buffer.as_ptr() as *const T;
+
+        //
+        // UNSAFE: Both regions lie within the initialized slots of the storage.
+        //
+
+        unsafe {
+            ptr::copy_nonoverlapping(base.add(self.head), data.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(base, data.as_mut_ptr().add(first), count - first);
+        }
+
+        self.head = self.wrap(self.head + count);
+        self.length -= count;
+        count
+    }
+}
+
+/// Drops the elements remaining in the buffer.
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Defines the default value for RingBuffer.
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        RingBuffer::new()
+    }
+}
+
+/// Formats a RingBuffer as the list of its elements, from front to back.
+impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut RingBuffer<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for RingBuffer<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { buffer: self }
+    }
+}
+
+/// Defines an iterator over the elements of a RingBuffer.
+pub struct Iter<'a, T> {
+    /// Holds the remaining elements of the front region.
+    front: slice::Iter<'a, T>,
+
+    /// Holds the remaining elements of the back region.
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.front.len() + self.back.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T> FusedIterator for Iter<'a, T> {}
+
+/// Defines an iterator over the mutable elements of a RingBuffer.
+pub struct IterMut<'a, T> {
+    /// Holds the remaining elements of the front region.
+    front: slice::IterMut<'a, T>,
+
+    /// Holds the remaining elements of the back region.
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.front.next().or_else(|| self.back.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.front.len() + self.back.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.back.next_back().or_else(|| self.front.next_back())
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+/// Defines an iterator that moves the elements out of a RingBuffer.
+pub struct IntoIter<T, const N: usize> {
+    /// Holds the buffer being drained.
+    buffer: RingBuffer<T, N>,
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.buffer.len(), Some(self.buffer.len()))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.buffer.pop_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::test_helpers::DropCounter;
+    use core::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::vec::Vec;
+
+    #[test]
+    fn push_pop_both_ends() {
+        let mut buffer: RingBuffer<u32, 4> = RingBuffer::new();
+
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.pop_front(), None);
+        assert_eq!(buffer.pop_back(), None);
+
+        buffer.push_back(2).unwrap();
+        buffer.push_front(1).unwrap();
+        buffer.push_back(3).unwrap();
+        buffer.push_front(0).unwrap();
+
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push_back(4), Err(4));
+        assert_eq!(buffer.push_front(4), Err(4));
+
+        assert_eq!(buffer.front(), Some(&0));
+        assert_eq!(buffer.back(), Some(&3));
+        assert_eq!(buffer.get(2), Some(&2));
+        assert_eq!(buffer.get(4), None);
+
+        assert_eq!(buffer.pop_front(), Some(0));
+        assert_eq!(buffer.pop_back(), Some(3));
+        assert_eq!(buffer.pop_back(), Some(2));
+        assert_eq!(buffer.pop_front(), Some(1));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn overwrite_mode() {
+        let mut buffer: RingBuffer<u32, 3> = RingBuffer::with_overwrite();
+
+        for value in 0..5 {
+            buffer.push_back(value).unwrap();
+        }
+
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [2, 3, 4]);
+
+        buffer.push_front(1).unwrap();
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+
+        buffer.set_overwrite(false);
+        assert_eq!(buffer.push_back(9), Err(9));
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut buffer: RingBuffer<u32, 0> = RingBuffer::with_overwrite();
+
+        assert!(buffer.is_full());
+        assert_eq!(buffer.push_back(1), Err(1));
+        assert_eq!(buffer.push_front(1), Err(1));
+        assert_eq!(buffer.write_slice(&[1, 2]), 2);
+        assert_eq!(buffer.as_slices(), (&[][..], &[][..]));
+    }
+
+    #[test]
+    fn slices_wrap() {
+        let mut buffer: RingBuffer<u8, 5> = RingBuffer::new();
+
+        assert_eq!(buffer.write_slice(&[0, 1, 2, 3]), 4);
+        assert_eq!(buffer.as_slices(), (&[0, 1, 2, 3][..], &[][..]));
+
+        let mut out = [0; 3];
+        assert_eq!(buffer.read_slice(&mut out), 3);
+        assert_eq!(out, [0, 1, 2]);
+
+        assert_eq!(buffer.write_slice(&[4, 5, 6, 7, 8]), 4);
+        assert_eq!(buffer.as_slices(), (&[3, 4][..], &[5, 6, 7][..]));
+
+        for value in buffer.as_mut_slices().1 {
+            *value *= 10;
+        }
+
+        let mut out = [0; 8];
+        assert_eq!(buffer.read_slice(&mut out), 5);
+        assert_eq!(out[..5], [3, 4, 50, 60, 70]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn write_slice_overwrite() {
+        let mut buffer: RingBuffer<u8, 4> = RingBuffer::with_overwrite();
+
+        assert_eq!(buffer.write_slice(&[0, 1, 2]), 3);
+        assert_eq!(buffer.write_slice(&[3, 4]), 2);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+        assert_eq!(buffer.write_slice(&[5, 6, 7, 8, 9, 10]), 6);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn free_slice_commit() {
+        let mut buffer: RingBuffer<u8, 4> = RingBuffer::new();
+        buffer.write_slice(b"abc");
+        assert_eq!(buffer.pop_front(), Some(b'a'));
+        assert_eq!(buffer.pop_front(), Some(b'b'));
+
+        let free = buffer.free_slice_mut();
+        assert_eq!(free.len(), 1);
+        free[0] = MaybeUninit::new(b'd');
+        unsafe { buffer.commit(1) };
+
+        let free = buffer.free_slice_mut();
+        assert_eq!(free.len(), 2);
+        free[0] = MaybeUninit::new(b'e');
+        unsafe { buffer.commit(1) };
+        assert_eq!(buffer.as_slices(), (&b"cd"[..], &b"e"[..]));
+
+        buffer.clear();
+        assert_eq!(buffer.free_slice_mut().len(), 4);
+
+        buffer.write_slice(b"abc");
+        buffer.pop_front();
+        assert_eq!(buffer.free_slice_mut().len(), 1);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| unsafe { buffer.commit(2) }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn iteration() {
+        let mut buffer: RingBuffer<u32, 4> = RingBuffer::new();
+
+        buffer.push_back(1).unwrap();
+        buffer.push_back(2).unwrap();
+        buffer.push_front(0).unwrap();
+
+        assert_eq!(buffer.iter().len(), 3);
+        assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), [2, 1, 0]);
+
+        for value in &mut buffer {
+            *value += 1;
+        }
+
+        assert_eq!(
+            (&buffer).into_iter().copied().collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(std::format!("{:?}", buffer), "[1, 2, 3]");
+
+        let mut values = buffer.into_iter();
+        assert_eq!(values.next_back(), Some(3));
+        assert_eq!(values.collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn drops_elements() {
+        let drops = Cell::new(0);
+
+        {
+            let mut buffer: RingBuffer<DropCounter, 3> = RingBuffer::with_overwrite();
+
+            for _ in 0..5 {
+                let _ = buffer.push_back(DropCounter(&drops));
+            }
+
+            assert_eq!(drops.get(), 2);
+
+            drop(buffer.pop_front());
+            assert_eq!(drops.get(), 3);
+        }
+
+        assert_eq!(drops.get(), 5);
+
+        let mut values = RingBuffer::<DropCounter, 3>::new();
+        let _ = values.push_back(DropCounter(&drops));
+        let _ = values.push_back(DropCounter(&drops));
+
+        let mut values = values.into_iter();
+        drop(values.next());
+        drop(values);
+        assert_eq!(drops.get(), 7);
+    }
+}
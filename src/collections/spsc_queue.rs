@@ -0,0 +1,599 @@
+//! # Single-Producer Single-Consumer Queue
+//!
+//! `spsc_queue` contains a fixed capacity, wait-free queue for passing data between exactly one
+//! producer and one consumer, such as an interrupt handler and a task.
+//!
+//! The queue is split into a `Producer` and a `Consumer` half which may be moved to different
+//! execution contexts. Each half owns one of the two positions in the queue and only ever reads
+//! the other, so every operation completes in a bounded number of steps using nothing but atomic
+//! loads and stores. No compare-and-swap is required, making the queue usable on cores such as
+//! the Cortex-M0 that lack it.
+//!
+//! Positions run from 0 to `2 * N - 1` so that a full queue can be told apart from an empty one
+//! without wasting a slot or dividing.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Defines the structure for the Single-Producer Single-Consumer Queue.
+///
+/// The queue holds up to `N` elements of type `T`. Elements are added and removed through the
+/// halves returned by `split`.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::spsc_queue::SpscQueue;
+///
+/// let mut queue: SpscQueue<u32, 4> = SpscQueue::new();
+/// let (mut producer, mut consumer) = queue.split();
+///
+/// producer.push(1).unwrap();
+/// producer.push(2).unwrap();
+///
+/// assert_eq!(consumer.pop(), Some(1));
+/// assert_eq!(consumer.pop(), Some(2));
+/// assert_eq!(consumer.pop(), None);
+/// ```
+pub struct SpscQueue<T, const N: usize> {
+    /// Holds the storage for the elements.
+    buffer: [UnsafeCell<MaybeUninit<T>>; N],
+
+    /// Holds the position of the next element to remove. Only written by the consumer.
+    head: AtomicUsize,
+
+    /// Holds the position of the next element to add. Only written by the producer.
+    tail: AtomicUsize,
+}
+
+//
+// UNSAFE: The producer and consumer never access the same slot at the same time, and elements
+//         only move between them, so sharing the queue is safe whenever `T` can be sent.
+//
+
+unsafe impl<T: Send, const N: usize> Sync for SpscQueue<T, N> {}
+
+/// Implements the functionality unique to `SpscQueue`.
+impl<T, const N: usize> SpscQueue<T, N> {
+    /// Creates a new, empty SpscQueue.
+    ///
+    /// # Returns
+    /// A SpscQueue.
+    pub const fn new() -> Self {
+        SpscQueue {
+            //
+            // UNSAFE: An array of `MaybeUninit` does not require initialization.
+            //
+            buffer: unsafe {
+                MaybeUninit::<[UnsafeCell<MaybeUninit<T>>; N]>::uninit().assume_init()
+            },
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gets the maximum number of elements the queue can hold.
+    ///
+    /// # Returns
+    /// The capacity `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the number of elements in the queue.
+    ///
+    /// # Returns
+    /// The number of elements at the time of the call.
+    pub fn len(&self) -> usize {
+        Self::distance(
+            self.head.load(Ordering::Acquire),
+            self.tail.load(Ordering::Acquire),
+        )
+    }
+
+    /// Determines whether or not the queue is empty.
+    ///
+    /// # Returns
+    /// `true` if empty at the time of the call, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Determines whether or not the queue is full.
+    ///
+    /// # Returns
+    /// `true` if full at the time of the call, `false` otherwise.
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Splits the queue into its producer and consumer halves.
+    ///
+    /// The halves borrow the queue mutably, so only one pair exists at a time. Splitting a
+    /// `&'static mut` queue yields `'static` halves that can be handed to an interrupt handler
+    /// and a task. A `static` cannot be borrowed mutably, so such a reference comes from a
+    /// singleton, or from a `static mut` that is split exactly once during initialization.
+    ///
+    /// # Returns
+    /// The producer and consumer.
+    ///
+    /// # Examples
+    /// ```
+    /// use core::ptr::addr_of_mut;
+    /// use raztos_util::collections::spsc_queue::{Consumer, Producer, SpscQueue};
+    ///
+    /// static mut EVENTS: SpscQueue<u8, 8> = SpscQueue::new();
+    ///
+    /// fn init() -> (Producer<'static, u8, 8>, Consumer<'static, u8, 8>) {
+    ///     //
+    ///     // UNSAFE: `init` is called once before interrupts are enabled, so no other reference
+    ///     //         to the queue exists.
+    ///     //
+    ///
+    ///     let queue = unsafe { &mut *addr_of_mut!(EVENTS) };
+    ///     queue.split()
+    /// }
+    ///
+    /// let (mut producer, mut consumer) = init();
+    /// producer.push(7).unwrap();
+    /// assert_eq!(consumer.pop(), Some(7));
+    /// ```
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        let queue = &*self;
+        (Producer { queue }, Consumer { queue })
+    }
+
+    /// Advances a position by a number of elements no larger than `N`.
+    fn advance(position: usize, count: usize) -> usize {
+        let position = position + count;
+
+        if position >= 2 * N {
+            position - 2 * N
+        } else {
+            position
+        }
+    }
+
+    /// Gets the number of elements between two positions.
+    fn distance(head: usize, tail: usize) -> usize {
+        if tail >= head {
+            tail - head
+        } else {
+            tail + 2 * N - head
+        }
+    }
+
+    /// Gets the index of the slot for a position.
+    fn slot(position: usize) -> usize {
+        if position >= N {
+            position - N
+        } else {
+            position
+        }
+    }
+
+    /// Gets a pointer to the start of the storage.
+    fn storage(&self) -> *mut T {
+        //
+        // `UnsafeCell<MaybeUninit<T>>` has the same layout as `T`, and writing through the pointer
+        // is allowed since the storage lives inside `UnsafeCell`.
+        //
+
+        self.buffer.as_ptr() as *mut T
+    }
+}
+
+/// Drops the elements remaining in the queue.
+impl<T, const N: usize> Drop for SpscQueue<T, N> {
+    fn drop(&mut self) {
+        let (_, mut consumer) = self.split();
+        while consumer.pop().is_some() {}
+    }
+}
+
+/// Defines the default value for SpscQueue.
+impl<T, const N: usize> Default for SpscQueue<T, N> {
+    fn default() -> Self {
+        SpscQueue::new()
+    }
+}
+
+/// Defines the half of a SpscQueue that adds elements.
+pub struct Producer<'a, T, const N: usize> {
+    /// Holds the queue.
+    queue: &'a SpscQueue<T, N>,
+}
+
+//
+// UNSAFE: Only one producer exists per queue, so moving it to another context is safe whenever
+//         the elements it sends can be.
+//
+
+unsafe impl<'a, T: Send, const N: usize> Send for Producer<'a, T, N> {}
+
+/// Implements the functionality unique to `Producer`.
+impl<'a, T, const N: usize> Producer<'a, T, N> {
+    /// Gets the number of elements that can be added without the queue becoming full.
+    ///
+    /// # Returns
+    /// The number of free slots. The consumer may free more at any time.
+    pub fn free_slots(&self) -> usize {
+        N - self.queue.len()
+    }
+
+    /// Determines whether or not the queue is full.
+    ///
+    /// # Returns
+    /// `true` if full, `false` otherwise. The consumer may free a slot at any time.
+    pub fn is_full(&self) -> bool {
+        self.queue.is_full()
+    }
+
+    /// Adds an element to the back of the queue.
+    ///
+    /// # Arguments
+    /// item - Provides the element to add.
+    ///
+    /// # Returns
+    /// `Ok` if added, or the element back if the queue is full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let tail = self.queue.tail.load(Ordering::Relaxed);
+        let head = self.queue.head.load(Ordering::Acquire);
+
+        if SpscQueue::<T, N>::distance(head, tail) == N {
+            return Err(item);
+        }
+
+        //
+        // UNSAFE: The slot at the tail is free and only the producer writes free slots.
+        //
+
+        unsafe {
+            self.queue
+                .storage()
+                .add(SpscQueue::<T, N>::slot(tail))
+                .write(item);
+        }
+
+        self.queue
+            .tail
+            .store(SpscQueue::<T, N>::advance(tail, 1), Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Adds elements from an iterator until either the iterator or the free slots run out.
+    ///
+    /// The consumer sees all of the elements at once.
+    ///
+    /// # Arguments
+    /// items - Provides the elements to add.
+    ///
+    /// # Returns
+    /// The number of elements added.
+    pub fn push_iter<I: IntoIterator<Item = T>>(&mut self, items: I) -> usize {
+        let tail = self.queue.tail.load(Ordering::Relaxed);
+        let head = self.queue.head.load(Ordering::Acquire);
+        let free = N - SpscQueue::<T, N>::distance(head, tail);
+
+        let mut count = 0;
+        for item in items.into_iter().take(free) {
+            let position = SpscQueue::<T, N>::advance(tail, count);
+
+            //
+            // UNSAFE: Every slot within `free` of the tail is free and only the producer writes
+            //         free slots.
+            //
+
+            unsafe {
+                self.queue
+                    .storage()
+                    .add(SpscQueue::<T, N>::slot(position))
+                    .write(item);
+            }
+
+            count += 1;
+        }
+
+        self.queue
+            .tail
+            .store(SpscQueue::<T, N>::advance(tail, count), Ordering::Release);
+
+        count
+    }
+}
+
+/// Implements the slice copy functionality of `Producer`.
+impl<'a, T: Copy, const N: usize> Producer<'a, T, N> {
+    /// Adds as many elements of a slice as fit, with at most two memory copies.
+    ///
+    /// The consumer sees all of the elements at once.
+    ///
+    /// # Arguments
+    /// data - Provides the elements to add.
+    ///
+    /// # Returns
+    /// The number of elements added.
+    pub fn write_slice(&mut self, data: &[T]) -> usize {
+        let tail = self.queue.tail.load(Ordering::Relaxed);
+        let head = self.queue.head.load(Ordering::Acquire);
+        let free = N - SpscQueue::<T, N>::distance(head, tail);
+
+        let count = core::cmp::min(data.len(), free);
+        let start = SpscQueue::<T, N>::slot(tail);
+        let first = core::cmp::min(count, N - start);
+        let base = self.queue.storage();
+
+        //
+        // UNSAFE: Both regions lie within the free slots, which only the producer writes.
+        //
+
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), base.add(start), first);
+            ptr::copy_nonoverlapping(data.as_ptr().add(first), base, count - first);
+        }
+
+        self.queue
+            .tail
+            .store(SpscQueue::<T, N>::advance(tail, count), Ordering::Release);
+
+        count
+    }
+}
+
+/// Defines the half of a SpscQueue that removes elements.
+pub struct Consumer<'a, T, const N: usize> {
+    /// Holds the queue.
+    queue: &'a SpscQueue<T, N>,
+}
+
+//
+// UNSAFE: Only one consumer exists per queue, so moving it to another context is safe whenever
+//         the elements it receives can be.
+//
+
+unsafe impl<'a, T: Send, const N: usize> Send for Consumer<'a, T, N> {}
+
+/// Implements the functionality unique to `Consumer`.
+impl<'a, T, const N: usize> Consumer<'a, T, N> {
+    /// Gets the number of elements available to remove.
+    ///
+    /// # Returns
+    /// The number of elements. The producer may add more at any time.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Determines whether or not the queue is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise. The producer may add an element at any time.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Gets the element at the front of the queue without removing it.
+    ///
+    /// # Returns
+    /// The first element or `None` if the queue is empty.
+    pub fn peek(&self) -> Option<&T> {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        let tail = self.queue.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        //
+        // UNSAFE: The slot at the head is initialized and the producer cannot reuse it until the
+        //         consumer, which this reference borrows, advances the head.
+        //
+
+        Some(unsafe { &*self.queue.storage().add(SpscQueue::<T, N>::slot(head)) })
+    }
+
+    /// Removes the element at the front of the queue.
+    ///
+    /// # Returns
+    /// The first element or `None` if the queue is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        let tail = self.queue.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        //
+        // UNSAFE: The slot at the head is initialized and only the consumer reads it.
+        //
+
+        let item = unsafe {
+            self.queue
+                .storage()
+                .add(SpscQueue::<T, N>::slot(head))
+                .read()
+        };
+
+        self.queue
+            .head
+            .store(SpscQueue::<T, N>::advance(head, 1), Ordering::Release);
+
+        Some(item)
+    }
+}
+
+/// Implements the slice copy functionality of `Consumer`.
+impl<'a, T: Copy, const N: usize> Consumer<'a, T, N> {
+    /// Removes as many elements as fit into a slice, with at most two memory copies.
+    ///
+    /// The producer sees all of the freed slots at once.
+    ///
+    /// # Arguments
+    /// data - Provides the slice to fill.
+    ///
+    /// # Returns
+    /// The number of elements removed.
+    pub fn read_slice(&mut self, data: &mut [T]) -> usize {
+        let head = self.queue.head.load(Ordering::Relaxed);
+        let tail = self.queue.tail.load(Ordering::Acquire);
+
+        let count = core::cmp::min(data.len(), SpscQueue::<T, N>::distance(head, tail));
+        let start = SpscQueue::<T, N>::slot(head);
+        let first = core::cmp::min(count, N - start);
+        let base = self.queue.storage();
+
+        //
+        // UNSAFE: Both regions lie within the initialized slots, which only the consumer reads.
+        //
+
+        unsafe {
+            ptr::copy_nonoverlapping(base.add(start), data.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(base, data.as_mut_ptr().add(first), count - first);
+        }
+
+        self.queue
+            .head
+            .store(SpscQueue::<T, N>::advance(head, count), Ordering::Release);
+
+        count
+    }
+}
+
+/// Implements draining the queue through an iterator.
+impl<'a, T, const N: usize> Iterator for Consumer<'a, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pop()
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::test_helpers::DropCounter;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[test]
+    fn push_pop() {
+        let mut queue: SpscQueue<u32, 3> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        for round in 0..4 {
+            assert!(consumer.is_empty());
+            assert_eq!(consumer.peek(), None);
+
+            producer.push(round).unwrap();
+            producer.push(round + 1).unwrap();
+            producer.push(round + 2).unwrap();
+
+            assert!(producer.is_full());
+            assert_eq!(producer.free_slots(), 0);
+            assert_eq!(producer.push(9), Err(9));
+
+            assert_eq!(consumer.len(), 3);
+            assert_eq!(consumer.peek(), Some(&round));
+            assert_eq!(consumer.pop(), Some(round));
+            assert_eq!(consumer.pop(), Some(round + 1));
+            assert_eq!(consumer.pop(), Some(round + 2));
+            assert_eq!(consumer.pop(), None);
+        }
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut queue: SpscQueue<u32, 0> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        assert!(producer.is_full());
+        assert_eq!(producer.push(1), Err(1));
+        assert_eq!(producer.write_slice(&[1, 2]), 0);
+        assert_eq!(consumer.pop(), None);
+    }
+
+    #[test]
+    fn batches_wrap() {
+        let mut queue: SpscQueue<u8, 5> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        let mut out = [0; 8];
+        for round in 0..10u8 {
+            let data = [round, round + 1, round + 2, round + 3];
+            assert_eq!(producer.write_slice(&data), 4);
+            assert_eq!(producer.write_slice(&data), 1);
+
+            assert_eq!(consumer.read_slice(&mut out[..3]), 3);
+            assert_eq!(out[..3], data[..3]);
+
+            assert_eq!(producer.push_iter(10..), 3);
+            assert_eq!(consumer.read_slice(&mut out), 5);
+            assert_eq!(out[..5], [round + 3, round, 10, 11, 12]);
+        }
+    }
+
+    #[test]
+    fn consumer_iterates() {
+        let mut queue: SpscQueue<u32, 8> = SpscQueue::new();
+        let (mut producer, consumer) = queue.split();
+
+        assert_eq!(producer.push_iter(0..5), 5);
+        assert_eq!(consumer.collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn drops_remaining() {
+        let drops = Cell::new(0);
+
+        {
+            let mut queue: SpscQueue<DropCounter, 4> = SpscQueue::new();
+            let (mut producer, mut consumer) = queue.split();
+
+            for _ in 0..3 {
+                let _ = producer.push(DropCounter(&drops));
+            }
+
+            drop(consumer.pop());
+            assert_eq!(drops.get(), 1);
+        }
+
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn threads() {
+        const COUNT: usize = 10_000;
+
+        let mut queue: SpscQueue<usize, 16> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                for value in 0..COUNT {
+                    while producer.push(value).is_err() {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+
+            let mut expected = 0;
+            while expected < COUNT {
+                match consumer.pop() {
+                    Some(value) => {
+                        assert_eq!(value, expected);
+                        expected += 1;
+                    }
+                    None => std::thread::yield_now(),
+                }
+            }
+        });
+
+        assert!(queue.is_empty());
+    }
+}
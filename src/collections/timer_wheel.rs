@@ -0,0 +1,680 @@
+//! # Timer Wheel
+//!
+//! `timer_wheel` contains a hierarchical timing wheel that tracks timeouts for any number of
+//! intrusively linked items, without any allocation.
+//!
+//! # How does it work?
+//!
+//! Time is measured in ticks. Each level of the wheel is a ring of `bits(usize)` slots, each slot
+//! holding an intrusive list of timers. A slot of level 0 covers a single tick, and a slot of every
+//! following level covers a full rotation of the level below it. A timer is placed on the lowest
+//! level where its expiry and the current time differ only within that level's slot index, so
+//! inserting a timer is a few bit operations followed by a list insert, and cancelling a timer is
+//! a list removal.
+//!
+//! Every level tracks its non-empty slots in a `SmallBitField`. Rotating the bitfield so the
+//! current slot becomes bit 0 lets `get_lowest_set_bit` find the next occupied slot of the level,
+//! so the next event of the wheel is found in constant time, however far away it is. Advancing
+//! the wheel by `N` ticks jumps from event to event rather than visiting every tick.
+//!
+//! When the current time reaches a slot above level 0, its timers are cascaded: each one is
+//! placed again relative to the new current time, landing on a lower level. When a slot of level
+//! 0 is reached, its timers have expired and are moved to the list of expired timers, where they
+//! can be collected with `pop_expired`.
+//!
+//! Timers further in the future than the top level can reach are parked in the furthest slot of
+//! the top level, and placed again each time that slot is reached.
+//!
+//! # Examples
+//! ```
+//! use core::pin::pin;
+//! use raztos_util::collections::timer_wheel::{TimerLink, TimerWheel};
+//! use raztos_util::timer_adapter;
+//!
+//! #[derive(Debug)]
+//! struct Task {
+//!     id: u32,
+//!     timeout: TimerLink,
+//! }
+//!
+//! timer_adapter!(TimeoutAdapter = Task { timeout });
+//!
+//! let sleeping = pin!(Task { id: 1, timeout: TimerLink::new() });
+//! let blocked = pin!(Task { id: 2, timeout: TimerLink::new() });
+//!
+//! let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::new();
+//! wheel.insert(sleeping.into_ref(), 10).unwrap();
+//! wheel.insert(blocked.into_ref(), 1000).unwrap();
+//!
+//! assert_eq!(wheel.ticks_until_next_expiry(), Some(10));
+//!
+//! assert_eq!(wheel.advance(10), 1);
+//! assert_eq!(wheel.pop_expired().map(|task| task.id), Some(1));
+//! assert_eq!(wheel.pop_expired().map(|task| task.id), None);
+//! ```
+
+use crate::collections::fast_bitfield::{FastBitField, SmallBitField};
+use crate::collections::intrusive_list::{Adapter, Link, List};
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::pin::Pin;
+
+//
+// Constants
+//
+
+/// Defines the number of slots in each level of the wheel.
+const SLOTS: usize = core::mem::size_of::<usize>() * 8;
+
+/// Defines the number of bits of a tick selecting the slot within a level.
+const SLOT_SHIFT: usize = SLOTS.trailing_zeros() as usize;
+
+/// Defines the mask selecting the slot within a level from a shifted tick.
+const SLOT_MASK: u64 = (SLOTS - 1) as u64;
+
+/// Defines the link embedded in items that can be placed in a timer wheel.
+pub struct TimerLink {
+    /// Holds the link into the slot list of the wheel.
+    link: Link,
+
+    /// Holds the tick at which the timer expires.
+    expiry: Cell<u64>,
+
+    /// Holds the level of the slot holding the timer, or the number of levels if expired.
+    level: Cell<usize>,
+
+    /// Holds the slot within the level holding the timer.
+    slot: Cell<usize>,
+}
+
+/// Implements the functionality unique to `TimerLink`.
+impl TimerLink {
+    /// Creates a new, unscheduled TimerLink.
+    ///
+    /// # Returns
+    /// A TimerLink.
+    pub const fn new() -> Self {
+        TimerLink {
+            link: Link::new(),
+            expiry: Cell::new(0),
+            level: Cell::new(0),
+            slot: Cell::new(0),
+        }
+    }
+
+    /// Determines whether or not the timer is in a wheel, either pending or expired.
+    ///
+    /// # Returns
+    /// `true` if scheduled, `false` otherwise.
+    pub fn is_scheduled(&self) -> bool {
+        self.link.is_linked()
+    }
+
+    /// Gets the tick at which the timer expires, or last expired.
+    ///
+    /// # Returns
+    /// The expiry tick.
+    pub fn expiry(&self) -> u64 {
+        self.expiry.get()
+    }
+}
+
+/// Defines the default value for TimerLink.
+impl Default for TimerLink {
+    fn default() -> Self {
+        TimerLink::new()
+    }
+}
+
+/// Formats a TimerLink by its schedule, since its pointers are meaningless to print.
+impl fmt::Debug for TimerLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerLink")
+            .field("scheduled", &self.is_scheduled())
+            .field("expiry", &self.expiry())
+            .finish()
+    }
+}
+
+/// Defines how to find the `TimerLink` of an item for a specific timer wheel.
+///
+/// # Unsafe
+/// Implementations must always return the same `TimerLink`, and that `TimerLink` must be a field
+/// of the item passed in. Use the `timer_adapter!` macro to generate a correct implementation.
+pub unsafe trait TimerAdapter {
+    /// The type of the items in the wheel.
+    type Item;
+
+    /// Gets the timer link of an item.
+    ///
+    /// # Arguments
+    /// item - Provides the item.
+    ///
+    /// # Returns
+    /// The timer link used by this adapter.
+    fn get_timer_link(item: &Self::Item) -> &TimerLink;
+}
+
+/// Generates a `TimerAdapter` that places items in a timer wheel through one of their `TimerLink`
+/// fields.
+///
+/// # Examples
+/// ```
+/// use raztos_util::collections::timer_wheel::TimerLink;
+/// use raztos_util::timer_adapter;
+///
+/// pub struct Task {
+///     timeout: TimerLink,
+/// }
+///
+/// timer_adapter!(pub TimeoutAdapter = Task { timeout });
+/// ```
+#[macro_export]
+macro_rules! timer_adapter {
+    ($vis:vis $name:ident = $item:ty { $field:ident }) => {
+        $vis struct $name;
+
+        unsafe impl $crate::collections::timer_wheel::TimerAdapter for $name {
+            type Item = $item;
+
+            fn get_timer_link(item: &$item) -> &$crate::collections::timer_wheel::TimerLink {
+                &item.$field
+            }
+        }
+    };
+}
+
+/// Defines the intrusive list adapter reaching the `Link` inside the `TimerLink` of an item.
+struct SlotAdapter<A>(PhantomData<A>);
+
+unsafe impl<A: TimerAdapter> Adapter for SlotAdapter<A> {
+    type Item = A::Item;
+
+    fn get_link(item: &A::Item) -> &Link {
+        &A::get_timer_link(item).link
+    }
+}
+
+/// Defines the structure for the Timer Wheel.
+///
+/// The wheel has `LEVELS` levels of `bits(usize)` slots, with `LEVELS * log2(bits(usize))` being
+/// at most 64. Timers within `(bits(usize) - 1) * bits(usize)^(LEVELS - 1)` ticks of the current
+/// time are placed directly, later timers are parked and placed again as time passes.
+///
+/// The wheel borrows every item it holds for the lifetime `'a`, so items can neither move nor be
+/// dropped while scheduled. Dropping the wheel unschedules all remaining items.
+pub struct TimerWheel<'a, A: TimerAdapter, const LEVELS: usize = 4> {
+    /// Holds the timers in each slot of each level.
+    slots: [[List<'a, SlotAdapter<A>>; SLOTS]; LEVELS],
+
+    /// Holds the non-empty slots of each level.
+    occupied: [SmallBitField; LEVELS],
+
+    /// Holds the expired timers that have not been collected yet, in expiry order.
+    expired: List<'a, SlotAdapter<A>>,
+
+    /// Holds the current tick.
+    now: u64,
+
+    /// Holds the number of timers in the wheel, including expired timers.
+    length: usize,
+}
+
+/// Implements the functionality unique to `TimerWheel`.
+impl<'a, A: TimerAdapter, const LEVELS: usize> TimerWheel<'a, A, LEVELS> {
+    /// Creates a new, empty TimerWheel starting at tick 0.
+    ///
+    /// # Returns
+    /// A TimerWheel.
+    ///
+    /// # Panics
+    /// Panics if `LEVELS` is 0 or the levels cover more than 64 bits of ticks.
+    pub fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    /// Creates a new, empty TimerWheel starting at a given tick.
+    ///
+    /// # Arguments
+    /// now - Provides the current tick.
+    ///
+    /// # Returns
+    /// A TimerWheel.
+    ///
+    /// # Panics
+    /// Panics if `LEVELS` is 0 or the levels cover more than 64 bits of ticks.
+    pub fn starting_at(now: u64) -> Self {
+        assert!(LEVELS > 0 && LEVELS * SLOT_SHIFT <= 64);
+
+        TimerWheel {
+            slots: core::array::from_fn(|_| core::array::from_fn(|_| List::new())),
+            occupied: [SmallBitField::new(); LEVELS],
+            expired: List::new(),
+            now,
+            length: 0,
+        }
+    }
+
+    /// Gets the current tick.
+    ///
+    /// # Returns
+    /// The current tick.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Gets the number of timers in the wheel, including expired timers not yet collected.
+    ///
+    /// # Returns
+    /// The number of timers.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Determines whether or not the wheel is empty.
+    ///
+    /// # Returns
+    /// `true` if empty, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Schedules an item to expire at a given tick.
+    ///
+    /// An item scheduled at or before the current tick expires on the next advance.
+    ///
+    /// # Arguments
+    /// item - Provides the item to schedule.
+    /// expiry - Provides the tick at which the item expires.
+    ///
+    /// # Returns
+    /// `Ok` if scheduled, or the item back if it is already scheduled.
+    pub fn insert(&mut self, item: Pin<&'a A::Item>, expiry: u64) -> Result<(), Pin<&'a A::Item>> {
+        let timer = A::get_timer_link(item.get_ref());
+        if timer.is_scheduled() {
+            return Err(item);
+        }
+
+        timer.expiry.set(expiry);
+        self.place(item);
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Schedules an item to expire a number of ticks after the current tick.
+    ///
+    /// # Arguments
+    /// item - Provides the item to schedule.
+    /// delay - Provides the number of ticks until the item expires.
+    ///
+    /// # Returns
+    /// `Ok` if scheduled, or the item back if it is already scheduled.
+    pub fn insert_after(
+        &mut self,
+        item: Pin<&'a A::Item>,
+        delay: u64,
+    ) -> Result<(), Pin<&'a A::Item>> {
+        self.insert(item, self.now.saturating_add(delay))
+    }
+
+    /// Removes a scheduled item from the wheel in constant time, whether pending or expired.
+    ///
+    /// # Arguments
+    /// item - Provides the item to remove.
+    ///
+    /// # Returns
+    /// `true` if the item was removed, `false` if it was not scheduled.
+    ///
+    /// # Unsafe
+    /// The wheel cannot verify membership in constant time. The caller must guarantee that the
+    /// item, if scheduled, is scheduled in this wheel and not another wheel using the same
+    /// adapter.
+    pub unsafe fn cancel(&mut self, item: Pin<&'a A::Item>) -> bool {
+        let timer = A::get_timer_link(item.get_ref());
+        if !timer.is_scheduled() {
+            return false;
+        }
+
+        let level = timer.level.get();
+        if level == LEVELS {
+            self.expired.remove(item);
+        } else {
+            let slot = timer.slot.get();
+            self.slots[level][slot].remove(item);
+            if self.slots[level][slot].is_empty() {
+                self.occupied[level].clear_bit(slot);
+            }
+        }
+
+        self.length -= 1;
+        true
+    }
+
+    /// Advances the current tick, expiring every timer due by the new tick.
+    ///
+    /// Only ticks on which a slot is reached are visited, so advancing is independent of the
+    /// number of ticks skipped.
+    ///
+    /// # Arguments
+    /// ticks - Provides the number of ticks to advance by.
+    ///
+    /// # Returns
+    /// The number of timers that expired.
+    pub fn advance(&mut self, ticks: u64) -> usize {
+        let target = self.now.saturating_add(ticks);
+        let expired = self.expired.len();
+
+        while let Some(event) = self.next_event() {
+            if event > target {
+                break;
+            }
+
+            self.now = event;
+            self.process();
+        }
+
+        self.now = target;
+        self.expired.len() - expired
+    }
+
+    /// Removes the earliest expired timer.
+    ///
+    /// # Returns
+    /// The expired item or `None` if no timers have expired.
+    pub fn pop_expired(&mut self) -> Option<Pin<&'a A::Item>> {
+        let item = self.expired.pop_front()?;
+        self.length -= 1;
+        Some(item)
+    }
+
+    /// Gets the number of ticks the wheel can be advanced by in one step, such as while idle
+    /// without a periodic tick, without missing an expiry.
+    ///
+    /// The next expiry on level 0 is exact. Timers on higher levels are only sorted to the tick
+    /// once cascaded, so when the nearest timer is still on a higher level, this is the time until
+    /// its slot is reached, which may be earlier than its expiry.
+    ///
+    /// # Returns
+    /// The number of ticks, 0 if timers have expired and not been collected, or `None` if the
+    /// wheel is empty.
+    pub fn ticks_until_next_expiry(&self) -> Option<u64> {
+        if !self.expired.is_empty() {
+            return Some(0);
+        }
+
+        self.next_event().map(|event| event - self.now)
+    }
+
+    /// Places a timer in the slot matching its expiry relative to the current tick.
+    ///
+    /// # Arguments
+    /// item - Provides the item, which must not be in any list.
+    fn place(&mut self, item: Pin<&'a A::Item>) {
+        let timer = A::get_timer_link(item.get_ref());
+
+        //
+        // Timers beyond the reach of the top level are parked at its furthest slot, and timers
+        // already due are placed in the current slot of level 0.
+        //
+
+        let top_shift = (LEVELS - 1) * SLOT_SHIFT;
+        let horizon = self.now.saturating_add(SLOT_MASK << top_shift);
+        let when = timer.expiry.get().clamp(self.now, horizon);
+
+        let differing = (when ^ self.now) | SLOT_MASK;
+        let significant = (u64::BITS - 1 - differing.leading_zeros()) as usize;
+        let level = (significant / SLOT_SHIFT).min(LEVELS - 1);
+        let slot = ((when >> (level * SLOT_SHIFT)) & SLOT_MASK) as usize;
+
+        timer.level.set(level);
+        timer.slot.set(slot);
+        let _ = self.slots[level][slot].push_back(item);
+        self.occupied[level].set_bit(slot);
+    }
+
+    /// Gets the next tick at which a slot of the wheel is reached.
+    ///
+    /// # Returns
+    /// The tick or `None` if every slot is empty.
+    fn next_event(&self) -> Option<u64> {
+        let mut next: Option<u64> = None;
+
+        for (level, occupied) in self.occupied.iter().enumerate() {
+            let shift = level * SLOT_SHIFT;
+            let current = (self.now >> shift) & SLOT_MASK;
+
+            let rotated = SmallBitField::from_bits(occupied.to_bits().rotate_right(current as u32));
+            if let Some(distance) = rotated.get_lowest_set_bit() {
+                let event = if level == 0 {
+                    self.now + distance as u64
+                } else {
+                    ((self.now >> shift) + distance as u64) << shift
+                };
+
+                next = Some(next.map_or(event, |next| next.min(event)));
+            }
+        }
+
+        next
+    }
+
+    /// Empties the slots reached at the current tick, expiring or cascading their timers.
+    fn process(&mut self) {
+        for level in (0..LEVELS).rev() {
+            let slot = ((self.now >> (level * SLOT_SHIFT)) & SLOT_MASK) as usize;
+            if !self.occupied[level].is_bit_set(slot) {
+                continue;
+            }
+
+            let mut timers = mem::take(&mut self.slots[level][slot]);
+            self.occupied[level].clear_bit(slot);
+
+            while let Some(item) = timers.pop_front() {
+                let timer = A::get_timer_link(item.get_ref());
+                if timer.expiry.get() <= self.now {
+                    timer.level.set(LEVELS);
+                    let _ = self.expired.push_back(item);
+                } else {
+                    self.place(item);
+                }
+            }
+        }
+    }
+}
+
+/// Defines the default value for TimerWheel.
+impl<'a, A: TimerAdapter, const LEVELS: usize> Default for TimerWheel<'a, A, LEVELS> {
+    fn default() -> Self {
+        TimerWheel::new()
+    }
+}
+
+/// Formats a TimerWheel by its current tick and number of timers.
+impl<'a, A: TimerAdapter, const LEVELS: usize> fmt::Debug for TimerWheel<'a, A, LEVELS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerWheel")
+            .field("now", &self.now)
+            .field("len", &self.length)
+            .field("expired", &self.expired.len())
+            .finish()
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pin::pin;
+    use std::vec::Vec;
+
+    #[derive(Debug)]
+    struct Task {
+        id: usize,
+        timeout: TimerLink,
+    }
+
+    timer_adapter!(TimeoutAdapter = Task { timeout });
+
+    fn task(id: usize) -> Task {
+        Task {
+            id,
+            timeout: TimerLink::new(),
+        }
+    }
+
+    fn collect<const LEVELS: usize>(wheel: &mut TimerWheel<TimeoutAdapter, LEVELS>) -> Vec<usize> {
+        core::iter::from_fn(|| wheel.pop_expired().map(|task| task.id)).collect()
+    }
+
+    #[test]
+    fn expires_in_order() {
+        let first = pin!(task(1));
+        let second = pin!(task(2));
+        let third = pin!(task(3));
+
+        let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::new();
+        wheel.insert(third.as_ref(), 7).unwrap();
+        wheel.insert(first.as_ref(), 2).unwrap();
+        wheel.insert_after(second.as_ref(), 5).unwrap();
+        assert!(wheel.insert(first.as_ref(), 3).is_err());
+        assert_eq!(wheel.len(), 3);
+
+        assert_eq!(wheel.advance(1), 0);
+        assert_eq!(wheel.advance(1), 1);
+        assert_eq!(collect(&mut wheel), [1]);
+        assert!(!first.timeout.is_scheduled());
+
+        assert_eq!(wheel.advance(10), 2);
+        assert_eq!(wheel.now(), 12);
+        assert_eq!(collect(&mut wheel), [2, 3]);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn due_timers_expire_on_next_advance() {
+        let late = pin!(task(1));
+
+        let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::starting_at(100);
+        wheel.insert(late.as_ref(), 40).unwrap();
+
+        assert_eq!(wheel.ticks_until_next_expiry(), Some(0));
+        assert_eq!(wheel.advance(0), 1);
+        assert_eq!(collect(&mut wheel), [1]);
+    }
+
+    #[test]
+    fn cascades_expire_on_exact_tick() {
+        let delays = [
+            SLOTS as u64 + 3,
+            1,
+            5000,
+            70,
+            (SLOTS * SLOTS) as u64 * 3 + 11,
+        ];
+        let tasks = delays.map(|_| task(0));
+        let mut wheel: TimerWheel<TimeoutAdapter, 2> = TimerWheel::starting_at(13);
+
+        for (index, delay) in delays.iter().enumerate() {
+            let item = unsafe { Pin::new_unchecked(&tasks[index]) };
+            wheel.insert_after(item, *delay).unwrap();
+        }
+
+        let mut expired = Vec::new();
+        while !wheel.is_empty() {
+            wheel.advance(1);
+            while let Some(item) = wheel.pop_expired() {
+                assert_eq!(item.timeout.expiry(), wheel.now());
+                expired.push(wheel.now() - 13);
+            }
+        }
+
+        let mut sorted = delays.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(expired, sorted);
+    }
+
+    #[test]
+    fn advance_skips_many_ticks() {
+        let delays = [3, 900, 40_000, 1_000_000];
+        let tasks = delays.map(|_| task(0));
+        let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::new();
+
+        for (index, delay) in delays.iter().enumerate() {
+            let item = unsafe { Pin::new_unchecked(&tasks[index]) };
+            wheel.insert(item, *delay).unwrap();
+        }
+
+        assert_eq!(wheel.advance(39_999), 2);
+        assert_eq!(wheel.advance(1), 1);
+        assert_eq!(wheel.advance(10_000_000), 1);
+        assert_eq!(wheel.now(), 10_040_000);
+        assert_eq!(wheel.ticks_until_next_expiry(), Some(0));
+        assert_eq!(wheel.len(), 4);
+    }
+
+    #[test]
+    fn cancel_removes_pending_and_expired() {
+        let soon = pin!(task(1));
+        let later = pin!(task(2));
+        let never = pin!(task(3));
+
+        let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::new();
+        wheel.insert(soon.as_ref(), 4).unwrap();
+        wheel.insert(later.as_ref(), 9).unwrap();
+
+        unsafe {
+            assert!(wheel.cancel(soon.as_ref()));
+            assert!(!wheel.cancel(soon.as_ref()));
+            assert!(!wheel.cancel(never.as_ref()));
+        }
+
+        assert_eq!(wheel.ticks_until_next_expiry(), Some(9));
+        assert_eq!(wheel.advance(9), 1);
+
+        unsafe {
+            assert!(wheel.cancel(later.as_ref()));
+        }
+
+        assert!(wheel.is_empty());
+        assert_eq!(wheel.pop_expired().map(|task| task.id), None);
+        assert_eq!(wheel.ticks_until_next_expiry(), None);
+    }
+
+    #[test]
+    fn next_expiry_bounds_higher_levels() {
+        let far = pin!(task(1));
+        let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::new();
+        let expiry = SLOTS as u64 * 2 + 5;
+        wheel.insert(far.as_ref(), expiry).unwrap();
+
+        let mut slept = 0;
+        while let Some(ticks) = wheel.ticks_until_next_expiry() {
+            assert!(slept + ticks <= expiry);
+            slept += ticks;
+            if wheel.advance(ticks) > 0 {
+                break;
+            }
+        }
+
+        assert_eq!(slept, expiry);
+        assert_eq!(collect(&mut wheel), [1]);
+    }
+
+    #[test]
+    fn dropping_wheel_unschedules_items() {
+        let waiting = pin!(task(1));
+
+        {
+            let mut wheel: TimerWheel<TimeoutAdapter> = TimerWheel::new();
+            wheel.insert(waiting.as_ref(), 1000).unwrap();
+            assert!(waiting.timeout.is_scheduled());
+        }
+
+        assert!(!waiting.timeout.is_scheduled());
+    }
+}
@@ -19,3 +19,21 @@ pub fn count_leading_zeros_exists() -> bool {
         false
     }
 }
+
+/// Returns whether or not this platform has a `Population Count` instruction
+#[inline(always)]
+pub fn population_count_exists() -> bool {
+    cfg!(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "popcnt"
+        ),
+        all(target_arch = "aarch64", target_feature = "neon")
+    ))
+}
+
+/// Returns whether or not this platform has a pointer sized `Compare And Swap` instruction
+#[inline(always)]
+pub fn compare_and_swap_exists() -> bool {
+    cfg!(target_has_atomic = "ptr")
+}
@@ -20,6 +20,9 @@
 #![cfg_attr(not(test), no_std)]
 #![feature(allocator_api)]
 
+#[cfg(all(feature = "std", not(test)))]
+extern crate std;
+
 pub mod algorithms;
 
 /// Memory Allocators with real-time guarantees.
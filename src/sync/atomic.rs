@@ -0,0 +1,523 @@
+//! # Atomic
+//!
+//! `atomic` contains atomic types with the same interface as those in `core::sync::atomic`, that
+//! are also complete on cores without compare-and-swap instructions, such as the Cortex-M0 or
+//! RISC-V cores without the `A` extension.
+//!
+//! On those cores, `core::sync::atomic` only provides loads and stores. The types here use the
+//! native instructions whenever the target has them, and otherwise perform every
+//! read-modify-write operation, such as `compare_exchange` or `fetch_add`, inside a
+//! `critical_section`. Single core systems cannot observe the difference, but the fallback is not
+//! lock-free, and is not atomic with respect to other cores.
+//!
+//! The implementation is selected at compile time from the same target information as
+//! `cpu_features::opcodes::compare_and_swap_exists`.
+//!
+//! # Examples
+//! ```
+//! use raztos_util::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+//!
+//! assert_eq!(NEXT_ID.fetch_add(1, Ordering::Relaxed), 0);
+//! assert_eq!(NEXT_ID.fetch_add(1, Ordering::Relaxed), 1);
+//! ```
+
+#[cfg(any(not(target_has_atomic = "8"), not(target_has_atomic = "ptr"), test))]
+use crate::sync::critical_section;
+use core::fmt;
+pub use core::sync::atomic::{compiler_fence, fence, Ordering};
+
+/// Gets the ordering of the load of an emulated read-modify-write operation.
+///
+/// # Arguments
+/// order - Provides the ordering of the operation.
+///
+/// # Returns
+/// The ordering of the load.
+#[cfg(any(not(target_has_atomic = "8"), not(target_has_atomic = "ptr"), test))]
+fn load_ordering(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        order => order,
+    }
+}
+
+/// Gets the ordering of the store of an emulated read-modify-write operation.
+///
+/// # Arguments
+/// order - Provides the ordering of the operation.
+///
+/// # Returns
+/// The ordering of the store.
+#[cfg(any(not(target_has_atomic = "8"), not(target_has_atomic = "ptr"), test))]
+fn store_ordering(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Acquire => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Release,
+        order => order,
+    }
+}
+
+/// Generates an atomic type wrapping a type of `core::sync::atomic`, with the operations shared by
+/// all atomic types.
+macro_rules! atomic_type {
+    (
+        $(#[$attr:meta])*
+        $name:ident $(<$generic:ident>)?, $value:ty, $native:ty, $width:literal
+    ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        pub struct $name $(<$generic>)? {
+            /// Holds the value, only loaded and stored when emulating.
+            inner: $native,
+        }
+
+        /// Implements the functionality unique to the atomic type.
+        impl $(<$generic>)? $name $(<$generic>)? {
+            /// Creates a new atomic.
+            ///
+            /// # Arguments
+            /// value - Provides the initial value.
+            ///
+            /// # Returns
+            /// The atomic.
+            pub const fn new(value: $value) -> Self {
+                $name {
+                    inner: <$native>::new(value),
+                }
+            }
+
+            /// Gets the value without synchronization, since the borrow guarantees exclusive
+            /// access.
+            ///
+            /// # Returns
+            /// A mutable reference to the value.
+            pub fn get_mut(&mut self) -> &mut $value {
+                self.inner.get_mut()
+            }
+
+            /// Consumes the atomic, returning the value.
+            ///
+            /// # Returns
+            /// The value.
+            pub fn into_inner(self) -> $value {
+                self.inner.into_inner()
+            }
+
+            /// Loads the value.
+            ///
+            /// # Arguments
+            /// order - Provides the memory ordering of the load.
+            ///
+            /// # Returns
+            /// The value.
+            pub fn load(&self, order: Ordering) -> $value {
+                self.inner.load(order)
+            }
+
+            /// Stores a value.
+            ///
+            /// # Arguments
+            /// value - Provides the value.
+            /// order - Provides the memory ordering of the store.
+            pub fn store(&self, value: $value, order: Ordering) {
+                self.inner.store(value, order);
+            }
+
+            /// Stores a value, returning the previous value.
+            ///
+            /// # Arguments
+            /// value - Provides the value.
+            /// order - Provides the memory ordering of the operation.
+            ///
+            /// # Returns
+            /// The previous value.
+            pub fn swap(&self, value: $value, order: Ordering) -> $value {
+                #[cfg(target_has_atomic = $width)]
+                return self.inner.swap(value, order);
+
+                #[cfg(not(target_has_atomic = $width))]
+                return self.emulate(order, |_| value);
+            }
+
+            /// Stores a value if the current value equals an expected value.
+            ///
+            /// # Arguments
+            /// current - Provides the expected value.
+            /// new - Provides the value to store.
+            /// success - Provides the memory ordering if the value is stored.
+            /// failure - Provides the memory ordering of the load if the value is not stored.
+            ///
+            /// # Returns
+            /// `Ok` with the previous value if stored, or `Err` with the current value otherwise.
+            pub fn compare_exchange(
+                &self,
+                current: $value,
+                new: $value,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$value, $value> {
+                #[cfg(target_has_atomic = $width)]
+                return self.inner.compare_exchange(current, new, success, failure);
+
+                #[cfg(not(target_has_atomic = $width))]
+                return self.emulate_compare_exchange(current, new, success, failure);
+            }
+
+            /// Stores a value if the current value equals an expected value, possibly failing
+            /// spuriously, which allows more efficient code in loops on some platforms.
+            ///
+            /// # Arguments
+            /// current - Provides the expected value.
+            /// new - Provides the value to store.
+            /// success - Provides the memory ordering if the value is stored.
+            /// failure - Provides the memory ordering of the load if the value is not stored.
+            ///
+            /// # Returns
+            /// `Ok` with the previous value if stored, or `Err` with the current value otherwise.
+            pub fn compare_exchange_weak(
+                &self,
+                current: $value,
+                new: $value,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$value, $value> {
+                #[cfg(target_has_atomic = $width)]
+                return self.inner.compare_exchange_weak(current, new, success, failure);
+
+                #[cfg(not(target_has_atomic = $width))]
+                return self.emulate_compare_exchange(current, new, success, failure);
+            }
+
+            /// Applies a function to the value until it is stored without interference.
+            ///
+            /// # Arguments
+            /// set_order - Provides the memory ordering when the new value is stored.
+            /// fetch_order - Provides the memory ordering of the loads.
+            /// f - Provides the function, returning the new value or `None` to stop.
+            ///
+            /// # Returns
+            /// `Ok` with the previous value if stored, or `Err` with the current value if `f`
+            /// returned `None`.
+            pub fn fetch_update<F>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                mut f: F,
+            ) -> Result<$value, $value>
+            where
+                F: FnMut($value) -> Option<$value>,
+            {
+                let mut previous = self.load(fetch_order);
+                while let Some(next) = f(previous) {
+                    match self.compare_exchange_weak(previous, next, set_order, fetch_order) {
+                        Ok(value) => return Ok(value),
+                        Err(value) => previous = value,
+                    }
+                }
+
+                Err(previous)
+            }
+
+            /// Replaces the value with a function of it inside a critical section.
+            ///
+            /// # Arguments
+            /// order - Provides the memory ordering of the operation.
+            /// f - Provides the function, returning the new value.
+            ///
+            /// # Returns
+            /// The previous value.
+            #[cfg(any(not(target_has_atomic = $width), test))]
+            #[cfg_attr(test, allow(dead_code))]
+            fn emulate<F>(&self, order: Ordering, f: F) -> $value
+            where
+                F: FnOnce($value) -> $value,
+            {
+                critical_section::critical_section(|_cs| {
+                    let previous = self.inner.load(load_ordering(order));
+                    self.inner.store(f(previous), store_ordering(order));
+                    previous
+                })
+            }
+
+            /// Performs a compare exchange inside a critical section.
+            ///
+            /// # Arguments
+            /// current - Provides the expected value.
+            /// new - Provides the value to store.
+            /// success - Provides the memory ordering if the value is stored.
+            /// failure - Provides the memory ordering of the load if the value is not stored.
+            ///
+            /// # Returns
+            /// `Ok` with the previous value if stored, or `Err` with the current value otherwise.
+            #[cfg(any(not(target_has_atomic = $width), test))]
+            #[cfg_attr(test, allow(dead_code))]
+            fn emulate_compare_exchange(
+                &self,
+                current: $value,
+                new: $value,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$value, $value> {
+                critical_section::critical_section(|_cs| {
+                    let previous = self.inner.load(load_ordering(success));
+                    if previous != current {
+                        return Err(self.inner.load(failure));
+                    }
+
+                    self.inner.store(new, store_ordering(success));
+                    Ok(previous)
+                })
+            }
+        }
+
+        /// Formats an atomic as its value.
+        impl $(<$generic>)? fmt::Debug for $name $(<$generic>)? {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+
+        impl $(<$generic>)? From<$value> for $name $(<$generic>)? {
+            fn from(value: $value) -> Self {
+                $name::new(value)
+            }
+        }
+    };
+}
+
+/// Generates a `fetch_*` operation of an atomic type.
+macro_rules! atomic_fetch {
+    ($(#[$attr:meta])* $fetch:ident, $value:ty, $width:literal, |$previous:ident, $operand:ident| $op:expr) => {
+        $(#[$attr])*
+        ///
+        /// # Arguments
+        /// value - Provides the operand.
+        /// order - Provides the memory ordering of the operation.
+        ///
+        /// # Returns
+        /// The previous value.
+        pub fn $fetch(&self, value: $value, order: Ordering) -> $value {
+            #[cfg(target_has_atomic = $width)]
+            return self.inner.$fetch(value, order);
+
+            #[cfg(not(target_has_atomic = $width))]
+            return self.emulate(order, |$previous| {
+                let $operand = value;
+                $op
+            });
+        }
+    };
+}
+
+atomic_type!(
+    /// Defines an unsigned integer that can be shared between threads.
+    AtomicUsize,
+    usize,
+    core::sync::atomic::AtomicUsize,
+    "ptr"
+);
+
+/// Implements the integer operations of `AtomicUsize`.
+impl AtomicUsize {
+    atomic_fetch!(
+        /// Adds to the value, wrapping around on overflow.
+        fetch_add, usize, "ptr", |previous, value| previous.wrapping_add(value)
+    );
+
+    atomic_fetch!(
+        /// Subtracts from the value, wrapping around on overflow.
+        fetch_sub, usize, "ptr", |previous, value| previous.wrapping_sub(value)
+    );
+
+    atomic_fetch!(
+        /// Applies a bitwise "and" to the value.
+        fetch_and, usize, "ptr", |previous, value| previous & value
+    );
+
+    atomic_fetch!(
+        /// Applies a bitwise "nand" to the value.
+        fetch_nand, usize, "ptr", |previous, value| !(previous & value)
+    );
+
+    atomic_fetch!(
+        /// Applies a bitwise "or" to the value.
+        fetch_or, usize, "ptr", |previous, value| previous | value
+    );
+
+    atomic_fetch!(
+        /// Applies a bitwise "xor" to the value.
+        fetch_xor, usize, "ptr", |previous, value| previous ^ value
+    );
+
+    atomic_fetch!(
+        /// Sets the value to the maximum of the value and the operand.
+        fetch_max, usize, "ptr", |previous, value| previous.max(value)
+    );
+
+    atomic_fetch!(
+        /// Sets the value to the minimum of the value and the operand.
+        fetch_min, usize, "ptr", |previous, value| previous.min(value)
+    );
+}
+
+/// Defines the default value for AtomicUsize.
+impl Default for AtomicUsize {
+    fn default() -> Self {
+        AtomicUsize::new(0)
+    }
+}
+
+atomic_type!(
+    /// Defines a boolean that can be shared between threads.
+    AtomicBool,
+    bool,
+    core::sync::atomic::AtomicBool,
+    "8"
+);
+
+/// Implements the logical operations of `AtomicBool`.
+impl AtomicBool {
+    atomic_fetch!(
+        /// Applies a logical "and" to the value.
+        fetch_and, bool, "8", |previous, value| previous & value
+    );
+
+    atomic_fetch!(
+        /// Applies a logical "nand" to the value.
+        fetch_nand, bool, "8", |previous, value| !(previous & value)
+    );
+
+    atomic_fetch!(
+        /// Applies a logical "or" to the value.
+        fetch_or, bool, "8", |previous, value| previous | value
+    );
+
+    atomic_fetch!(
+        /// Applies a logical "xor" to the value.
+        fetch_xor, bool, "8", |previous, value| previous ^ value
+    );
+}
+
+/// Defines the default value for AtomicBool.
+impl Default for AtomicBool {
+    fn default() -> Self {
+        AtomicBool::new(false)
+    }
+}
+
+atomic_type!(
+    /// Defines a raw pointer that can be shared between threads.
+    AtomicPtr<T>,
+    *mut T,
+    core::sync::atomic::AtomicPtr<T>,
+    "ptr"
+);
+
+/// Defines the default value for AtomicPtr.
+impl<T> Default for AtomicPtr<T> {
+    fn default() -> Self {
+        AtomicPtr::new(core::ptr::null_mut())
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn integer_operations() {
+        let atomic = AtomicUsize::new(5);
+
+        assert_eq!(atomic.fetch_add(3, Ordering::SeqCst), 5);
+        assert_eq!(atomic.fetch_sub(10, Ordering::SeqCst), 8);
+        assert_eq!(atomic.load(Ordering::SeqCst), usize::MAX - 1);
+        assert_eq!(atomic.fetch_and(0b1100, Ordering::SeqCst), usize::MAX - 1);
+        assert_eq!(atomic.fetch_or(0b0001, Ordering::SeqCst), 0b1100);
+        assert_eq!(atomic.fetch_xor(0b0101, Ordering::SeqCst), 0b1101);
+        assert_eq!(atomic.fetch_max(20, Ordering::SeqCst), 0b1000);
+        assert_eq!(atomic.fetch_min(2, Ordering::SeqCst), 20);
+        assert_eq!(atomic.fetch_nand(3, Ordering::SeqCst), 2);
+        assert_eq!(atomic.swap(7, Ordering::SeqCst), !2);
+
+        assert_eq!(
+            atomic.compare_exchange(1, 2, Ordering::SeqCst, Ordering::SeqCst),
+            Err(7)
+        );
+        assert_eq!(
+            atomic.compare_exchange(7, 2, Ordering::SeqCst, Ordering::SeqCst),
+            Ok(7)
+        );
+        assert_eq!(
+            atomic.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |value| value
+                .checked_sub(3)),
+            Err(2)
+        );
+        assert_eq!(atomic.into_inner(), 2);
+    }
+
+    #[test]
+    fn bool_and_pointer_operations() {
+        let flag = AtomicBool::default();
+        assert!(!flag.fetch_or(true, Ordering::SeqCst));
+        assert!(flag.fetch_xor(true, Ordering::SeqCst));
+        assert!(!flag.fetch_nand(true, Ordering::SeqCst));
+        assert!(flag.fetch_and(false, Ordering::SeqCst));
+        assert!(!flag.load(Ordering::SeqCst));
+
+        let mut value = 3;
+        let pointer: AtomicPtr<i32> = AtomicPtr::default();
+        assert!(pointer.swap(&mut value, Ordering::SeqCst).is_null());
+        assert_eq!(unsafe { *pointer.load(Ordering::SeqCst) }, 3);
+    }
+
+    #[test]
+    fn emulation_is_atomic() {
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 1000;
+
+        let atomic = Arc::new(AtomicUsize::new(0));
+        let mut handles = Vec::new();
+
+        for _ in 0..THREADS {
+            let atomic = atomic.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..ROUNDS {
+                    atomic.emulate(Ordering::AcqRel, |value| value + 1);
+
+                    let mut current = atomic.load(Ordering::Relaxed);
+                    while let Err(value) = atomic.emulate_compare_exchange(
+                        current,
+                        current + 1,
+                        Ordering::AcqRel,
+                        Ordering::Relaxed,
+                    ) {
+                        current = value;
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(atomic.load(Ordering::Relaxed), THREADS * ROUNDS * 2);
+
+        let flag = AtomicBool::new(false);
+        assert!(!flag.emulate(Ordering::SeqCst, |value| !value));
+        assert_eq!(
+            flag.emulate_compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst),
+            Err(true)
+        );
+    }
+}
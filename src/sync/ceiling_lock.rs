@@ -0,0 +1,415 @@
+//! # Ceiling Lock
+//!
+//! `ceiling_lock` contains locks following the stack resource policy, an alternative to priority
+//! inheritance for tasks with static priorities.
+//!
+//! Every `CeilingLock` has a ceiling, the highest priority of any task that uses it. Taking the
+//! lock raises the system ceiling to the ceiling of the lock, so no task that uses the lock can
+//! preempt its owner, and restores the system ceiling when released. Taking a lock therefore
+//! never waits, locks cannot deadlock, and since a preempting task always runs to completion
+//! before the preempted task resumes, all tasks can share a single stack.
+//!
+//! # System Ceiling
+//!
+//! How the system ceiling is raised depends on the platform, so it is provided by a
+//! `SystemCeiling` hook. On ARMv7-M and later, `BasePri` masks interrupts at and below the ceiling
+//! with the `BASEPRI` register. Kernels scheduling tasks in software can implement the hook with
+//! their own scheduler lock.
+//!
+//! Ceilings are checked to be within the priorities of the hook at compile time. The ceiling must
+//! be at least the priority of every task that uses the lock. A task above the ceiling taking the
+//! lock while it is held is detected and panics, rather than giving out the value twice.
+//!
+//! # Examples
+//! ```
+//! use core::cell::Cell;
+//! use raztos_util::sync::ceiling_lock::{CeilingLock, SystemCeiling};
+//!
+//! std::thread_local! {
+//!     static CEILING: Cell<usize> = Cell::new(0);
+//! }
+//!
+//! // A hook for a host with a single task.
+//! struct Host;
+//!
+//! unsafe impl SystemCeiling for Host {
+//!     const MAX: usize = 15;
+//!
+//!     fn get() -> usize {
+//!         CEILING.with(|ceiling| ceiling.get())
+//!     }
+//!
+//!     unsafe fn set(ceiling: usize) {
+//!         CEILING.with(|current| current.set(ceiling));
+//!     }
+//! }
+//!
+//! static SENSOR: CeilingLock<Host, u32, 3> = CeilingLock::new(0);
+//!
+//! SENSOR.lock(|value| {
+//!     assert_eq!(Host::get(), 3);
+//!     *value += 1;
+//! });
+//!
+//! assert_eq!(Host::get(), 0);
+//! ```
+
+use crate::sync::atomic::{AtomicBool, Ordering};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+
+/// Defines how a platform raises the system ceiling, the priority below which tasks cannot
+/// preempt the running task.
+///
+/// Priorities range from 0, where no task is excluded, to `MAX`, with larger numbers being more
+/// urgent.
+///
+/// # Unsafe
+/// Implementations must guarantee that no task of a priority at or below the system ceiling
+/// preempts the running task while the ceiling is raised.
+pub unsafe trait SystemCeiling {
+    /// Defines the highest ceiling of the platform.
+    const MAX: usize;
+
+    /// Gets the current system ceiling.
+    ///
+    /// # Returns
+    /// The ceiling.
+    fn get() -> usize;
+
+    /// Sets the system ceiling.
+    ///
+    /// # Arguments
+    /// ceiling - Provides the ceiling, at most `MAX`.
+    ///
+    /// # Unsafe
+    /// Lowering the ceiling lets tasks preempt the running task, so the caller must have raised it
+    /// from the value it is restored to.
+    unsafe fn set(ceiling: usize);
+}
+
+/// Defines a system ceiling hook for ARMv7-M and later, masking interrupts with `BASEPRI`.
+///
+/// `PRIORITY_BITS` is the number of priority bits implemented by the interrupt controller.
+/// Ceiling `n` masks interrupts of logical priority `n` and below, where logical priority 1 is
+/// the least urgent hardware priority `(2^PRIORITY_BITS - 1) << (8 - PRIORITY_BITS)`. Ceiling 0
+/// disables masking.
+///
+/// Only suitable for single core systems, since other cores keep running.
+#[cfg(all(target_arch = "arm", target_feature = "mclass", target_feature = "v7"))]
+pub struct BasePri<const PRIORITY_BITS: usize>;
+
+#[cfg(all(target_arch = "arm", target_feature = "mclass", target_feature = "v7"))]
+unsafe impl<const PRIORITY_BITS: usize> SystemCeiling for BasePri<PRIORITY_BITS> {
+    const MAX: usize = (1 << PRIORITY_BITS) - 1;
+
+    #[inline(always)]
+    fn get() -> usize {
+        let basepri: usize;
+
+        //
+        // UNSAFE: Reading BASEPRI has no side effects.
+        //
+
+        unsafe {
+            core::arch::asm!("mrs {}, BASEPRI", out(reg) basepri, options(nomem, nostack, preserves_flags));
+        }
+
+        if basepri == 0 {
+            0
+        } else {
+            (1 << PRIORITY_BITS) - (basepri >> (8 - PRIORITY_BITS))
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn set(ceiling: usize) {
+        let basepri = if ceiling == 0 {
+            0
+        } else {
+            ((1 << PRIORITY_BITS) - ceiling) << (8 - PRIORITY_BITS)
+        };
+
+        //
+        // Without `nomem`, the compiler also treats the instruction as a memory barrier, so no
+        // accesses are moved across the change of the ceiling.
+        //
+
+        core::arch::asm!("msr BASEPRI, {}", in(reg) basepri, options(nostack, preserves_flags));
+    }
+}
+
+/// Defines the structure for a lock following the stack resource policy, protecting a value used
+/// by tasks of priorities up to `CEILING`.
+///
+/// # Examples
+/// A ceiling above the highest priority of the hook fails to compile:
+/// ```compile_fail
+/// use raztos_util::sync::ceiling_lock::{CeilingLock, SystemCeiling};
+///
+/// struct Fixed;
+///
+/// unsafe impl SystemCeiling for Fixed {
+///     const MAX: usize = 7;
+///
+///     fn get() -> usize {
+///         0
+///     }
+///
+///     unsafe fn set(_ceiling: usize) {}
+/// }
+///
+/// static TOO_HIGH: CeilingLock<Fixed, u32, 8> = CeilingLock::new(0);
+/// ```
+pub struct CeilingLock<S, T: ?Sized, const CEILING: usize> {
+    /// Holds whether or not the lock is held, to detect tasks above the ceiling.
+    locked: AtomicBool,
+
+    /// Ties the lock to its system ceiling hook.
+    _ceiling: PhantomData<fn() -> S>,
+
+    /// Holds the protected value.
+    data: UnsafeCell<T>,
+}
+
+//
+// UNSAFE: The lock gives a single owner at a time access to the value.
+//
+
+unsafe impl<S, T: ?Sized + Send, const CEILING: usize> Sync for CeilingLock<S, T, CEILING> {}
+
+/// Implements the construction of `CeilingLock`.
+impl<S: SystemCeiling, T, const CEILING: usize> CeilingLock<S, T, CEILING> {
+    /// Checks that the ceiling is a priority of the hook, failing to compile otherwise.
+    const VALID_CEILING: () = assert!(
+        CEILING > 0 && CEILING <= S::MAX,
+        "the ceiling must be between 1 and the highest ceiling of the hook"
+    );
+
+    /// Creates a new CeilingLock. Fails to compile if `CEILING` is 0 or above `S::MAX`.
+    ///
+    /// # Arguments
+    /// value - Provides the value to protect.
+    ///
+    /// # Returns
+    /// A CeilingLock.
+    pub const fn new(value: T) -> Self {
+        let () = Self::VALID_CEILING;
+
+        CeilingLock {
+            locked: AtomicBool::new(false),
+            _ceiling: PhantomData,
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the lock, returning the protected value.
+    ///
+    /// # Returns
+    /// The value.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Implements the functionality unique to `CeilingLock`.
+impl<S: SystemCeiling, T: ?Sized, const CEILING: usize> CeilingLock<S, T, CEILING> {
+    /// Gets the ceiling of the lock.
+    ///
+    /// # Returns
+    /// The ceiling.
+    pub const fn ceiling(&self) -> usize {
+        CEILING
+    }
+
+    /// Runs a closure with the lock held, raising the system ceiling to the ceiling of the lock
+    /// if it is lower.
+    ///
+    /// # Arguments
+    /// f - Provides the closure, which receives the value.
+    ///
+    /// # Returns
+    /// The value returned by the closure.
+    ///
+    /// # Panics
+    /// Panics if the lock is already held, which means it is taken again within the closure or
+    /// by a task above its ceiling.
+    pub fn lock<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        //
+        // The ceiling is restored when the guard is dropped, so a panicking closure still
+        // releases the lock on hosts that unwind.
+        //
+
+        struct Guard<'a, S: SystemCeiling> {
+            locked: &'a AtomicBool,
+            previous: Option<usize>,
+            _ceiling: PhantomData<fn() -> S>,
+        }
+
+        impl<'a, S: SystemCeiling> Drop for Guard<'a, S> {
+            fn drop(&mut self) {
+                self.locked.store(false, Ordering::Release);
+
+                if let Some(previous) = self.previous {
+                    //
+                    // UNSAFE: The ceiling was raised from the previous value by the lock.
+                    //
+
+                    unsafe { S::set(previous) }
+                }
+            }
+        }
+
+        let current = S::get();
+        let previous = if current < CEILING {
+            //
+            // UNSAFE: Raising the ceiling only keeps tasks from preempting.
+            //
+
+            unsafe { S::set(CEILING) };
+            Some(current)
+        } else {
+            None
+        };
+
+        if self.locked.swap(true, Ordering::Acquire) {
+            if let Some(previous) = previous {
+                //
+                // UNSAFE: The ceiling was raised from the previous value above.
+                //
+
+                unsafe { S::set(previous) }
+            }
+
+            panic!("the lock is held, either by the caller or by a task above its ceiling");
+        }
+
+        let _guard = Guard::<S> {
+            locked: &self.locked,
+            previous,
+            _ceiling: PhantomData,
+        };
+
+        //
+        // UNSAFE: The flag excludes every other owner until the guard is dropped.
+        //
+
+        f(unsafe { &mut *self.data.get() })
+    }
+
+    /// Determines whether or not the lock is held.
+    ///
+    /// # Returns
+    /// `true` if held, `false` otherwise.
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    /// Gets the value without locking, since the borrow guarantees the lock is not held.
+    ///
+    /// # Returns
+    /// A mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+/// Defines the default value for CeilingLock.
+impl<S: SystemCeiling, T: Default, const CEILING: usize> Default for CeilingLock<S, T, CEILING> {
+    fn default() -> Self {
+        CeilingLock::new(T::default())
+    }
+}
+
+/// Formats a CeilingLock with its ceiling.
+impl<S, T: ?Sized, const CEILING: usize> fmt::Debug for CeilingLock<S, T, CEILING> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CeilingLock")
+            .field("ceiling", &CEILING)
+            .field("locked", &self.locked.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static CEILING: Cell<usize> = const { Cell::new(0) };
+        static CHANGES: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// A hook recording every change of the ceiling of the thread.
+    struct Recorder;
+
+    unsafe impl SystemCeiling for Recorder {
+        const MAX: usize = 7;
+
+        fn get() -> usize {
+            CEILING.with(|ceiling| ceiling.get())
+        }
+
+        unsafe fn set(ceiling: usize) {
+            CEILING.with(|current| current.set(ceiling));
+            CHANGES.with(|changes| changes.borrow_mut().push(ceiling));
+        }
+    }
+
+    fn changes() -> Vec<usize> {
+        CHANGES.with(|changes| changes.borrow_mut().split_off(0))
+    }
+
+    #[test]
+    fn nested_locks_raise_and_restore_ceiling() {
+        let low: CeilingLock<Recorder, u32, 2> = CeilingLock::new(1);
+        let high: CeilingLock<Recorder, u32, 5> = CeilingLock::default();
+
+        let sum = low.lock(|low_value| {
+            assert_eq!(Recorder::get(), 2);
+            high.lock(|high_value| {
+                assert!(high.is_locked());
+                *high_value += *low_value;
+
+                //
+                // The ceiling is already above the lower lock.
+                //
+
+                let other: CeilingLock<Recorder, u32, 3> = CeilingLock::new(3);
+                other.lock(|other_value| *other_value + *high_value)
+            })
+        });
+
+        assert_eq!(sum, 4);
+        assert_eq!(Recorder::get(), 0);
+        assert_eq!(changes(), [2, 5, 2, 0]);
+        assert!(!low.is_locked());
+        assert_eq!(high.into_inner(), 1);
+    }
+
+    #[test]
+    fn relocking_panics_and_restores_ceiling() {
+        let lock: CeilingLock<Recorder, u32, 4> = CeilingLock::new(0);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            lock.lock(|_| lock.lock(|_| ()));
+        }));
+
+        assert!(result.is_err());
+        assert!(!lock.is_locked());
+        lock.lock(|value| *value += 1);
+        assert_eq!(Recorder::get(), 0);
+        assert_eq!(changes(), [4, 0, 4, 0]);
+    }
+}
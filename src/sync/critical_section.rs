@@ -0,0 +1,408 @@
+//! # Critical Section
+//!
+//! `critical_section` contains a way to run code with interrupts disabled, so that it cannot be
+//! preempted by interrupt handlers or, on single core systems, by other tasks.
+//!
+//! `critical_section(|cs| ...)` disables interrupts, runs the closure and then restores the
+//! interrupt state from before the call. Critical sections may be nested: an inner section finds
+//! interrupts already disabled and leaves them disabled when it ends, so only the outermost
+//! section enables them again. The closure receives a `CriticalSection` token, which data
+//! structures can require as proof that they are accessed with interrupts disabled.
+//!
+//! # Backends
+//!
+//! How interrupts are disabled depends on the platform, so the implementation is provided by a
+//! `Backend` selected once per application with `set_critical_section_backend!`. This crate
+//! provides backends for:
+//! * ARM Cortex-M, masking interrupts with `PRIMASK`.
+//! * RISC-V, masking machine mode interrupts with `mstatus.MIE`.
+//! * Hosted targets with the `std` feature, serializing sections with a global mutex, which is
+//!   useful to test code using critical sections on a development machine.
+//!
+//! Other platforms can implement `Backend` themselves.
+//!
+//! # Examples
+//! ```
+//! use core::cell::Cell;
+//! use raztos_util::sync::critical_section::{self, Backend, RestoreState};
+//!
+//! // A backend for a single threaded host without interrupts.
+//! struct Host;
+//!
+//! unsafe impl Backend for Host {
+//!     unsafe fn acquire() -> RestoreState {
+//!         RestoreState::from_raw(0)
+//!     }
+//!
+//!     unsafe fn release(_state: RestoreState) {}
+//! }
+//!
+//! raztos_util::set_critical_section_backend!(Host);
+//!
+//! let ticks = Cell::new(0);
+//!
+//! critical_section::critical_section(|_cs| {
+//!     ticks.set(ticks.get() + 1);
+//!
+//!     critical_section::critical_section(|_cs| ticks.set(ticks.get() + 1));
+//! });
+//!
+//! assert_eq!(ticks.get(), 2);
+//! ```
+
+use core::marker::PhantomData;
+
+/// Defines a token proving that interrupts are disabled for the lifetime `'cs`.
+#[derive(Clone, Copy, Debug)]
+pub struct CriticalSection<'cs> {
+    /// Ties the token to the lifetime of the critical section.
+    _marker: PhantomData<&'cs ()>,
+}
+
+/// Implements the functionality unique to `CriticalSection`.
+impl<'cs> CriticalSection<'cs> {
+    /// Creates a new CriticalSection token.
+    ///
+    /// # Returns
+    /// A CriticalSection.
+    ///
+    /// # Unsafe
+    /// The caller must guarantee that interrupts stay disabled for the lifetime `'cs`.
+    pub unsafe fn new() -> Self {
+        CriticalSection {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Defines the interrupt state from before a critical section was entered, used to restore it
+/// when the section ends.
+///
+/// The meaning of the raw value is up to the backend that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RestoreState(usize);
+
+/// Implements the functionality unique to `RestoreState`.
+impl RestoreState {
+    /// Creates a new RestoreState from its raw value.
+    ///
+    /// # Arguments
+    /// raw - Provides the raw value.
+    ///
+    /// # Returns
+    /// A RestoreState.
+    pub const fn from_raw(raw: usize) -> Self {
+        RestoreState(raw)
+    }
+
+    /// Gets the raw value of the RestoreState.
+    ///
+    /// # Returns
+    /// The raw value.
+    pub const fn to_raw(self) -> usize {
+        self.0
+    }
+}
+
+/// Defines how a platform enters and leaves critical sections.
+///
+/// # Unsafe
+/// Implementations must guarantee that no interrupt handler or other thread runs code in a
+/// critical section between `acquire` and the matching `release`, and that nested pairs of calls
+/// leave the section entered until the outermost `release`.
+pub unsafe trait Backend {
+    /// Enters a critical section.
+    ///
+    /// # Returns
+    /// The state to restore when the section ends.
+    ///
+    /// # Unsafe
+    /// Every call must be paired with a call to `release` with the returned state, in reverse
+    /// order of nested calls.
+    unsafe fn acquire() -> RestoreState;
+
+    /// Leaves a critical section.
+    ///
+    /// # Arguments
+    /// state - Provides the state returned by the matching `acquire`.
+    ///
+    /// # Unsafe
+    /// The state must come from the matching call to `acquire`.
+    unsafe fn release(state: RestoreState);
+}
+
+extern "Rust" {
+    fn _raztos_critical_section_acquire() -> RestoreState;
+    fn _raztos_critical_section_release(state: RestoreState);
+}
+
+/// Selects the `Backend` used by every critical section of the application.
+///
+/// Must be used exactly once in the final application.
+///
+/// # Examples
+/// ```ignore
+/// use raztos_util::sync::critical_section::CortexM;
+///
+/// raztos_util::set_critical_section_backend!(CortexM);
+/// ```
+#[macro_export]
+macro_rules! set_critical_section_backend {
+    ($backend:ty) => {
+        #[no_mangle]
+        unsafe fn _raztos_critical_section_acquire() -> $crate::sync::critical_section::RestoreState
+        {
+            <$backend as $crate::sync::critical_section::Backend>::acquire()
+        }
+
+        #[no_mangle]
+        unsafe fn _raztos_critical_section_release(
+            state: $crate::sync::critical_section::RestoreState,
+        ) {
+            <$backend as $crate::sync::critical_section::Backend>::release(state)
+        }
+    };
+}
+
+/// Enters a critical section with the backend of the application.
+///
+/// Prefer `critical_section`, which cannot leave a section unbalanced. This is for code whose
+/// sections do not follow a single scope, such as lock guards.
+///
+/// # Returns
+/// The state to restore when the section ends.
+///
+/// # Unsafe
+/// Every call must be paired with a call to `release` with the returned state, in reverse order
+/// of nested calls.
+#[inline]
+pub unsafe fn acquire() -> RestoreState {
+    _raztos_critical_section_acquire()
+}
+
+/// Leaves a critical section entered with `acquire`.
+///
+/// # Arguments
+/// state - Provides the state returned by the matching `acquire`.
+///
+/// # Unsafe
+/// The state must come from the matching call to `acquire`.
+#[inline]
+pub unsafe fn release(state: RestoreState) {
+    _raztos_critical_section_release(state)
+}
+
+/// Runs a closure in a critical section.
+///
+/// # Arguments
+/// f - Provides the closure, which receives the token of the critical section.
+///
+/// # Returns
+/// The value returned by the closure.
+#[inline]
+pub fn critical_section<F, R>(f: F) -> R
+where
+    F: FnOnce(CriticalSection<'_>) -> R,
+{
+    //
+    // The section ends when the guard is dropped, so a panicking closure still restores the
+    // interrupt state on hosts that unwind.
+    //
+
+    struct Guard(RestoreState);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            unsafe { release(self.0) }
+        }
+    }
+
+    //
+    // UNSAFE: The guard releases the section after the closure, and the token cannot outlive the
+    //         closure.
+    //
+
+    let _guard = Guard(unsafe { acquire() });
+    f(unsafe { CriticalSection::new() })
+}
+
+/// Defines a backend for ARM Cortex-M processors, masking interrupts with `PRIMASK`.
+///
+/// Only suitable for single core systems, since other cores keep running.
+#[cfg(all(target_arch = "arm", target_feature = "mclass"))]
+pub struct CortexM;
+
+#[cfg(all(target_arch = "arm", target_feature = "mclass"))]
+unsafe impl Backend for CortexM {
+    #[inline(always)]
+    unsafe fn acquire() -> RestoreState {
+        let primask: usize;
+        core::arch::asm!("mrs {}, PRIMASK", out(reg) primask, options(nomem, nostack, preserves_flags));
+
+        //
+        // Without `nomem`, the compiler also treats the instruction as a memory barrier, so no
+        // accesses are moved out of the section.
+        //
+
+        core::arch::asm!("cpsid i", options(nostack, preserves_flags));
+
+        //
+        // PRIMASK bit 0 set means interrupts were already masked.
+        //
+
+        RestoreState::from_raw(primask & 1)
+    }
+
+    #[inline(always)]
+    unsafe fn release(state: RestoreState) {
+        if state.to_raw() == 0 {
+            core::arch::asm!("cpsie i", options(nostack, preserves_flags));
+        }
+    }
+}
+
+/// Defines a backend for RISC-V processors running in machine mode, masking interrupts with the
+/// `MIE` bit of `mstatus`.
+///
+/// Only suitable for single hart systems, since other harts keep running.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+pub struct RiscV;
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+unsafe impl Backend for RiscV {
+    #[inline(always)]
+    unsafe fn acquire() -> RestoreState {
+        const MSTATUS_MIE: usize = 1 << 3;
+
+        //
+        // Atomically clears MIE and reads the previous mstatus.
+        //
+
+        let mstatus: usize;
+        core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus, options(nostack));
+
+        RestoreState::from_raw(mstatus & MSTATUS_MIE)
+    }
+
+    #[inline(always)]
+    unsafe fn release(state: RestoreState) {
+        if state.to_raw() != 0 {
+            core::arch::asm!("csrsi mstatus, 8", options(nostack));
+        }
+    }
+}
+
+/// Defines a backend for hosted targets, serializing critical sections of all threads with a
+/// global mutex.
+///
+/// Interrupts are not involved, which makes this backend suitable for testing code that uses
+/// critical sections on a development machine.
+#[cfg(any(test, feature = "std"))]
+pub struct StdBackend;
+
+#[cfg(any(test, feature = "std"))]
+mod std_backend {
+    use super::{Backend, RestoreState, StdBackend};
+    use std::cell::RefCell;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Holds the lock serializing critical sections.
+    static SECTION: Mutex<()> = Mutex::new(());
+
+    std::thread_local! {
+        /// Holds the lock while the current thread is in a critical section.
+        static HELD: RefCell<Option<MutexGuard<'static, ()>>> = const { RefCell::new(None) };
+    }
+
+    //
+    // The raw states of the backend.
+    //
+
+    const OUTERMOST: usize = 0;
+
+    const NESTED: usize = 1;
+
+    unsafe impl Backend for StdBackend {
+        unsafe fn acquire() -> RestoreState {
+            HELD.with(|held| {
+                if held.borrow().is_some() {
+                    return RestoreState::from_raw(NESTED);
+                }
+
+                //
+                // A panic inside a section poisons the lock, but the section has ended by the
+                // time another thread acquires it, so the poison is ignored.
+                //
+
+                let guard = SECTION.lock().unwrap_or_else(|poison| poison.into_inner());
+                *held.borrow_mut() = Some(guard);
+                RestoreState::from_raw(OUTERMOST)
+            })
+        }
+
+        unsafe fn release(state: RestoreState) {
+            if state.to_raw() == OUTERMOST {
+                HELD.with(|held| held.borrow_mut().take());
+            }
+        }
+    }
+
+    //
+    // The unit tests of this crate use the host backend.
+    //
+
+    #[cfg(test)]
+    crate::set_critical_section_backend!(StdBackend);
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn returns_closure_value() {
+        assert_eq!(critical_section(|_cs| 7), 7);
+    }
+
+    #[test]
+    fn nested_sections_stay_entered() {
+        critical_section(|_cs| {
+            let inner = unsafe { acquire() };
+            assert_eq!(inner, RestoreState::from_raw(1));
+            unsafe { release(inner) };
+
+            critical_section(|_cs| {});
+        });
+    }
+
+    #[test]
+    fn sections_exclude_each_other() {
+        let inside = Arc::new(AtomicUsize::new(0));
+        let mut handles = Vec::new();
+
+        for _ in 0..4 {
+            let inside = inside.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..200 {
+                    critical_section(|_cs| {
+                        assert_eq!(inside.fetch_add(1, Ordering::SeqCst), 0);
+                        thread::yield_now();
+                        inside.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}
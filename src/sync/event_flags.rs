@@ -0,0 +1,494 @@
+//! # Event Flags
+//!
+//! `event_flags` contains an event flag group, a word of flags that tasks and interrupt handlers
+//! set to signal events, and that tasks wait on until any or all of a set of flags is set.
+//!
+//! The flags and masks are `SmallBitField`s, so a group holds one flag per bit of a `usize`.
+//! Setting and clearing flags is lock-free. A waiter can leave the flags it waited for set, so
+//! every waiter sees the event, or clear them when it wakes, consuming the event.
+//!
+//! Waiting goes through the `WaitStrategy` of the group, shared with `Semaphore`, so a kernel can
+//! block the task in its scheduler. Waits with a timeout need a `TimedWaitStrategy`, which also
+//! provides the clock the timeout is measured with.
+//!
+//! # Examples
+//! ```
+//! use raztos_util::collections::fast_bitfield::SmallBitField;
+//! use raztos_util::sync::event_flags::{EventFlags, OnWake};
+//!
+//! const RX_DONE: SmallBitField = SmallBitField::from_bits(0b01);
+//! const TX_DONE: SmallBitField = SmallBitField::from_bits(0b10);
+//!
+//! static UART: EventFlags = EventFlags::new();
+//!
+//! UART.set(RX_DONE);
+//! UART.set(TX_DONE);
+//!
+//! assert_eq!(UART.wait_all(RX_DONE | TX_DONE, OnWake::Keep), RX_DONE | TX_DONE);
+//! assert_eq!(UART.wait_any(RX_DONE, OnWake::Clear), RX_DONE);
+//! assert_eq!(UART.try_wait_all(RX_DONE | TX_DONE, OnWake::Keep), None);
+//! assert_eq!(UART.get(), TX_DONE);
+//! ```
+
+use crate::collections::fast_bitfield::SmallBitField;
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::semaphore::{SpinWait, WaitStrategy};
+
+/// Defines a wait strategy that can also give up waiting at a deadline.
+pub trait TimedWaitStrategy: WaitStrategy {
+    /// Gets the current time in ticks of the clock timeouts are measured with.
+    ///
+    /// The tick count may wrap around.
+    ///
+    /// # Returns
+    /// The current tick.
+    fn now(&self) -> usize;
+
+    /// Waits until the condition may be met or the deadline has passed.
+    ///
+    /// Like `wait`, returning early is allowed, since the caller checks both again.
+    ///
+    /// # Arguments
+    /// ready - Provides a check returning `true` if the condition is met.
+    /// deadline - Provides the tick to stop waiting at.
+    fn wait_until<F: Fn() -> bool>(&self, ready: F, deadline: usize);
+}
+
+/// Defines what a waiter does with the flags it waited for when it wakes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnWake {
+    /// Leaves the flags set, so other waiters also see them.
+    Keep,
+
+    /// Clears the flags the waiter waited for, in the same operation that checks them.
+    Clear,
+}
+
+/// Defines the condition a waiter waits for.
+#[derive(Clone, Copy)]
+enum Condition {
+    /// Waits for any flag of the mask.
+    Any,
+
+    /// Waits for every flag of the mask.
+    All,
+}
+
+/// Implements the functionality unique to `Condition`.
+impl Condition {
+    /// Determines whether or not flags meet the condition for a mask.
+    ///
+    /// # Arguments
+    /// flags - Provides the flags.
+    /// mask - Provides the mask.
+    ///
+    /// # Returns
+    /// `true` if met, `false` otherwise.
+    fn is_met(self, flags: usize, mask: usize) -> bool {
+        match self {
+            Condition::Any => flags & mask != 0,
+            Condition::All => flags & mask == mask,
+        }
+    }
+}
+
+/// Defines the structure for an event flag group.
+#[derive(Debug)]
+pub struct EventFlags<S = SpinWait> {
+    /// Holds the flags.
+    flags: AtomicUsize,
+
+    /// Holds how tasks wait for flags.
+    strategy: S,
+}
+
+/// Implements the construction of a busy-waiting `EventFlags`.
+impl EventFlags<SpinWait> {
+    /// Creates a new EventFlags with every flag clear, that busy-waits for flags.
+    ///
+    /// # Returns
+    /// An EventFlags.
+    pub const fn new() -> Self {
+        Self::with_strategy(SmallBitField::new(), SpinWait)
+    }
+}
+
+/// Implements the functionality unique to `EventFlags`.
+impl<S: WaitStrategy> EventFlags<S> {
+    /// Creates a new EventFlags.
+    ///
+    /// # Arguments
+    /// initial - Provides the flags that are set.
+    /// strategy - Provides how tasks wait for flags.
+    ///
+    /// # Returns
+    /// An EventFlags.
+    pub const fn with_strategy(initial: SmallBitField, strategy: S) -> Self {
+        EventFlags {
+            flags: AtomicUsize::new(initial.to_bits()),
+            strategy,
+        }
+    }
+
+    /// Gets the flags that are set.
+    ///
+    /// # Returns
+    /// The flags.
+    pub fn get(&self) -> SmallBitField {
+        SmallBitField::from_bits(self.flags.load(Ordering::Acquire))
+    }
+
+    /// Gets the wait strategy of the group.
+    ///
+    /// # Returns
+    /// The wait strategy.
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Sets flags, notifying waiters if any flag was clear.
+    ///
+    /// # Arguments
+    /// flags - Provides the flags to set.
+    ///
+    /// # Returns
+    /// The flags that were set before.
+    pub fn set(&self, flags: SmallBitField) -> SmallBitField {
+        let previous = self.flags.fetch_or(flags.to_bits(), Ordering::AcqRel);
+        if flags.to_bits() & !previous != 0 {
+            self.strategy.notify(usize::MAX);
+        }
+
+        SmallBitField::from_bits(previous)
+    }
+
+    /// Clears flags.
+    ///
+    /// # Arguments
+    /// flags - Provides the flags to clear.
+    ///
+    /// # Returns
+    /// The flags that were set before.
+    pub fn clear(&self, flags: SmallBitField) -> SmallBitField {
+        SmallBitField::from_bits(self.flags.fetch_and(!flags.to_bits(), Ordering::AcqRel))
+    }
+
+    /// Waits until any flag of a mask is set.
+    ///
+    /// # Arguments
+    /// mask - Provides the flags to wait for.
+    /// on_wake - Provides whether or not to clear the flags of the mask when woken.
+    ///
+    /// # Returns
+    /// The flags of the mask that were set when woken.
+    ///
+    /// # Panics
+    /// Panics if the mask is empty.
+    pub fn wait_any(&self, mask: SmallBitField, on_wake: OnWake) -> SmallBitField {
+        self.wait(Condition::Any, mask, on_wake)
+    }
+
+    /// Waits until every flag of a mask is set.
+    ///
+    /// # Arguments
+    /// mask - Provides the flags to wait for.
+    /// on_wake - Provides whether or not to clear the flags of the mask when woken.
+    ///
+    /// # Returns
+    /// The flags of the mask, which were all set when woken.
+    ///
+    /// # Panics
+    /// Panics if the mask is empty.
+    pub fn wait_all(&self, mask: SmallBitField, on_wake: OnWake) -> SmallBitField {
+        self.wait(Condition::All, mask, on_wake)
+    }
+
+    /// Takes any flag of a mask if one is set, without waiting.
+    ///
+    /// # Arguments
+    /// mask - Provides the flags to take.
+    /// on_wake - Provides whether or not to clear the flags of the mask if taken.
+    ///
+    /// # Returns
+    /// The flags of the mask that were set, or `None` if none was set.
+    pub fn try_wait_any(&self, mask: SmallBitField, on_wake: OnWake) -> Option<SmallBitField> {
+        self.try_take(Condition::Any, mask.to_bits(), on_wake)
+    }
+
+    /// Takes every flag of a mask if all are set, without waiting.
+    ///
+    /// # Arguments
+    /// mask - Provides the flags to take.
+    /// on_wake - Provides whether or not to clear the flags of the mask if taken.
+    ///
+    /// # Returns
+    /// The flags of the mask, or `None` if any was clear.
+    pub fn try_wait_all(&self, mask: SmallBitField, on_wake: OnWake) -> Option<SmallBitField> {
+        self.try_take(Condition::All, mask.to_bits(), on_wake)
+    }
+
+    /// Waits until flags meet a condition.
+    ///
+    /// # Arguments
+    /// condition - Provides the condition.
+    /// mask - Provides the flags to wait for.
+    /// on_wake - Provides whether or not to clear the flags of the mask when woken.
+    ///
+    /// # Returns
+    /// The flags of the mask that were set when woken.
+    fn wait(&self, condition: Condition, mask: SmallBitField, on_wake: OnWake) -> SmallBitField {
+        let mask = mask.to_bits();
+        assert!(mask != 0, "the mask must not be empty");
+
+        loop {
+            if let Some(flags) = self.try_take(condition, mask, on_wake) {
+                return flags;
+            }
+
+            self.strategy
+                .wait(|| condition.is_met(self.flags.load(Ordering::Relaxed), mask));
+        }
+    }
+
+    /// Takes flags if they meet a condition.
+    ///
+    /// # Arguments
+    /// condition - Provides the condition.
+    /// mask - Provides the flags to take.
+    /// on_wake - Provides whether or not to clear the flags of the mask if taken.
+    ///
+    /// # Returns
+    /// The flags of the mask that were set, or `None` if the condition was not met.
+    fn try_take(
+        &self,
+        condition: Condition,
+        mask: usize,
+        on_wake: OnWake,
+    ) -> Option<SmallBitField> {
+        let flags = match on_wake {
+            OnWake::Keep => Some(self.flags.load(Ordering::Acquire))
+                .filter(|flags| condition.is_met(*flags, mask)),
+            OnWake::Clear => self
+                .flags
+                .fetch_update(Ordering::AcqRel, Ordering::Relaxed, |flags| {
+                    if condition.is_met(flags, mask) {
+                        Some(flags & !mask)
+                    } else {
+                        None
+                    }
+                })
+                .ok(),
+        };
+
+        flags.map(|flags| SmallBitField::from_bits(flags & mask))
+    }
+}
+
+/// Implements the waits with a timeout of `EventFlags`.
+impl<S: TimedWaitStrategy> EventFlags<S> {
+    /// Waits until any flag of a mask is set, or a timeout passes.
+    ///
+    /// # Arguments
+    /// mask - Provides the flags to wait for.
+    /// on_wake - Provides whether or not to clear the flags of the mask when woken.
+    /// timeout - Provides the number of ticks to wait at most.
+    ///
+    /// # Returns
+    /// The flags of the mask that were set when woken, or `None` if the timeout passed.
+    ///
+    /// # Panics
+    /// Panics if the mask is empty.
+    pub fn wait_any_for(
+        &self,
+        mask: SmallBitField,
+        on_wake: OnWake,
+        timeout: usize,
+    ) -> Option<SmallBitField> {
+        self.wait_for(Condition::Any, mask, on_wake, timeout)
+    }
+
+    /// Waits until every flag of a mask is set, or a timeout passes.
+    ///
+    /// # Arguments
+    /// mask - Provides the flags to wait for.
+    /// on_wake - Provides whether or not to clear the flags of the mask when woken.
+    /// timeout - Provides the number of ticks to wait at most.
+    ///
+    /// # Returns
+    /// The flags of the mask, which were all set when woken, or `None` if the timeout passed.
+    ///
+    /// # Panics
+    /// Panics if the mask is empty.
+    pub fn wait_all_for(
+        &self,
+        mask: SmallBitField,
+        on_wake: OnWake,
+        timeout: usize,
+    ) -> Option<SmallBitField> {
+        self.wait_for(Condition::All, mask, on_wake, timeout)
+    }
+
+    /// Waits until flags meet a condition, or a timeout passes.
+    ///
+    /// # Arguments
+    /// condition - Provides the condition.
+    /// mask - Provides the flags to wait for.
+    /// on_wake - Provides whether or not to clear the flags of the mask when woken.
+    /// timeout - Provides the number of ticks to wait at most.
+    ///
+    /// # Returns
+    /// The flags of the mask that were set when woken, or `None` if the timeout passed.
+    fn wait_for(
+        &self,
+        condition: Condition,
+        mask: SmallBitField,
+        on_wake: OnWake,
+        timeout: usize,
+    ) -> Option<SmallBitField> {
+        let mask = mask.to_bits();
+        assert!(mask != 0, "the mask must not be empty");
+
+        //
+        // Elapsed ticks are measured from the start, so the deadline may wrap around.
+        //
+
+        let start = self.strategy.now();
+        loop {
+            if let Some(flags) = self.try_take(condition, mask, on_wake) {
+                return Some(flags);
+            }
+
+            if self.strategy.now().wrapping_sub(start) >= timeout {
+                return None;
+            }
+
+            self.strategy.wait_until(
+                || condition.is_met(self.flags.load(Ordering::Relaxed), mask),
+                start.wrapping_add(timeout),
+            );
+        }
+    }
+}
+
+/// Defines the default value for EventFlags.
+impl<S: WaitStrategy + Default> Default for EventFlags<S> {
+    fn default() -> Self {
+        EventFlags::with_strategy(SmallBitField::new(), S::default())
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    const A: SmallBitField = SmallBitField::from_bits(0b001);
+    const B: SmallBitField = SmallBitField::from_bits(0b010);
+    const C: SmallBitField = SmallBitField::from_bits(0b100);
+
+    /// A strategy where every wait lets one tick pass.
+    #[derive(Default)]
+    struct Ticks {
+        now: Cell<usize>,
+        notified: Cell<usize>,
+    }
+
+    impl WaitStrategy for Ticks {
+        fn wait<F: Fn() -> bool>(&self, _ready: F) {
+            self.now.set(self.now.get().wrapping_add(1));
+        }
+
+        fn notify(&self, _count: usize) {
+            self.notified.set(self.notified.get() + 1);
+        }
+    }
+
+    impl TimedWaitStrategy for Ticks {
+        fn now(&self) -> usize {
+            self.now.get()
+        }
+
+        fn wait_until<F: Fn() -> bool>(&self, ready: F, deadline: usize) {
+            assert!(!ready());
+            assert_ne!(self.now.get(), deadline);
+            self.wait(ready);
+        }
+    }
+
+    #[test]
+    fn set_and_clear() {
+        let flags: EventFlags<Ticks> = EventFlags::default();
+
+        assert_eq!(flags.set(A | B), SmallBitField::new());
+        assert_eq!(flags.set(A), A | B);
+        assert_eq!(flags.strategy().notified.get(), 1);
+        assert_eq!(flags.clear(A | C), A | B);
+        assert_eq!(flags.get(), B);
+    }
+
+    #[test]
+    fn try_wait_any_and_all() {
+        let flags = EventFlags::with_strategy(A | C, SpinWait);
+
+        assert_eq!(flags.try_wait_all(A | B, OnWake::Clear), None);
+        assert_eq!(flags.try_wait_any(A | B, OnWake::Keep), Some(A));
+        assert_eq!(flags.try_wait_all(A | C, OnWake::Keep), Some(A | C));
+        assert_eq!(flags.try_wait_any(B | C, OnWake::Clear), Some(C));
+        assert_eq!(flags.get(), A);
+        assert_eq!(flags.try_wait_any(B | C, OnWake::Keep), None);
+    }
+
+    #[test]
+    fn waits_time_out() {
+        let flags: EventFlags<Ticks> = EventFlags::default();
+        flags.set(A);
+
+        assert_eq!(flags.wait_all_for(A | B, OnWake::Keep, 3), None);
+        assert_eq!(flags.strategy().now(), 3);
+        assert_eq!(flags.wait_any_for(A | B, OnWake::Clear, 0), Some(A));
+        assert_eq!(flags.wait_any_for(A, OnWake::Keep, 0), None);
+
+        flags.strategy().now.set(usize::MAX - 1);
+        assert_eq!(flags.wait_any_for(B, OnWake::Keep, 4), None);
+        assert_eq!(flags.strategy().now(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_mask_panics() {
+        EventFlags::new().wait_any(SmallBitField::new(), OnWake::Keep);
+    }
+
+    #[test]
+    fn waiters_wake_on_flags() {
+        let flags = Arc::new(EventFlags::new());
+        let mut handles = Vec::new();
+
+        for mask in [A, B, C] {
+            let flags = flags.clone();
+            handles.push(thread::spawn(move || {
+                assert_eq!(flags.wait_all(mask | A, OnWake::Keep), mask | A);
+                flags.set(SmallBitField::from_bits(mask.to_bits() << 3));
+            }));
+        }
+
+        flags.set(B | C);
+        flags.set(A);
+
+        let done = SmallBitField::from_bits(0b111000);
+        assert_eq!(flags.wait_all(done, OnWake::Clear), done);
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(flags.get(), A | B | C);
+    }
+}
@@ -0,0 +1,1179 @@
+//! # Lock
+//!
+//! `lock` contains busy-waiting mutual exclusion locks protecting a value, for use where a task
+//! cannot block, such as in a kernel or between cores.
+//!
+//! Every lock is a `Lock` combining the protected value with a `RawLock` that implements the
+//! locking, and is locked through an RAII guard that unlocks it when dropped. The provided locks
+//! are:
+//! * `SpinLock`, a single flag taken by whichever waiter sees it free first. It is the cheapest
+//!   lock, but a waiter may starve under contention.
+//! * `TicketLock`, where each waiter draws a ticket and waits for its number to be served, so
+//!   waiters are served in arrival order.
+//! * `IrqSpinLock` and `IrqTicketLock`, which also disable interrupts while held. A task holding
+//!   the lock can then not be interrupted by a handler spinning on the same lock, so the value can
+//!   be shared between tasks and interrupt handlers. Their guards must be dropped in the reverse
+//!   order they were taken, since each restores the interrupt state from before its lock.
+//!
+//! `RwLock` is a reader-writer lock for values that are read often and written rarely, letting
+//! any number of readers or a single writer hold it at once.
+//!
+//! # Backoff
+//!
+//! A waiter calls its `Backoff` after every failed attempt, and the unlocking side notifies
+//! waiters through it. `Spin` waits exponentially longer between attempts with the `spin_loop`
+//! hint, reducing traffic on the contended cache line. On ARM, `WaitForEvent` sleeps the core
+//! with `WFE` until an unlock signals an event with `SEV`.
+//!
+//! # Examples
+//! ```
+//! use raztos_util::sync::lock::SpinLock;
+//!
+//! static COUNTER: SpinLock<u32> = SpinLock::new(0);
+//!
+//! *COUNTER.lock() += 1;
+//!
+//! let guard = COUNTER.lock();
+//! assert!(COUNTER.try_lock().is_none());
+//! assert_eq!(*guard, 1);
+//! ```
+
+use crate::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::sync::critical_section::{self, RestoreState};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::hint;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+
+//
+// Constants
+//
+
+/// Defines the largest exponent of the spin count of `Spin`.
+const SPIN_LIMIT: u32 = 6;
+
+/// Defines the flag set while a writer holds a RwLock.
+const WRITER: usize = 1 << 0;
+
+/// Defines the flag set while an upgradable reader holds a RwLock.
+const UPGRADABLE: usize = 1 << 1;
+
+/// Defines the flag set while a writer waits, keeping new readers out of a RwLock.
+const WRITER_WAITING: usize = 1 << 2;
+
+/// Defines the amount added to the state of a RwLock for each reader.
+const READER: usize = 1 << 3;
+
+/// Defines how a waiter waits between attempts to take a lock.
+pub trait Backoff {
+    /// Creates the state of a new waiter.
+    ///
+    /// # Returns
+    /// A Backoff.
+    fn new() -> Self;
+
+    /// Waits after a failed attempt to take the lock.
+    fn wait(&mut self);
+
+    /// Notifies waiters that the lock was released.
+    fn notify() {}
+}
+
+/// Defines a backoff spinning with the `spin_loop` hint, doubling the number of spins after each
+/// failed attempt up to a limit.
+#[derive(Debug)]
+pub struct Spin {
+    /// Holds the exponent of the number of spins of the next wait.
+    step: u32,
+}
+
+impl Backoff for Spin {
+    fn new() -> Self {
+        Spin { step: 0 }
+    }
+
+    fn wait(&mut self) {
+        for _ in 0..(1 << self.step) {
+            hint::spin_loop();
+        }
+
+        if self.step < SPIN_LIMIT {
+            self.step += 1;
+        }
+    }
+}
+
+/// Defines a backoff sleeping the core with `WFE` until an unlock signals an event with `SEV`.
+///
+/// Any event or interrupt also wakes the core, so waiters simply try again.
+#[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+#[derive(Debug)]
+pub struct WaitForEvent;
+
+#[cfg(any(target_arch = "arm", target_arch = "aarch64"))]
+impl Backoff for WaitForEvent {
+    fn new() -> Self {
+        WaitForEvent
+    }
+
+    fn wait(&mut self) {
+        unsafe { core::arch::asm!("wfe", options(nomem, nostack, preserves_flags)) }
+    }
+
+    fn notify() {
+        unsafe { core::arch::asm!("sev", options(nomem, nostack, preserves_flags)) }
+    }
+}
+
+/// Defines the locking algorithm of a `Lock`, without any protected value.
+///
+/// # Unsafe
+/// Implementations must guarantee that the lock is held by at most one owner at a time, from a
+/// successful `lock` or `try_lock` until the matching `unlock`.
+pub unsafe trait RawLock {
+    /// An unlocked lock.
+    const INIT: Self;
+
+    /// Takes the lock, waiting until it is free.
+    fn lock(&self);
+
+    /// Takes the lock if it is free.
+    ///
+    /// # Returns
+    /// `true` if the lock was taken, `false` otherwise.
+    fn try_lock(&self) -> bool;
+
+    /// Releases the lock.
+    ///
+    /// # Unsafe
+    /// The caller must hold the lock.
+    unsafe fn unlock(&self);
+
+    /// Determines whether or not the lock is held.
+    ///
+    /// # Returns
+    /// `true` if held, `false` otherwise.
+    fn is_locked(&self) -> bool;
+}
+
+/// Defines a lock taken by whichever waiter sees it free first.
+pub struct RawSpinLock<B = Spin> {
+    /// Holds whether or not the lock is held.
+    locked: AtomicBool,
+
+    /// Ties the lock to its backoff.
+    _backoff: PhantomData<fn() -> B>,
+}
+
+unsafe impl<B: Backoff> RawLock for RawSpinLock<B> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = RawSpinLock {
+        locked: AtomicBool::new(false),
+        _backoff: PhantomData,
+    };
+
+    fn lock(&self) {
+        let mut backoff = B::new();
+        while !self.try_lock() {
+            //
+            // Wait on plain loads, which keep the cache line shared, until the lock looks free.
+            //
+
+            while self.is_locked() {
+                backoff.wait();
+            }
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    unsafe fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+        B::notify();
+    }
+
+    fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+}
+
+/// Defines a lock serving waiters in the order they arrived.
+pub struct RawTicketLock<B = Spin> {
+    /// Holds the ticket of the next waiter to arrive.
+    next: AtomicUsize,
+
+    /// Holds the ticket allowed to hold the lock.
+    serving: AtomicUsize,
+
+    /// Ties the lock to its backoff.
+    _backoff: PhantomData<fn() -> B>,
+}
+
+unsafe impl<B: Backoff> RawLock for RawTicketLock<B> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = RawTicketLock {
+        next: AtomicUsize::new(0),
+        serving: AtomicUsize::new(0),
+        _backoff: PhantomData,
+    };
+
+    fn lock(&self) {
+        let ticket = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut backoff = B::new();
+        while self.serving.load(Ordering::Acquire) != ticket {
+            backoff.wait();
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        //
+        // Drawing a ticket only succeeds when it would be served immediately.
+        //
+
+        let serving = self.serving.load(Ordering::Acquire);
+        self.next
+            .compare_exchange(
+                serving,
+                serving.wrapping_add(1),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+    }
+
+    unsafe fn unlock(&self) {
+        //
+        // Only the holder changes the ticket being served.
+        //
+
+        let serving = self.serving.load(Ordering::Relaxed);
+        self.serving
+            .store(serving.wrapping_add(1), Ordering::Release);
+        B::notify();
+    }
+
+    fn is_locked(&self) -> bool {
+        self.next.load(Ordering::Relaxed) != self.serving.load(Ordering::Relaxed)
+    }
+}
+
+/// Defines a lock that disables interrupts while held, wrapping another lock.
+///
+/// Interrupts are disabled before waiting for the lock, so a handler cannot interrupt a holder,
+/// and restored after releasing it.
+///
+/// Each lock restores the interrupt state saved when it was taken, so nested locks must be
+/// released in the reverse order they were taken. Releasing an outer lock first enables
+/// interrupts while the inner lock is still held, and a handler spinning on that lock then
+/// deadlocks.
+pub struct Irq<R> {
+    /// Holds the wrapped lock.
+    raw: R,
+
+    /// Holds the interrupt state to restore when the lock is released.
+    state: UnsafeCell<RestoreState>,
+}
+
+//
+// UNSAFE: The restore state is only accessed by the holder of the wrapped lock.
+//
+
+unsafe impl<R: Sync> Sync for Irq<R> {}
+
+unsafe impl<R: RawLock> RawLock for Irq<R> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Irq {
+        raw: R::INIT,
+        state: UnsafeCell::new(RestoreState::from_raw(0)),
+    };
+
+    fn lock(&self) {
+        //
+        // UNSAFE: The section is released by unlock, after the lock.
+        //
+
+        unsafe {
+            let state = critical_section::acquire();
+            self.raw.lock();
+            *self.state.get() = state;
+        }
+    }
+
+    fn try_lock(&self) -> bool {
+        //
+        // UNSAFE: The section is released right away if the lock is held, or by unlock otherwise.
+        //
+
+        unsafe {
+            let state = critical_section::acquire();
+            if !self.raw.try_lock() {
+                critical_section::release(state);
+                return false;
+            }
+
+            *self.state.get() = state;
+            true
+        }
+    }
+
+    unsafe fn unlock(&self) {
+        let state = *self.state.get();
+        self.raw.unlock();
+        critical_section::release(state);
+    }
+
+    fn is_locked(&self) -> bool {
+        self.raw.is_locked()
+    }
+}
+
+/// Defines a lock taken by whichever waiter sees it free first.
+pub type SpinLock<T, B = Spin> = Lock<RawSpinLock<B>, T>;
+
+/// Defines a lock serving waiters in the order they arrived.
+pub type TicketLock<T, B = Spin> = Lock<RawTicketLock<B>, T>;
+
+/// Defines a `SpinLock` that disables interrupts while held.
+///
+/// Guards of nested Irq locks must be dropped in the reverse order they were taken. See `Irq`.
+pub type IrqSpinLock<T, B = Spin> = Lock<Irq<RawSpinLock<B>>, T>;
+
+/// Defines a `TicketLock` that disables interrupts while held.
+///
+/// Guards of nested Irq locks must be dropped in the reverse order they were taken. See `Irq`.
+pub type IrqTicketLock<T, B = Spin> = Lock<Irq<RawTicketLock<B>>, T>;
+
+/// Defines the structure for a Lock protecting a value with a `RawLock`.
+pub struct Lock<R, T: ?Sized> {
+    /// Holds the locking algorithm.
+    raw: R,
+
+    /// Holds the protected value.
+    data: UnsafeCell<T>,
+}
+
+//
+// UNSAFE: The raw lock gives a single owner at a time access to the value.
+//
+
+unsafe impl<R: RawLock + Send, T: ?Sized + Send> Send for Lock<R, T> {}
+
+unsafe impl<R: RawLock + Sync, T: ?Sized + Send> Sync for Lock<R, T> {}
+
+/// Implements the construction of `Lock`.
+impl<R: RawLock, T> Lock<R, T> {
+    /// Creates a new, unlocked Lock.
+    ///
+    /// # Arguments
+    /// value - Provides the value to protect.
+    ///
+    /// # Returns
+    /// A Lock.
+    pub const fn new(value: T) -> Self {
+        Lock {
+            raw: R::INIT,
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the lock, returning the protected value.
+    ///
+    /// # Returns
+    /// The value.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Implements the functionality unique to `Lock`.
+impl<R: RawLock, T: ?Sized> Lock<R, T> {
+    /// Takes the lock, waiting until it is free.
+    ///
+    /// # Returns
+    /// A guard giving access to the value until dropped.
+    pub fn lock(&self) -> LockGuard<'_, R, T> {
+        self.raw.lock();
+        LockGuard {
+            lock: self,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Takes the lock if it is free.
+    ///
+    /// # Returns
+    /// A guard giving access to the value until dropped, or `None` if the lock is held.
+    pub fn try_lock(&self) -> Option<LockGuard<'_, R, T>> {
+        if !self.raw.try_lock() {
+            return None;
+        }
+
+        Some(LockGuard {
+            lock: self,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Determines whether or not the lock is held.
+    ///
+    /// # Returns
+    /// `true` if held, `false` otherwise.
+    pub fn is_locked(&self) -> bool {
+        self.raw.is_locked()
+    }
+
+    /// Gets the value without locking, since the borrow guarantees no guards exist.
+    ///
+    /// # Returns
+    /// A mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Releases the lock without a guard.
+    ///
+    /// # Unsafe
+    /// The caller must hold the lock, taken through a guard that was forgotten.
+    pub unsafe fn force_unlock(&self) {
+        self.raw.unlock();
+    }
+}
+
+/// Defines the default value for Lock.
+impl<R: RawLock, T: Default> Default for Lock<R, T> {
+    fn default() -> Self {
+        Lock::new(T::default())
+    }
+}
+
+/// Formats a Lock with its value, if it is free.
+impl<R: RawLock, T: ?Sized + fmt::Debug> fmt::Debug for Lock<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_lock() {
+            Some(guard) => f.debug_struct("Lock").field("data", &&*guard).finish(),
+            None => f
+                .debug_struct("Lock")
+                .field("data", &format_args!("<locked>"))
+                .finish(),
+        }
+    }
+}
+
+/// Defines the RAII guard of a held Lock, releasing it when dropped.
+///
+/// The guard cannot be sent to another thread, since some locks must be released by the context
+/// that took them. Guards of `Irq` locks must also be dropped in the reverse order they were
+/// taken, since each restores the interrupt state from before its lock.
+pub struct LockGuard<'a, R: RawLock, T: ?Sized> {
+    /// Holds the held lock.
+    lock: &'a Lock<R, T>,
+
+    /// Prevents the guard from being sent to another thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+//
+// UNSAFE: A shared guard only gives shared access to the value.
+//
+
+unsafe impl<'a, R: RawLock + Sync, T: ?Sized + Sync> Sync for LockGuard<'a, R, T> {}
+
+impl<'a, R: RawLock, T: ?Sized> Deref for LockGuard<'a, R, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //
+        // UNSAFE: The guard holds the lock.
+        //
+
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, R: RawLock, T: ?Sized> DerefMut for LockGuard<'a, R, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        //
+        // UNSAFE: The guard holds the lock.
+        //
+
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, R: RawLock, T: ?Sized> Drop for LockGuard<'a, R, T> {
+    fn drop(&mut self) {
+        //
+        // UNSAFE: The guard holds the lock.
+        //
+
+        unsafe { self.lock.raw.unlock() }
+    }
+}
+
+impl<'a, R: RawLock, T: ?Sized + fmt::Debug> fmt::Debug for LockGuard<'a, R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Defines the structure for a reader-writer lock, allowing either any number of readers or a
+/// single writer.
+///
+/// Waiting writers take precedence over new readers: once a writer waits, readers that have not
+/// yet taken the lock wait until it has been written, so a steady stream of readers cannot starve
+/// writers. An upgradable reader shares the lock with readers, excludes writers and other
+/// upgradable readers, and can later become the writer without letting a writer in between.
+///
+/// # Examples
+/// ```
+/// use raztos_util::sync::lock::RwLock;
+///
+/// static CONFIG: RwLock<[u32; 4]> = RwLock::new([0; 4]);
+///
+/// CONFIG.write()[1] = 3;
+///
+/// let first = CONFIG.read();
+/// let second = CONFIG.read();
+/// assert_eq!(first[1], second[1]);
+/// assert!(CONFIG.try_write().is_none());
+/// ```
+pub struct RwLock<T: ?Sized, B = Spin> {
+    /// Holds the writer, upgradable reader and writer waiting flags, and the number of readers.
+    state: AtomicUsize,
+
+    /// Ties the lock to its backoff.
+    _backoff: PhantomData<fn() -> B>,
+
+    /// Holds the protected value.
+    data: UnsafeCell<T>,
+}
+
+//
+// UNSAFE: Readers share access to the value across threads, and writers have exclusive access.
+//
+
+unsafe impl<T: ?Sized + Send, B> Send for RwLock<T, B> {}
+
+unsafe impl<T: ?Sized + Send + Sync, B> Sync for RwLock<T, B> {}
+
+/// Implements the construction of `RwLock`.
+impl<T, B: Backoff> RwLock<T, B> {
+    /// Creates a new, unlocked RwLock.
+    ///
+    /// # Arguments
+    /// value - Provides the value to protect.
+    ///
+    /// # Returns
+    /// A RwLock.
+    pub const fn new(value: T) -> Self {
+        RwLock {
+            state: AtomicUsize::new(0),
+            _backoff: PhantomData,
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the lock, returning the protected value.
+    ///
+    /// # Returns
+    /// The value.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Implements the functionality unique to `RwLock`.
+impl<T: ?Sized, B: Backoff> RwLock<T, B> {
+    /// Takes the lock for reading, waiting while a writer holds or waits for it.
+    ///
+    /// # Returns
+    /// A guard giving shared access to the value until dropped.
+    pub fn read(&self) -> RwLockReadGuard<'_, T, B> {
+        let mut backoff = B::new();
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+
+            backoff.wait();
+        }
+    }
+
+    /// Takes the lock for reading if no writer holds or waits for it.
+    ///
+    /// # Returns
+    /// A guard giving shared access to the value until dropped, or `None` otherwise.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T, B>> {
+        self.try_add(WRITER | WRITER_WAITING, READER)?;
+        Some(RwLockReadGuard { lock: self })
+    }
+
+    /// Takes the lock for reading with the option to upgrade to writing, waiting while a writer
+    /// or another upgradable reader holds it, or a writer waits for it.
+    ///
+    /// # Returns
+    /// A guard giving shared access to the value until dropped.
+    pub fn upgradable_read(&self) -> RwLockUpgradableGuard<'_, T, B> {
+        let mut backoff = B::new();
+        loop {
+            if let Some(guard) = self.try_upgradable_read() {
+                return guard;
+            }
+
+            backoff.wait();
+        }
+    }
+
+    /// Takes the lock for reading with the option to upgrade to writing, if no writer or other
+    /// upgradable reader holds it and no writer waits for it.
+    ///
+    /// # Returns
+    /// A guard giving shared access to the value until dropped, or `None` otherwise.
+    pub fn try_upgradable_read(&self) -> Option<RwLockUpgradableGuard<'_, T, B>> {
+        self.try_add(WRITER | UPGRADABLE | WRITER_WAITING, UPGRADABLE)?;
+        Some(RwLockUpgradableGuard { lock: self })
+    }
+
+    /// Takes the lock for writing, keeping new readers out until it is free.
+    ///
+    /// # Returns
+    /// A guard giving exclusive access to the value until dropped.
+    pub fn write(&self) -> RwLockWriteGuard<'_, T, B> {
+        let mut backoff = B::new();
+        loop {
+            if let Some(guard) = self.try_write() {
+                return guard;
+            }
+
+            //
+            // The flag is cleared by whichever writer takes the lock, so it is raised again on
+            // every attempt while other writers still wait.
+            //
+
+            self.state.fetch_or(WRITER_WAITING, Ordering::Relaxed);
+            backoff.wait();
+        }
+    }
+
+    /// Takes the lock for writing if it is free.
+    ///
+    /// # Returns
+    /// A guard giving exclusive access to the value until dropped, or `None` otherwise.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T, B>> {
+        self.try_replace(WRITER_WAITING, WRITER)?;
+        Some(RwLockWriteGuard { lock: self })
+    }
+
+    /// Determines whether or not a writer holds the lock.
+    ///
+    /// # Returns
+    /// `true` if held for writing, `false` otherwise.
+    pub fn is_locked_exclusive(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & WRITER != 0
+    }
+
+    /// Gets the number of readers holding the lock, not counting an upgradable reader.
+    ///
+    /// # Returns
+    /// The number of readers.
+    pub fn reader_count(&self) -> usize {
+        self.state.load(Ordering::Relaxed) / READER
+    }
+
+    /// Gets the value without locking, since the borrow guarantees no guards exist.
+    ///
+    /// # Returns
+    /// A mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Adds to the state unless any of a set of flags is set.
+    ///
+    /// # Arguments
+    /// blocking - Provides the flags that prevent the addition.
+    /// amount - Provides the amount to add.
+    ///
+    /// # Returns
+    /// `Some` if added, `None` if a blocking flag was set.
+    fn try_add(&self, blocking: usize, amount: usize) -> Option<()> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        while state & blocking == 0 {
+            match self.state.compare_exchange_weak(
+                state,
+                state + amount,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(()),
+                Err(current) => state = current,
+            }
+        }
+
+        None
+    }
+
+    /// Replaces the state if it holds nothing but a set of flags.
+    ///
+    /// # Arguments
+    /// allowed - Provides the flags that may be set in the current state.
+    /// new - Provides the new state.
+    ///
+    /// # Returns
+    /// `Some` if replaced, `None` if the state held anything else.
+    fn try_replace(&self, allowed: usize, new: usize) -> Option<()> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        while state & !allowed == 0 {
+            match self
+                .state
+                .compare_exchange_weak(state, new, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => return Some(()),
+                Err(current) => state = current,
+            }
+        }
+
+        None
+    }
+
+    /// Removes flags or readers from the state and notifies waiters.
+    ///
+    /// # Arguments
+    /// amount - Provides the amount to subtract, which must be held by the caller.
+    fn release(&self, amount: usize) {
+        self.state.fetch_sub(amount, Ordering::Release);
+        B::notify();
+    }
+}
+
+/// Defines the default value for RwLock.
+impl<T: Default, B: Backoff> Default for RwLock<T, B> {
+    fn default() -> Self {
+        RwLock::new(T::default())
+    }
+}
+
+/// Formats a RwLock with its value, if it is not held for writing.
+impl<T: ?Sized + fmt::Debug, B: Backoff> fmt::Debug for RwLock<T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_read() {
+            Some(guard) => f.debug_struct("RwLock").field("data", &&*guard).finish(),
+            None => f
+                .debug_struct("RwLock")
+                .field("data", &format_args!("<locked>"))
+                .finish(),
+        }
+    }
+}
+
+/// Defines the RAII guard of a RwLock held for reading, releasing it when dropped.
+pub struct RwLockReadGuard<'a, T: ?Sized, B: Backoff> {
+    /// Holds the held lock.
+    lock: &'a RwLock<T, B>,
+}
+
+impl<'a, T: ?Sized, B: Backoff> Deref for RwLockReadGuard<'a, T, B> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //
+        // UNSAFE: The guard holds the lock for reading.
+        //
+
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized, B: Backoff> Drop for RwLockReadGuard<'a, T, B> {
+    fn drop(&mut self) {
+        self.lock.release(READER);
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug, B: Backoff> fmt::Debug for RwLockReadGuard<'a, T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Defines the RAII guard of a RwLock held for reading with the option to upgrade to writing,
+/// releasing it when dropped.
+pub struct RwLockUpgradableGuard<'a, T: ?Sized, B: Backoff> {
+    /// Holds the held lock.
+    lock: &'a RwLock<T, B>,
+}
+
+/// Implements the functionality unique to `RwLockUpgradableGuard`.
+impl<'a, T: ?Sized, B: Backoff> RwLockUpgradableGuard<'a, T, B> {
+    /// Upgrades to writing, keeping new readers out and waiting for the current readers to
+    /// leave.
+    ///
+    /// # Returns
+    /// A guard giving exclusive access to the value until dropped.
+    pub fn upgrade(self) -> RwLockWriteGuard<'a, T, B> {
+        let mut backoff = B::new();
+        let mut guard = self;
+        loop {
+            match guard.try_upgrade() {
+                Ok(writer) => return writer,
+                Err(upgradable) => guard = upgradable,
+            }
+
+            guard.lock.state.fetch_or(WRITER_WAITING, Ordering::Relaxed);
+            backoff.wait();
+        }
+    }
+
+    /// Upgrades to writing if no readers hold the lock.
+    ///
+    /// # Returns
+    /// A guard giving exclusive access to the value until dropped, or the upgradable guard back
+    /// if readers hold the lock.
+    pub fn try_upgrade(self) -> Result<RwLockWriteGuard<'a, T, B>, Self> {
+        let lock = self.lock;
+        if lock
+            .try_replace(UPGRADABLE | WRITER_WAITING, WRITER)
+            .is_none()
+        {
+            return Err(self);
+        }
+
+        mem::forget(self);
+        Ok(RwLockWriteGuard { lock })
+    }
+
+    /// Gives up the option to upgrade, keeping the lock for reading.
+    ///
+    /// # Returns
+    /// A guard giving shared access to the value until dropped.
+    pub fn downgrade(self) -> RwLockReadGuard<'a, T, B> {
+        let lock = self.lock;
+        mem::forget(self);
+
+        //
+        // The reader is added before the flag is removed, so no writer can slip in between.
+        //
+
+        lock.state.fetch_add(READER, Ordering::Acquire);
+        lock.release(UPGRADABLE);
+        RwLockReadGuard { lock }
+    }
+}
+
+impl<'a, T: ?Sized, B: Backoff> Deref for RwLockUpgradableGuard<'a, T, B> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //
+        // UNSAFE: The guard holds the lock for reading.
+        //
+
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized, B: Backoff> Drop for RwLockUpgradableGuard<'a, T, B> {
+    fn drop(&mut self) {
+        self.lock.release(UPGRADABLE);
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug, B: Backoff> fmt::Debug for RwLockUpgradableGuard<'a, T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Defines the RAII guard of a RwLock held for writing, releasing it when dropped.
+pub struct RwLockWriteGuard<'a, T: ?Sized, B: Backoff> {
+    /// Holds the held lock.
+    lock: &'a RwLock<T, B>,
+}
+
+/// Implements the functionality unique to `RwLockWriteGuard`.
+impl<'a, T: ?Sized, B: Backoff> RwLockWriteGuard<'a, T, B> {
+    /// Turns the lock into a read lock, letting other readers in.
+    ///
+    /// # Returns
+    /// A guard giving shared access to the value until dropped.
+    pub fn downgrade(self) -> RwLockReadGuard<'a, T, B> {
+        let lock = self.lock;
+        mem::forget(self);
+
+        lock.state.fetch_add(READER, Ordering::Acquire);
+        lock.release(WRITER);
+        RwLockReadGuard { lock }
+    }
+}
+
+impl<'a, T: ?Sized, B: Backoff> Deref for RwLockWriteGuard<'a, T, B> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //
+        // UNSAFE: The guard holds the lock for writing.
+        //
+
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized, B: Backoff> DerefMut for RwLockWriteGuard<'a, T, B> {
+    fn deref_mut(&mut self) -> &mut T {
+        //
+        // UNSAFE: The guard holds the lock for writing.
+        //
+
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized, B: Backoff> Drop for RwLockWriteGuard<'a, T, B> {
+    fn drop(&mut self) {
+        self.lock.release(WRITER);
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug, B: Backoff> fmt::Debug for RwLockWriteGuard<'a, T, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    fn hammer<R: RawLock + Send + Sync + 'static>() {
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 1000;
+
+        let lock: Arc<Lock<R, usize>> = Arc::new(Lock::new(0));
+        let mut handles = Vec::new();
+
+        for _ in 0..THREADS {
+            let lock = lock.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..ROUNDS {
+                    *lock.lock() += 1;
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*lock.lock(), THREADS * ROUNDS);
+    }
+
+    fn exclusive<R: RawLock>() {
+        let lock: Lock<R, u32> = Lock::new(5);
+
+        {
+            let mut guard = lock.lock();
+            assert!(lock.is_locked());
+            assert!(lock.try_lock().is_none());
+            *guard += 1;
+        }
+
+        assert!(!lock.is_locked());
+        assert_eq!(lock.try_lock().map(|guard| *guard), Some(6));
+        assert_eq!(lock.into_inner(), 6);
+    }
+
+    #[test]
+    fn spin_lock() {
+        exclusive::<RawSpinLock>();
+        hammer::<RawSpinLock>();
+    }
+
+    #[test]
+    fn ticket_lock() {
+        exclusive::<RawTicketLock>();
+        hammer::<RawTicketLock>();
+    }
+
+    #[test]
+    fn irq_locks() {
+        exclusive::<Irq<RawSpinLock>>();
+        exclusive::<Irq<RawTicketLock>>();
+        hammer::<Irq<RawTicketLock>>();
+    }
+
+    #[test]
+    fn irq_locks_restore_in_reverse_order() {
+        let outer: IrqSpinLock<()> = IrqSpinLock::new(());
+        let inner: IrqTicketLock<()> = IrqTicketLock::new(());
+
+        //
+        // Gets the state a new section would restore, which is nested while a lock is held.
+        //
+
+        let probe = || unsafe {
+            let state = critical_section::acquire();
+            critical_section::release(state);
+            state
+        };
+
+        let outermost = probe();
+
+        let outer_guard = outer.lock();
+        let inner_guard = inner.lock();
+        drop(inner_guard);
+        assert_ne!(probe(), outermost);
+        drop(outer_guard);
+        assert_eq!(probe(), outermost);
+
+        //
+        // Dropping the outer guard first leaves the section while the inner lock is held.
+        //
+
+        let outer_guard = outer.lock();
+        let inner_guard = inner.lock();
+        drop(outer_guard);
+        assert_eq!(probe(), outermost);
+        assert!(inner.is_locked());
+        drop(inner_guard);
+    }
+
+    #[test]
+    fn ticket_lock_serves_in_order() {
+        let lock: TicketLock<()> = TicketLock::new(());
+        let first = lock.lock();
+
+        let raw = &lock.raw;
+        assert_eq!(raw.next.load(Ordering::Relaxed), 1);
+        assert!(!raw.try_lock());
+        assert_eq!(raw.next.load(Ordering::Relaxed), 1);
+
+        drop(first);
+        assert_eq!(raw.serving.load(Ordering::Relaxed), 1);
+        assert!(lock.try_lock().is_some());
+    }
+
+    #[test]
+    fn debug_shows_free_value() {
+        let lock: SpinLock<u32> = SpinLock::new(3);
+        assert_eq!(std::format!("{:?}", lock), "Lock { data: 3 }");
+
+        let _guard = lock.lock();
+        assert_eq!(std::format!("{:?}", lock), "Lock { data: <locked> }");
+    }
+
+    #[test]
+    fn rw_lock_readers_share() {
+        let lock: RwLock<u32> = RwLock::new(1);
+
+        let first = lock.read();
+        let second = lock.try_read().unwrap();
+        assert_eq!(lock.reader_count(), 2);
+        assert!(lock.try_write().is_none());
+        assert_eq!(*first + *second, 2);
+
+        drop(first);
+        drop(second);
+        let mut writer = lock.try_write().unwrap();
+        *writer = 4;
+        assert!(lock.try_read().is_none());
+        drop(writer);
+        assert_eq!(lock.into_inner(), 4);
+    }
+
+    #[test]
+    fn rw_lock_upgrades_and_downgrades() {
+        let lock: RwLock<u32> = RwLock::new(1);
+
+        let upgradable = lock.upgradable_read();
+        assert!(lock.try_upgradable_read().is_none());
+        let reader = lock.read();
+
+        let upgradable = upgradable.try_upgrade().unwrap_err();
+        drop(reader);
+
+        let mut writer = upgradable.upgrade();
+        *writer += 1;
+        assert!(lock.is_locked_exclusive());
+
+        let reader = writer.downgrade();
+        assert_eq!(*lock.read(), 2);
+        drop(reader);
+
+        let reader = lock.upgradable_read().downgrade();
+        assert!(lock.try_upgradable_read().is_some());
+        drop(reader);
+        assert_eq!(lock.state.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn rw_lock_prefers_writers() {
+        let lock: Arc<RwLock<u32>> = Arc::new(RwLock::new(0));
+        let reader = lock.read();
+
+        let writer = {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.write() += 1)
+        };
+
+        while lock.state.load(Ordering::Relaxed) & WRITER_WAITING == 0 {
+            thread::yield_now();
+        }
+
+        assert!(lock.try_read().is_none());
+        drop(reader);
+
+        writer.join().unwrap();
+        assert_eq!(*lock.read(), 1);
+    }
+
+    #[test]
+    fn rw_lock_hammer() {
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 500;
+
+        let lock: Arc<RwLock<(usize, usize)>> = Arc::new(RwLock::new((0, 0)));
+        let mut handles = Vec::new();
+
+        for index in 0..THREADS {
+            let lock = lock.clone();
+            handles.push(thread::spawn(move || {
+                for round in 0..ROUNDS {
+                    if (index + round) % 3 == 0 {
+                        let mut guard = lock.write();
+                        guard.0 += 1;
+                        guard.1 += 1;
+                    } else if round % 5 == 0 {
+                        let guard = lock.upgradable_read();
+                        assert_eq!(guard.0, guard.1);
+                        let mut guard = guard.upgrade();
+                        guard.0 += 1;
+                        guard.1 += 1;
+                    } else {
+                        let guard = lock.read();
+                        assert_eq!(guard.0, guard.1);
+                    }
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let guard = lock.read();
+        assert_eq!(guard.0, guard.1);
+    }
+}
@@ -9,4 +9,14 @@ pub mod atomic;
 
 pub mod lock;
 
-pub mod semaphore;
\ No newline at end of file
+pub mod semaphore;
+
+pub mod pi_mutex;
+
+pub mod ceiling_lock;
+
+pub mod event_flags;
+
+pub mod once;
+
+pub mod seq_lock;
\ No newline at end of file
@@ -0,0 +1,574 @@
+//! # Once
+//!
+//! `once` contains primitives for one-time initialization that can be used in `static` items:
+//! * `Once`, which runs a closure exactly once.
+//! * `OnceCell`, which holds a value written at most once.
+//! * `Lazy`, which holds a value built by a closure on first access, e.g. a global allocator
+//!   whose backing memory is only known at run time.
+//!
+//! Once initialized, every access is a single load with `Acquire` ordering. The initialization
+//! itself runs in a `critical_section`, so an interrupt handler cannot observe or race a partially
+//! initialized value. Initialization therefore runs with interrupts disabled on single core
+//! targets, and should be short. Whether other cores are excluded depends on the critical section
+//! backend.
+//!
+//! If the initializing closure panics, the primitive stays uninitialized and the next access
+//! tries again, except for a `Lazy`, whose closure was consumed. Accessing a primitive from within
+//! its own initializing closure panics.
+//!
+//! # Examples
+//! ```
+//! use raztos_util::sync::once::{Lazy, OnceCell};
+//! # use raztos_util::sync::critical_section::{Backend, RestoreState};
+//! # struct Host;
+//! # unsafe impl Backend for Host {
+//! #     unsafe fn acquire() -> RestoreState { RestoreState::from_raw(0) }
+//! #     unsafe fn release(_state: RestoreState) {}
+//! # }
+//! # raztos_util::set_critical_section_backend!(Host);
+//!
+//! static TICK_RATE: OnceCell<u32> = OnceCell::new();
+//! static PERIOD_US: Lazy<u32> = Lazy::new(|| 1_000_000 / *TICK_RATE.get().unwrap());
+//!
+//! TICK_RATE.set(1000).unwrap();
+//! assert_eq!(TICK_RATE.set(10), Err(10));
+//!
+//! assert_eq!(*PERIOD_US, 1000);
+//! ```
+
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::critical_section;
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+
+//
+// Constants
+//
+
+/// Defines the state of a `Once` that has not run.
+const INCOMPLETE: usize = 0;
+
+/// Defines the state of a `Once` whose closure is running.
+const RUNNING: usize = 1;
+
+/// Defines the state of a `Once` whose closure has returned.
+const COMPLETE: usize = 2;
+
+/// Defines the structure for a one-time initialization.
+///
+/// # Examples
+/// ```
+/// use raztos_util::sync::once::Once;
+/// # use raztos_util::sync::critical_section::{Backend, RestoreState};
+/// # struct Host;
+/// # unsafe impl Backend for Host {
+/// #     unsafe fn acquire() -> RestoreState { RestoreState::from_raw(0) }
+/// #     unsafe fn release(_state: RestoreState) {}
+/// # }
+/// # raztos_util::set_critical_section_backend!(Host);
+///
+/// static INIT: Once = Once::new();
+///
+/// let mut runs = 0;
+/// INIT.call_once(|| runs += 1);
+/// INIT.call_once(|| runs += 1);
+///
+/// assert!(INIT.is_completed());
+/// assert_eq!(runs, 1);
+/// ```
+pub struct Once {
+    /// Holds the state of the initialization.
+    state: AtomicUsize,
+}
+
+/// Implements the functionality unique to `Once`.
+impl Once {
+    /// Creates a new Once that has not run.
+    ///
+    /// # Returns
+    /// A Once.
+    pub const fn new() -> Self {
+        Once {
+            state: AtomicUsize::new(INCOMPLETE),
+        }
+    }
+
+    /// Runs a closure if no closure has completed on this Once yet.
+    ///
+    /// When the call returns, a closure has completed, either this one or an earlier one.
+    ///
+    /// # Arguments
+    /// f - Provides the closure.
+    ///
+    /// # Panics
+    /// Panics if called from within the closure of this Once, and passes on panics of the
+    /// closure, leaving the Once to run again.
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.is_completed() {
+            return;
+        }
+
+        self.call_once_slow(f);
+    }
+
+    /// Determines whether or not a closure has completed on this Once.
+    ///
+    /// # Returns
+    /// `true` if completed, `false` otherwise.
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    /// Runs a closure in a critical section, if no closure has completed yet.
+    ///
+    /// # Arguments
+    /// f - Provides the closure.
+    #[cold]
+    fn call_once_slow<F: FnOnce()>(&self, f: F) {
+        //
+        // Resets the state when the closure panics, so the next call runs again.
+        //
+
+        struct Reset<'a>(&'a AtomicUsize);
+
+        impl<'a> Drop for Reset<'a> {
+            fn drop(&mut self) {
+                self.0.store(INCOMPLETE, Ordering::Relaxed);
+            }
+        }
+
+        critical_section::critical_section(|_cs| {
+            match self.state.load(Ordering::Acquire) {
+                COMPLETE => return,
+                RUNNING => panic!("the Once is accessed from within its own closure"),
+                _ => {}
+            }
+
+            self.state.store(RUNNING, Ordering::Relaxed);
+
+            let reset = Reset(&self.state);
+            f();
+            core::mem::forget(reset);
+
+            self.state.store(COMPLETE, Ordering::Release);
+        });
+    }
+}
+
+/// Defines the default value for Once.
+impl Default for Once {
+    fn default() -> Self {
+        Once::new()
+    }
+}
+
+/// Formats a Once with whether or not it completed.
+impl fmt::Debug for Once {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once")
+            .field("completed", &self.is_completed())
+            .finish()
+    }
+}
+
+/// Defines the structure for a cell written at most once.
+pub struct OnceCell<T> {
+    /// Holds the initialization of the value.
+    once: Once,
+
+    /// Holds the value, initialized once `once` has completed.
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+//
+// UNSAFE: The value is only written once, before `once` completes, and only shared afterwards.
+//
+
+unsafe impl<T: Send> Send for OnceCell<T> {}
+
+unsafe impl<T: Send + Sync> Sync for OnceCell<T> {}
+
+/// Implements the functionality unique to `OnceCell`.
+impl<T> OnceCell<T> {
+    /// Creates a new, empty OnceCell.
+    ///
+    /// # Returns
+    /// A OnceCell.
+    pub const fn new() -> Self {
+        OnceCell {
+            once: Once::new(),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Gets the value.
+    ///
+    /// # Returns
+    /// The value, or `None` if the cell is empty.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        if !self.once.is_completed() {
+            return None;
+        }
+
+        //
+        // UNSAFE: The value was written before the Once completed.
+        //
+
+        Some(unsafe { (*self.value.get()).assume_init_ref() })
+    }
+
+    /// Gets the value mutably, since the borrow guarantees no shared access exists.
+    ///
+    /// # Returns
+    /// The value, or `None` if the cell is empty.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        if !self.once.is_completed() {
+            return None;
+        }
+
+        //
+        // UNSAFE: The value was written before the Once completed.
+        //
+
+        Some(unsafe { self.value.get_mut().assume_init_mut() })
+    }
+
+    /// Writes the value if the cell is empty.
+    ///
+    /// # Arguments
+    /// value - Provides the value.
+    ///
+    /// # Returns
+    /// `Ok` if written, or the value back if the cell was already written.
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        self.get_or_init(|| value.take().unwrap());
+
+        match value {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }
+    }
+
+    /// Gets the value, writing it with a closure if the cell is empty.
+    ///
+    /// # Arguments
+    /// f - Provides the closure building the value.
+    ///
+    /// # Returns
+    /// The value.
+    ///
+    /// # Panics
+    /// Panics if called from within the closure initializing this cell, and passes on panics of
+    /// the closure, leaving the cell empty.
+    pub fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+        self.once.call_once(|| {
+            //
+            // UNSAFE: The Once gives the closure exclusive access to the empty value.
+            //
+
+            unsafe { (*self.value.get()).write(f()) };
+        });
+
+        //
+        // UNSAFE: The value was written before the Once completed.
+        //
+
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+
+    /// Consumes the cell, returning the value.
+    ///
+    /// # Returns
+    /// The value, or `None` if the cell is empty.
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+
+    /// Takes the value out of the cell, leaving it empty.
+    ///
+    /// # Returns
+    /// The value, or `None` if the cell is empty.
+    pub fn take(&mut self) -> Option<T> {
+        if !self.once.is_completed() {
+            return None;
+        }
+
+        self.once = Once::new();
+
+        //
+        // UNSAFE: The value was written before the Once completed, and the cell is now empty, so
+        //         it is not read or dropped again.
+        //
+
+        Some(unsafe { self.value.get_mut().assume_init_read() })
+    }
+}
+
+impl<T> Drop for OnceCell<T> {
+    fn drop(&mut self) {
+        drop(self.take());
+    }
+}
+
+/// Defines the default value for OnceCell.
+impl<T> Default for OnceCell<T> {
+    fn default() -> Self {
+        OnceCell::new()
+    }
+}
+
+/// Formats a OnceCell with its value, if written.
+impl<T: fmt::Debug> fmt::Debug for OnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("OnceCell").field(value).finish(),
+            None => f
+                .debug_tuple("OnceCell")
+                .field(&format_args!("<uninit>"))
+                .finish(),
+        }
+    }
+}
+
+impl<T> From<T> for OnceCell<T> {
+    fn from(value: T) -> Self {
+        let cell = OnceCell::new();
+        let _ = cell.set(value);
+        cell
+    }
+}
+
+/// Defines the structure for a value built by a closure on first access.
+pub struct Lazy<T, F = fn() -> T> {
+    /// Holds the value once built.
+    cell: OnceCell<T>,
+
+    /// Holds the closure until it has run.
+    init: Cell<Option<F>>,
+}
+
+//
+// UNSAFE: The closure is only taken while initializing the cell, which is exclusive.
+//
+
+unsafe impl<T: Send + Sync, F: Send> Sync for Lazy<T, F> {}
+
+/// Implements the functionality unique to `Lazy`.
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    /// Creates a new Lazy.
+    ///
+    /// # Arguments
+    /// init - Provides the closure building the value.
+    ///
+    /// # Returns
+    /// A Lazy.
+    pub const fn new(init: F) -> Self {
+        Lazy {
+            cell: OnceCell::new(),
+            init: Cell::new(Some(init)),
+        }
+    }
+
+    /// Gets the value, building it if this is the first access.
+    ///
+    /// # Arguments
+    /// this - Provides the Lazy.
+    ///
+    /// # Returns
+    /// The value.
+    ///
+    /// # Panics
+    /// Panics if the closure panicked on an earlier access, or if called from within the closure.
+    pub fn force(this: &Self) -> &T {
+        this.cell.get_or_init(|| match this.init.take() {
+            Some(init) => init(),
+            None => panic!("the Lazy was poisoned by a panic while building its value"),
+        })
+    }
+
+    /// Gets the value if it is built.
+    ///
+    /// # Arguments
+    /// this - Provides the Lazy.
+    ///
+    /// # Returns
+    /// The value, or `None` if it has not been accessed yet.
+    pub fn get(this: &Self) -> Option<&T> {
+        this.cell.get()
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+/// Defines the default value for Lazy.
+impl<T: Default> Default for Lazy<T> {
+    fn default() -> Self {
+        Lazy::new(T::default)
+    }
+}
+
+/// Formats a Lazy with its value, if built.
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cell.get() {
+            Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+            None => f
+                .debug_tuple("Lazy")
+                .field(&format_args!("<uninit>"))
+                .finish(),
+        }
+    }
+}
+
+/// Implements a global allocator that is built on its first allocation, so allocators whose
+/// backing memory is only known at run time can be used with `#[global_allocator]`.
+///
+/// # Examples
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: Lazy<MonotonicAllocator> =
+///     Lazy::new(|| MonotonicAllocator::new(unsafe { &mut HEAP }).unwrap());
+/// ```
+unsafe impl<T: GlobalAlloc, F: FnOnce() -> T> GlobalAlloc for Lazy<T, F> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Lazy::force(self).alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        Lazy::force(self).dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Lazy::force(self).alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Lazy::force(self).realloc(ptr, layout, new_size)
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+    use std::sync::atomic::AtomicUsize as StdAtomicUsize;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn once_runs_exactly_once() {
+        static RUNS: StdAtomicUsize = StdAtomicUsize::new(0);
+        let once = Arc::new(Once::new());
+        let mut handles = Vec::new();
+
+        for _ in 0..4 {
+            let once = once.clone();
+            handles.push(thread::spawn(move || {
+                once.call_once(|| {
+                    RUNS.fetch_add(1, Ordering::Relaxed);
+                });
+                assert!(once.is_completed());
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(RUNS.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn panicking_closure_runs_again() {
+        let once = Once::new();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| once.call_once(|| panic!())));
+        assert!(result.is_err());
+        assert!(!once.is_completed());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            once.call_once(|| once.call_once(|| {}));
+        }));
+        assert!(result.is_err());
+
+        once.call_once(|| {});
+        assert!(once.is_completed());
+    }
+
+    #[test]
+    fn once_cell_is_written_once() {
+        let mut cell = OnceCell::new();
+        assert_eq!(cell.get(), None);
+        assert_eq!(format!("{:?}", cell), "OnceCell(<uninit>)");
+
+        assert_eq!(cell.set(1), Ok(()));
+        assert_eq!(cell.set(2), Err(2));
+        assert_eq!(*cell.get_or_init(|| 3), 1);
+
+        *cell.get_mut().unwrap() += 1;
+        assert_eq!(format!("{:?}", cell), "OnceCell(2)");
+        assert_eq!(cell.take(), Some(2));
+        assert_eq!(cell.get(), None);
+        assert_eq!(OnceCell::from(4).into_inner(), Some(4));
+    }
+
+    #[test]
+    fn once_cell_drops_value() {
+        let value = Rc::new(());
+        let cell = OnceCell::new();
+        cell.set(value.clone()).unwrap();
+        assert_eq!(Rc::strong_count(&value), 2);
+
+        drop(cell);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn lazy_builds_on_first_access() {
+        static LAZY: Lazy<Vec<usize>> = Lazy::new(|| (0..4).collect());
+
+        assert!(Lazy::get(&LAZY).is_none());
+        assert_eq!(LAZY.len(), 4);
+        assert_eq!(Lazy::get(&LAZY).map(|value| value[3]), Some(3));
+
+        let lazy: Lazy<u32> = Lazy::default();
+        assert_eq!(format!("{:?}", lazy), "Lazy(<uninit>)");
+        assert_eq!(*lazy, 0);
+        assert_eq!(format!("{:?}", lazy), "Lazy(0)");
+    }
+
+    #[test]
+    fn lazy_allocates_through_value() {
+        use std::alloc::System;
+
+        let allocator: Lazy<System> = Lazy::new(|| System);
+        let layout = Layout::new::<u64>();
+
+        unsafe {
+            let ptr = allocator.alloc_zeroed(layout);
+            assert!(!ptr.is_null());
+            assert_eq!(*(ptr as *const u64), 0);
+            allocator.dealloc(ptr, layout);
+        }
+
+        assert!(Lazy::get(&allocator).is_some());
+    }
+}
@@ -0,0 +1,723 @@
+//! # Priority Inheritance Mutex
+//!
+//! `pi_mutex` contains a blocking mutex for real-time tasks, which avoids unbounded priority
+//! inversion by priority inheritance.
+//!
+//! Priority inversion happens when a high priority task waits for a mutex held by a low priority
+//! task, which is in turn preempted by medium priority tasks, so the high priority task waits on
+//! all of them. With priority inheritance, the owner of a `PiMutex` is boosted to the priority of
+//! its highest priority waiter until it releases the mutex, so only the critical region of the
+//! owner delays the waiter.
+//!
+//! The mutex does not know how tasks are scheduled. It calls into a `Scheduler`, implemented by
+//! the kernel, to identify the current task, change priorities, and block and wake tasks. Waiters
+//! are kept in a `BitmapPriorityQueue`, so the highest priority waiter is found with a single
+//! bitfield lookup, and waiters of equal priority are served in arrival order. The mutex is
+//! handed directly to the waiter when released, so a task of lower priority cannot take it in
+//! between.
+//!
+//! The state of the mutex is only accessed in a `critical_section`, which also covers every call
+//! into the scheduler.
+//!
+//! # Nesting
+//!
+//! An owner is restored to the priority it had when it took the mutex. This is correct as long
+//! as tasks release nested mutexes in reverse order of taking them. Boosts are not propagated
+//! along chains of blocked owners, which a scheduler can do in `boost_priority` if needed.
+
+use crate::collections::fast_bitfield::{FastBitField, SmallBitField};
+use crate::collections::priority_queue::BitmapPriorityQueue;
+use crate::sync::critical_section::{self, CriticalSection};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+/// Defines the interface of the kernel scheduler used by a `PiMutex`.
+///
+/// Priorities are numbers below the `LEVELS` of the mutex, with larger numbers being more urgent.
+/// Every method but `current_task` is called in a critical section.
+///
+/// # Examples
+/// A kernel could implement the scheduler as:
+/// ```ignore
+/// impl Scheduler for Kernel {
+///     type Task = TaskId;
+///
+///     fn current_task(&self) -> TaskId {
+///         self.running()
+///     }
+///
+///     fn priority(&self, task: TaskId) -> usize {
+///         self.tcb(task).priority
+///     }
+///
+///     fn boost_priority(&self, cs: CriticalSection<'_>, task: TaskId, priority: usize) {
+///         self.set_priority(cs, task, priority);
+///     }
+///
+///     fn restore_priority(&self, cs: CriticalSection<'_>, task: TaskId, priority: usize) {
+///         self.set_priority(cs, task, priority);
+///     }
+///
+///     fn block_on(&self, cs: CriticalSection<'_>, task: TaskId) {
+///         self.make_blocked(cs, task);
+///         self.pend_context_switch();
+///     }
+///
+///     fn wake(&self, cs: CriticalSection<'_>, task: TaskId) {
+///         self.make_ready(cs, task);
+///     }
+/// }
+/// ```
+pub trait Scheduler {
+    /// Defines the handle identifying a task.
+    type Task: Copy + Eq;
+
+    /// Gets the task that is running.
+    ///
+    /// # Returns
+    /// The current task.
+    fn current_task(&self) -> Self::Task;
+
+    /// Gets the priority a task is running at, including any boost.
+    ///
+    /// # Arguments
+    /// task - Provides the task.
+    ///
+    /// # Returns
+    /// The priority.
+    fn priority(&self, task: Self::Task) -> usize;
+
+    /// Raises the priority of a task, since a task of that priority waits for it.
+    ///
+    /// # Arguments
+    /// cs - Provides the critical section the call is made in.
+    /// task - Provides the task.
+    /// priority - Provides the new priority, higher than the current priority of the task.
+    fn boost_priority(&self, cs: CriticalSection<'_>, task: Self::Task, priority: usize);
+
+    /// Lowers the priority of a task back to the priority before it was boosted.
+    ///
+    /// # Arguments
+    /// cs - Provides the critical section the call is made in.
+    /// task - Provides the task.
+    /// priority - Provides the priority to restore.
+    fn restore_priority(&self, cs: CriticalSection<'_>, task: Self::Task, priority: usize);
+
+    /// Blocks the current task until it is woken.
+    ///
+    /// The mutex checks whether it was handed the task after every return, so the task may be
+    /// switched out when the critical section ends rather than within the call, and returning
+    /// early is allowed.
+    ///
+    /// # Arguments
+    /// cs - Provides the critical section the call is made in.
+    /// task - Provides the current task.
+    fn block_on(&self, cs: CriticalSection<'_>, task: Self::Task);
+
+    /// Wakes a task blocked with `block_on`.
+    ///
+    /// # Arguments
+    /// cs - Provides the critical section the call is made in.
+    /// task - Provides the task.
+    fn wake(&self, cs: CriticalSection<'_>, task: Self::Task);
+}
+
+/// Defines the ownership and waiters of a PiMutex.
+struct State<S: Scheduler, B: FastBitField, const LEVELS: usize, const WAITERS: usize> {
+    /// Holds the task owning the mutex, if any.
+    owner: Option<S::Task>,
+
+    /// Holds the priority of the owner when it took the mutex.
+    owner_priority: usize,
+
+    /// Holds the tasks waiting for the mutex, by priority.
+    waiters: BitmapPriorityQueue<S::Task, LEVELS, WAITERS, B>,
+}
+
+/// Defines the structure for a mutex with priority inheritance.
+///
+/// Up to `WAITERS` tasks can wait for the mutex at once, at priorities below `LEVELS`. `B` is the
+/// fast bitfield ordering the waiters and must hold at least `LEVELS` bits.
+///
+/// # Examples
+/// ```
+/// use raztos_util::sync::critical_section::CriticalSection;
+/// use raztos_util::sync::pi_mutex::{PiMutex, Scheduler};
+/// # use raztos_util::sync::critical_section::{Backend, RestoreState};
+/// # struct Host;
+/// # unsafe impl Backend for Host {
+/// #     unsafe fn acquire() -> RestoreState { RestoreState::from_raw(0) }
+/// #     unsafe fn release(_state: RestoreState) {}
+/// # }
+/// # raztos_util::set_critical_section_backend!(Host);
+///
+/// // A scheduler running a single task.
+/// struct Kernel;
+///
+/// impl Scheduler for Kernel {
+///     type Task = usize;
+///
+///     fn current_task(&self) -> usize { 0 }
+///     fn priority(&self, _task: usize) -> usize { 3 }
+///     fn boost_priority(&self, _cs: CriticalSection<'_>, _task: usize, _priority: usize) {}
+///     fn restore_priority(&self, _cs: CriticalSection<'_>, _task: usize, _priority: usize) {}
+///     fn block_on(&self, _cs: CriticalSection<'_>, _task: usize) {}
+///     fn wake(&self, _cs: CriticalSection<'_>, _task: usize) {}
+/// }
+///
+/// let mutex: PiMutex<Kernel, u32, 8, 4> = PiMutex::new(Kernel, 0);
+///
+/// *mutex.lock() += 1;
+///
+/// let guard = mutex.lock();
+/// assert!(mutex.try_lock().is_none());
+/// assert_eq!(mutex.owner(), Some(0));
+/// assert_eq!(*guard, 1);
+/// ```
+pub struct PiMutex<S, T, const LEVELS: usize, const WAITERS: usize, B = SmallBitField>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized,
+{
+    /// Holds the scheduler of the tasks using the mutex.
+    scheduler: S,
+
+    /// Holds the state, only accessed in a critical section.
+    state: UnsafeCell<State<S, B, LEVELS, WAITERS>>,
+
+    /// Holds the protected value.
+    data: UnsafeCell<T>,
+}
+
+//
+// UNSAFE: The state is only accessed in a critical section, and the value only by the owner.
+//
+
+unsafe impl<S, T, B, const LEVELS: usize, const WAITERS: usize> Send
+    for PiMutex<S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler + Send,
+    S::Task: Send,
+    B: FastBitField + Send,
+    T: ?Sized + Send,
+{
+}
+
+unsafe impl<S, T, B, const LEVELS: usize, const WAITERS: usize> Sync
+    for PiMutex<S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler + Sync,
+    S::Task: Send,
+    B: FastBitField + Send,
+    T: ?Sized + Send,
+{
+}
+
+/// Implements the construction of `PiMutex`.
+impl<S, T, B, const LEVELS: usize, const WAITERS: usize> PiMutex<S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    B: FastBitField,
+{
+    /// Creates a new, unlocked PiMutex.
+    ///
+    /// # Arguments
+    /// scheduler - Provides the scheduler of the tasks using the mutex.
+    /// value - Provides the value to protect.
+    ///
+    /// # Returns
+    /// A PiMutex.
+    ///
+    /// # Panics
+    /// Panics if `LEVELS` is larger than the number of bits in `B`.
+    pub fn new(scheduler: S, value: T) -> Self {
+        PiMutex {
+            scheduler,
+            state: UnsafeCell::new(State {
+                owner: None,
+                owner_priority: 0,
+                waiters: BitmapPriorityQueue::new(),
+            }),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the protected value.
+    ///
+    /// # Returns
+    /// The value.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Implements the functionality unique to `PiMutex`.
+impl<S, T, B, const LEVELS: usize, const WAITERS: usize> PiMutex<S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized,
+{
+    /// Takes the mutex, blocking the current task until it is handed the mutex.
+    ///
+    /// While the task waits, the owner runs at least at the priority of the task.
+    ///
+    /// # Returns
+    /// A guard giving access to the value until dropped.
+    ///
+    /// # Panics
+    /// Panics if the current task already owns the mutex, if its priority is not below `LEVELS`,
+    /// or if `WAITERS` tasks already wait for the mutex.
+    pub fn lock(&self) -> PiMutexGuard<'_, S, T, LEVELS, WAITERS, B> {
+        let current = self.scheduler.current_task();
+        let mut queued = false;
+
+        loop {
+            let acquired = critical_section::critical_section(|cs| {
+                //
+                // UNSAFE: The state is only accessed in a critical section.
+                //
+
+                let state = unsafe { &mut *self.state.get() };
+
+                match state.owner {
+                    None => {
+                        state.owner = Some(current);
+                        state.owner_priority = self.scheduler.priority(current);
+                        true
+                    }
+                    Some(owner) if owner == current => {
+                        assert!(queued, "the current task already owns the mutex");
+                        true
+                    }
+                    Some(owner) => {
+                        if !queued {
+                            let priority = self.scheduler.priority(current);
+                            assert!(priority < LEVELS, "the priority must be below `LEVELS`");
+
+                            if state.waiters.push(priority, current).is_err() {
+                                panic!("too many tasks wait for the mutex");
+                            }
+
+                            queued = true;
+
+                            if priority > self.scheduler.priority(owner) {
+                                self.scheduler.boost_priority(cs, owner, priority);
+                            }
+                        }
+
+                        self.scheduler.block_on(cs, current);
+                        false
+                    }
+                }
+            });
+
+            if acquired {
+                return PiMutexGuard {
+                    mutex: self,
+                    _not_send: PhantomData,
+                };
+            }
+        }
+    }
+
+    /// Takes the mutex if it is free.
+    ///
+    /// # Returns
+    /// A guard giving access to the value until dropped, or `None` if the mutex is owned.
+    pub fn try_lock(&self) -> Option<PiMutexGuard<'_, S, T, LEVELS, WAITERS, B>> {
+        let current = self.scheduler.current_task();
+
+        critical_section::critical_section(|_cs| {
+            //
+            // UNSAFE: The state is only accessed in a critical section.
+            //
+
+            let state = unsafe { &mut *self.state.get() };
+            if state.owner.is_some() {
+                return None;
+            }
+
+            state.owner = Some(current);
+            state.owner_priority = self.scheduler.priority(current);
+
+            Some(PiMutexGuard {
+                mutex: self,
+                _not_send: PhantomData,
+            })
+        })
+    }
+
+    /// Gets the task owning the mutex.
+    ///
+    /// # Returns
+    /// The owner, or `None` if the mutex is free.
+    pub fn owner(&self) -> Option<S::Task> {
+        //
+        // UNSAFE: The state is only accessed in a critical section.
+        //
+
+        critical_section::critical_section(|_cs| unsafe { (*self.state.get()).owner })
+    }
+
+    /// Determines whether or not the mutex is owned.
+    ///
+    /// # Returns
+    /// `true` if owned, `false` otherwise.
+    pub fn is_locked(&self) -> bool {
+        self.owner().is_some()
+    }
+
+    /// Gets the number of tasks waiting for the mutex.
+    ///
+    /// # Returns
+    /// The number of waiters.
+    pub fn waiters(&self) -> usize {
+        //
+        // UNSAFE: The state is only accessed in a critical section.
+        //
+
+        critical_section::critical_section(|_cs| unsafe { (*self.state.get()).waiters.len() })
+    }
+
+    /// Gets the scheduler of the mutex.
+    ///
+    /// # Returns
+    /// The scheduler.
+    pub fn scheduler(&self) -> &S {
+        &self.scheduler
+    }
+
+    /// Gets the value without locking, since the borrow guarantees no guards exist.
+    ///
+    /// # Returns
+    /// A mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Releases the mutex, restoring the priority of the owner and handing the mutex to the
+    /// highest priority waiter.
+    fn unlock(&self) {
+        critical_section::critical_section(|cs| {
+            //
+            // UNSAFE: The state is only accessed in a critical section.
+            //
+
+            let state = unsafe { &mut *self.state.get() };
+
+            if let Some(owner) = state.owner.take() {
+                if self.scheduler.priority(owner) != state.owner_priority {
+                    self.scheduler
+                        .restore_priority(cs, owner, state.owner_priority);
+                }
+            }
+
+            if let Some(next) = state.waiters.pop_highest() {
+                state.owner = Some(next);
+                state.owner_priority = self.scheduler.priority(next);
+                self.scheduler.wake(cs, next);
+            }
+        });
+    }
+}
+
+/// Formats a PiMutex with its owner.
+impl<S, T, B, const LEVELS: usize, const WAITERS: usize> fmt::Debug
+    for PiMutex<S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    S::Task: fmt::Debug,
+    B: FastBitField,
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PiMutex")
+            .field("owner", &self.owner())
+            .field("waiters", &self.waiters())
+            .finish()
+    }
+}
+
+/// Defines the RAII guard of an owned PiMutex, releasing it when dropped.
+///
+/// The guard cannot be sent to another thread, since the mutex must be released by its owner.
+pub struct PiMutexGuard<'a, S, T, const LEVELS: usize, const WAITERS: usize, B = SmallBitField>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized,
+{
+    /// Holds the owned mutex.
+    mutex: &'a PiMutex<S, T, LEVELS, WAITERS, B>,
+
+    /// Prevents the guard from being sent to another thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+//
+// UNSAFE: A shared guard only gives shared access to the value.
+//
+
+unsafe impl<'a, S, T, B, const LEVELS: usize, const WAITERS: usize> Sync
+    for PiMutexGuard<'a, S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler + Sync,
+    B: FastBitField,
+    T: ?Sized + Sync,
+{
+}
+
+impl<'a, S, T, B, const LEVELS: usize, const WAITERS: usize> Deref
+    for PiMutexGuard<'a, S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //
+        // UNSAFE: The guard owns the mutex.
+        //
+
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, S, T, B, const LEVELS: usize, const WAITERS: usize> DerefMut
+    for PiMutexGuard<'a, S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        //
+        // UNSAFE: The guard owns the mutex.
+        //
+
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<'a, S, T, B, const LEVELS: usize, const WAITERS: usize> Drop
+    for PiMutexGuard<'a, S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
+
+impl<'a, S, T, B, const LEVELS: usize, const WAITERS: usize> fmt::Debug
+    for PiMutexGuard<'a, S, T, LEVELS, WAITERS, B>
+where
+    S: Scheduler,
+    B: FastBitField,
+    T: ?Sized + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        /// Holds the task identifier of the thread.
+        static TASK: Cell<usize> = const { Cell::new(0) };
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Event {
+        Boost(usize, usize),
+        Restore(usize, usize),
+        Block(usize),
+        Wake(usize),
+    }
+
+    /// A scheduler running each task on its own thread, where blocking yields the thread.
+    struct Threads {
+        priorities: Mutex<[usize; 4]>,
+        events: Mutex<Vec<Event>>,
+    }
+
+    impl Threads {
+        fn new(priorities: [usize; 4]) -> Self {
+            Threads {
+                priorities: Mutex::new(priorities),
+                events: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn events(&self) -> Vec<Event> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl Scheduler for Threads {
+        type Task = usize;
+
+        fn current_task(&self) -> usize {
+            TASK.with(|task| task.get())
+        }
+
+        fn priority(&self, task: usize) -> usize {
+            self.priorities.lock().unwrap()[task]
+        }
+
+        fn boost_priority(&self, _cs: CriticalSection<'_>, task: usize, priority: usize) {
+            self.priorities.lock().unwrap()[task] = priority;
+            self.events
+                .lock()
+                .unwrap()
+                .push(Event::Boost(task, priority));
+        }
+
+        fn restore_priority(&self, _cs: CriticalSection<'_>, task: usize, priority: usize) {
+            self.priorities.lock().unwrap()[task] = priority;
+            self.events
+                .lock()
+                .unwrap()
+                .push(Event::Restore(task, priority));
+        }
+
+        fn block_on(&self, _cs: CriticalSection<'_>, task: usize) {
+            let mut events = self.events.lock().unwrap();
+            if events.last() != Some(&Event::Block(task)) {
+                events.push(Event::Block(task));
+            }
+
+            thread::yield_now();
+        }
+
+        fn wake(&self, _cs: CriticalSection<'_>, task: usize) {
+            self.events.lock().unwrap().push(Event::Wake(task));
+        }
+    }
+
+    type TestMutex = PiMutex<Threads, usize, 8, 4>;
+
+    #[test]
+    fn lock_and_try_lock() {
+        let mut mutex = TestMutex::new(Threads::new([2, 0, 0, 0]), 5);
+
+        {
+            let mut guard = mutex.lock();
+            *guard += 1;
+            assert!(mutex.try_lock().is_none());
+            assert_eq!(mutex.owner(), Some(0));
+        }
+
+        assert!(!mutex.is_locked());
+        assert_eq!(*mutex.try_lock().unwrap(), 6);
+        *mutex.get_mut() += 1;
+        assert!(mutex.scheduler().events().is_empty());
+        assert_eq!(mutex.into_inner(), 7);
+    }
+
+    #[test]
+    fn owner_inherits_priority_of_waiter() {
+        let mutex = Arc::new(TestMutex::new(Threads::new([1, 5, 3, 0]), 0));
+        let guard = mutex.lock();
+
+        let mut handles = Vec::new();
+        for task in [2, 1] {
+            let waiter = mutex.clone();
+            handles.push(thread::spawn(move || {
+                TASK.with(|current| current.set(task));
+                *waiter.lock() += task;
+            }));
+
+            while mutex.waiters() < handles.len() {
+                thread::yield_now();
+            }
+        }
+
+        assert_eq!(mutex.scheduler().priority(0), 5);
+        drop(guard);
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*mutex.lock(), 3);
+
+        //
+        // The highest priority waiter is handed the mutex first, although it arrived last.
+        //
+
+        let events: Vec<Event> = mutex
+            .scheduler()
+            .events()
+            .into_iter()
+            .filter(|event| !matches!(event, Event::Block(_)))
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                Event::Boost(0, 3),
+                Event::Boost(0, 5),
+                Event::Restore(0, 1),
+                Event::Wake(1),
+                Event::Wake(2),
+            ]
+        );
+        assert_eq!(mutex.scheduler().priority(0), 1);
+    }
+
+    #[test]
+    fn tasks_exclude_each_other() {
+        const ROUNDS: usize = 200;
+
+        let mutex = Arc::new(TestMutex::new(Threads::new([0, 1, 2, 3]), 0));
+        let mut handles = Vec::new();
+
+        for task in 0..4 {
+            let mutex = mutex.clone();
+            handles.push(thread::spawn(move || {
+                TASK.with(|current| current.set(task));
+                for _ in 0..ROUNDS {
+                    let mut guard = mutex.lock();
+                    let value = *guard;
+                    thread::yield_now();
+                    *guard = value + 1;
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*mutex.lock(), 4 * ROUNDS);
+        assert_eq!(mutex.waiters(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn relocking_panics() {
+        let mutex = TestMutex::new(Threads::new([0; 4]), 0);
+        let _guard = mutex.lock();
+        let _again = mutex.lock();
+    }
+}
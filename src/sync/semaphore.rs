@@ -0,0 +1,410 @@
+//! # Semaphore
+//!
+//! `semaphore` contains a counting semaphore, holding up to a maximum number of permits that tasks
+//! acquire and release, e.g. to count free buffers or pending events.
+//!
+//! A binary semaphore, created with `Semaphore::binary`, holds at most a single permit. It
+//! signals a single pending event, e.g. an interrupt handler releasing it to wake a task, and
+//! releasing it while the permit is available fails rather than counting the event twice.
+//!
+//! Taking and returning permits is lock-free. What a task does while no permit is available is
+//! decided by the `WaitStrategy` of the semaphore: `SpinWait` busy-waits, while a kernel can
+//! provide a strategy that blocks the task in its scheduler and wakes it when permits are
+//! released.
+//!
+//! # Examples
+//! ```
+//! use raztos_util::sync::semaphore::Semaphore;
+//!
+//! static FREE_BUFFERS: Semaphore = Semaphore::new(2, 2);
+//!
+//! FREE_BUFFERS.acquire();
+//! assert!(FREE_BUFFERS.try_acquire());
+//! assert!(!FREE_BUFFERS.try_acquire());
+//!
+//! FREE_BUFFERS.release(2).unwrap();
+//! assert_eq!(FREE_BUFFERS.available(), 2);
+//! ```
+
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::sync::lock::{Backoff, Spin};
+
+/// Defines how a task waits for a synchronization primitive, such as for permits of a semaphore.
+///
+/// # Examples
+/// A kernel blocking tasks in its scheduler could implement the strategy as:
+/// ```ignore
+/// struct KernelWait {
+///     queue: WaitQueue,
+/// }
+///
+/// impl WaitStrategy for KernelWait {
+///     fn wait<F: Fn() -> bool>(&self, ready: F) {
+///         critical_section(|cs| {
+///             if !ready() {
+///                 self.queue.block_current_task(cs);
+///             }
+///         });
+///     }
+///
+///     fn notify(&self, count: usize) {
+///         critical_section(|cs| self.queue.wake(cs, count));
+///     }
+/// }
+/// ```
+pub trait WaitStrategy {
+    /// Waits until the condition the task waits for may be met, such as permits being available.
+    ///
+    /// The semaphore tries again after every return, so returning early is allowed. To avoid
+    /// missing a `notify`, a blocking implementation should check `ready` in the same critical
+    /// section that blocks the task.
+    ///
+    /// # Arguments
+    /// ready - Provides a check returning `true` if the condition is met.
+    fn wait<F: Fn() -> bool>(&self, ready: F);
+
+    /// Notifies waiters that the condition they wait for may be met, such as permits being
+    /// released.
+    ///
+    /// # Arguments
+    /// count - Provides the number of waiters that can proceed, or `usize::MAX` for all of them.
+    fn notify(&self, count: usize);
+}
+
+/// Defines a wait strategy that busy-waits with exponential backoff.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpinWait;
+
+impl WaitStrategy for SpinWait {
+    fn wait<F: Fn() -> bool>(&self, ready: F) {
+        let mut backoff = Spin::new();
+        while !ready() {
+            backoff.wait();
+        }
+    }
+
+    fn notify(&self, _count: usize) {}
+}
+
+/// Defines the structure for the Semaphore.
+#[derive(Debug)]
+pub struct Semaphore<S = SpinWait> {
+    /// Holds the number of available permits.
+    count: AtomicUsize,
+
+    /// Holds the maximum number of permits.
+    max: usize,
+
+    /// Holds how tasks wait for permits.
+    strategy: S,
+}
+
+/// Implements the construction of a busy-waiting `Semaphore`.
+impl Semaphore<SpinWait> {
+    /// Creates a new Semaphore that busy-waits for permits.
+    ///
+    /// # Arguments
+    /// initial - Provides the number of available permits.
+    /// max - Provides the maximum number of permits.
+    ///
+    /// # Returns
+    /// A Semaphore.
+    ///
+    /// # Panics
+    /// Panics if `initial` is larger than `max`.
+    pub const fn new(initial: usize, max: usize) -> Self {
+        Self::with_strategy(initial, max, SpinWait)
+    }
+
+    /// Creates a new binary Semaphore, holding at most a single permit, that busy-waits for it.
+    ///
+    /// # Arguments
+    /// initially_available - Provides whether or not the permit is available.
+    ///
+    /// # Returns
+    /// A Semaphore.
+    ///
+    /// # Examples
+    /// ```
+    /// use raztos_util::sync::semaphore::Semaphore;
+    ///
+    /// static DATA_READY: Semaphore = Semaphore::binary(false);
+    ///
+    /// // Signalled by an interrupt handler.
+    /// DATA_READY.release(1).unwrap();
+    /// assert_eq!(DATA_READY.release(1), Err(1));
+    ///
+    /// // Waited on by a task.
+    /// DATA_READY.acquire();
+    /// assert!(!DATA_READY.try_acquire());
+    /// ```
+    pub const fn binary(initially_available: bool) -> Self {
+        Self::binary_with_strategy(initially_available, SpinWait)
+    }
+}
+
+/// Implements the functionality unique to `Semaphore`.
+impl<S: WaitStrategy> Semaphore<S> {
+    /// Creates a new Semaphore.
+    ///
+    /// # Arguments
+    /// initial - Provides the number of available permits.
+    /// max - Provides the maximum number of permits.
+    /// strategy - Provides how tasks wait for permits.
+    ///
+    /// # Returns
+    /// A Semaphore.
+    ///
+    /// # Panics
+    /// Panics if `initial` is larger than `max`.
+    pub const fn with_strategy(initial: usize, max: usize, strategy: S) -> Self {
+        assert!(initial <= max);
+
+        Semaphore {
+            count: AtomicUsize::new(initial),
+            max,
+            strategy,
+        }
+    }
+
+    /// Creates a new binary Semaphore, holding at most a single permit.
+    ///
+    /// # Arguments
+    /// initially_available - Provides whether or not the permit is available.
+    /// strategy - Provides how tasks wait for the permit.
+    ///
+    /// # Returns
+    /// A Semaphore.
+    pub const fn binary_with_strategy(initially_available: bool, strategy: S) -> Self {
+        Self::with_strategy(initially_available as usize, 1, strategy)
+    }
+
+    /// Gets the number of available permits.
+    ///
+    /// # Returns
+    /// The number of permits.
+    pub fn available(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Gets the maximum number of permits.
+    ///
+    /// # Returns
+    /// The maximum number of permits.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Gets the wait strategy of the semaphore.
+    ///
+    /// # Returns
+    /// The wait strategy.
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// Acquires a permit, waiting with the wait strategy until one is available.
+    pub fn acquire(&self) {
+        self.acquire_many(1);
+    }
+
+    /// Acquires a number of permits at once, waiting with the wait strategy until enough are
+    /// available.
+    ///
+    /// # Arguments
+    /// count - Provides the number of permits.
+    ///
+    /// # Panics
+    /// Panics if `count` is larger than the maximum number of permits, since it could never be
+    /// acquired.
+    pub fn acquire_many(&self, count: usize) {
+        assert!(count <= self.max);
+
+        while !self.try_acquire_many(count) {
+            self.strategy
+                .wait(|| self.count.load(Ordering::Relaxed) >= count);
+        }
+    }
+
+    /// Acquires a permit if one is available.
+    ///
+    /// # Returns
+    /// `true` if acquired, `false` otherwise.
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_many(1)
+    }
+
+    /// Acquires a number of permits at once if enough are available.
+    ///
+    /// # Arguments
+    /// count - Provides the number of permits.
+    ///
+    /// # Returns
+    /// `true` if acquired, `false` otherwise.
+    pub fn try_acquire_many(&self, count: usize) -> bool {
+        self.count
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |available| {
+                available.checked_sub(count)
+            })
+            .is_ok()
+    }
+
+    /// Releases a number of permits and notifies waiters.
+    ///
+    /// # Arguments
+    /// count - Provides the number of permits.
+    ///
+    /// # Returns
+    /// `Ok` if released, or the count back if releasing it would exceed the maximum, in which
+    /// case no permits are released.
+    pub fn release(&self, count: usize) -> Result<(), usize> {
+        let max = self.max;
+        self.count
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |available| {
+                available.checked_add(count).filter(|total| *total <= max)
+            })
+            .map_err(|_| count)?;
+
+        self.strategy.notify(count);
+        Ok(())
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn permits_are_counted() {
+        let semaphore = Semaphore::new(3, 4);
+
+        assert!(semaphore.try_acquire_many(2));
+        assert!(!semaphore.try_acquire_many(2));
+        assert!(semaphore.try_acquire());
+        assert!(!semaphore.try_acquire());
+        assert_eq!(semaphore.available(), 0);
+
+        assert_eq!(semaphore.release(5), Err(5));
+        assert_eq!(semaphore.release(4), Ok(()));
+        assert_eq!(semaphore.release(1), Err(1));
+        assert_eq!(semaphore.available(), semaphore.max());
+    }
+
+    #[test]
+    fn binary_holds_a_single_permit() {
+        let semaphore = Semaphore::binary(true);
+        assert_eq!(semaphore.max(), 1);
+        assert!(semaphore.try_acquire());
+        assert!(!semaphore.try_acquire());
+
+        assert_eq!(semaphore.release(1), Ok(()));
+        assert_eq!(semaphore.release(1), Err(1));
+        assert_eq!(Semaphore::binary(false).available(), 0);
+    }
+
+    #[test]
+    fn strategy_is_used_to_wait_and_notify() {
+        #[derive(Default)]
+        struct Recorder {
+            waits: Cell<usize>,
+            notified: Cell<usize>,
+        }
+
+        impl WaitStrategy for Recorder {
+            fn wait<F: Fn() -> bool>(&self, ready: F) {
+                assert!(!ready());
+                self.waits.set(self.waits.get() + 1);
+            }
+
+            fn notify(&self, count: usize) {
+                self.notified.set(self.notified.get() + count);
+            }
+        }
+
+        let semaphore = Semaphore::with_strategy(1, 2, Recorder::default());
+        semaphore.acquire();
+        assert_eq!(semaphore.strategy().waits.get(), 0);
+
+        semaphore.release(2).unwrap();
+        assert_eq!(semaphore.strategy().notified.get(), 2);
+        assert_eq!(semaphore.release(1), Err(1));
+        assert_eq!(semaphore.strategy().notified.get(), 2);
+    }
+
+    #[test]
+    fn acquire_waits_with_strategy() {
+        //
+        // Stands in for another task releasing a permit while the waiting task is blocked.
+        //
+
+        struct ReleaseOnWait<'a> {
+            semaphore: &'a Cell<Option<&'a Semaphore<ReleaseOnWait<'a>>>>,
+            waits: Cell<usize>,
+            saw_permit: Cell<bool>,
+        }
+
+        impl<'a> WaitStrategy for ReleaseOnWait<'a> {
+            fn wait<F: Fn() -> bool>(&self, ready: F) {
+                assert!(!ready());
+                self.waits.set(self.waits.get() + 1);
+                self.semaphore.get().unwrap().release(1).unwrap();
+                self.saw_permit.set(ready());
+            }
+
+            fn notify(&self, _count: usize) {}
+        }
+
+        let cell = Cell::new(None);
+        let semaphore = Semaphore::with_strategy(
+            0,
+            1,
+            ReleaseOnWait {
+                semaphore: &cell,
+                waits: Cell::new(0),
+                saw_permit: Cell::new(false),
+            },
+        );
+        cell.set(Some(&semaphore));
+
+        semaphore.acquire();
+        assert_eq!(semaphore.strategy().waits.get(), 1);
+        assert!(semaphore.strategy().saw_permit.get());
+        assert_eq!(semaphore.available(), 0);
+    }
+
+    #[test]
+    fn threads_wait_for_permits() {
+        const THREADS: usize = 4;
+        const ROUNDS: usize = 200;
+
+        let semaphore = Arc::new(Semaphore::new(0, THREADS * ROUNDS));
+        let mut handles = Vec::new();
+
+        for _ in 0..THREADS {
+            let semaphore = semaphore.clone();
+            handles.push(thread::spawn(move || {
+                for _ in 0..ROUNDS {
+                    semaphore.acquire();
+                }
+            }));
+        }
+
+        for _ in 0..(THREADS * ROUNDS) {
+            semaphore.release(1).unwrap();
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(semaphore.available(), 0);
+    }
+}
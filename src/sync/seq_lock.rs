@@ -0,0 +1,324 @@
+//! # Sequence Lock
+//!
+//! `seq_lock` contains a sequence lock, sharing a small `Copy` value between a single writer, such
+//! as an interrupt handler updating a sensor snapshot or the system time, and any number of
+//! readers.
+//!
+//! The writer never waits for readers. It makes a sequence counter odd while it writes, and even
+//! again when done. A reader copies the value and checks that the counter was even and unchanged
+//! around the copy, retrying otherwise, so it never returns a value torn by a concurrent write.
+//!
+//! Readers retry for as long as a write is in progress, so a reader must not preempt the writer,
+//! e.g. an interrupt handler must not read a value written by a task, since the task cannot finish
+//! its write until the handler returns. Such a reader can use `try_read` instead.
+//!
+//! # Examples
+//! ```
+//! use raztos_util::sync::seq_lock::SeqLock;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! struct Snapshot {
+//!     temperature: i32,
+//!     timestamp: u64,
+//! }
+//!
+//! static SENSOR: SeqLock<Snapshot> = SeqLock::new(Snapshot {
+//!     temperature: 0,
+//!     timestamp: 0,
+//! });
+//!
+//! SENSOR.write(Snapshot {
+//!     temperature: 21,
+//!     timestamp: 1000,
+//! });
+//!
+//! assert_eq!(SENSOR.read().temperature, 21);
+//! ```
+
+use crate::sync::atomic::{fence, AtomicUsize, Ordering};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::hint;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// Defines the structure for a sequence lock.
+pub struct SeqLock<T: Copy> {
+    /// Holds the sequence counter, odd while a write is in progress.
+    sequence: AtomicUsize,
+
+    /// Holds the value.
+    data: UnsafeCell<T>,
+}
+
+//
+// UNSAFE: Readers only keep copies that were not written concurrently, and writers exclude each
+//         other through the sequence counter.
+//
+
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+
+/// Implements the functionality unique to `SeqLock`.
+impl<T: Copy> SeqLock<T> {
+    /// Creates a new SeqLock.
+    ///
+    /// # Arguments
+    /// value - Provides the initial value.
+    ///
+    /// # Returns
+    /// A SeqLock.
+    pub const fn new(value: T) -> Self {
+        SeqLock {
+            sequence: AtomicUsize::new(0),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Reads the value, retrying while it is written.
+    ///
+    /// # Returns
+    /// A copy of the value.
+    pub fn read(&self) -> T {
+        loop {
+            if let Some(value) = self.try_read() {
+                return value;
+            }
+
+            hint::spin_loop();
+        }
+    }
+
+    /// Reads the value if it is not written during the read.
+    ///
+    /// # Returns
+    /// A copy of the value, or `None` if a write was in progress.
+    pub fn try_read(&self) -> Option<T> {
+        let before = self.sequence.load(Ordering::Acquire);
+        if before & 1 != 0 {
+            return None;
+        }
+
+        //
+        // UNSAFE: The copy may race with a write and be torn, so it is read volatile into
+        //         uninitialized memory and only assumed initialized once the sequence shows that
+        //         no write happened. `T: Copy` guarantees it has no drop glue to run on a torn
+        //         copy.
+        //
+
+        let value = unsafe { ptr::read_volatile(self.data.get() as *const MaybeUninit<T>) };
+
+        //
+        // Keeps the copy from being moved after the second load of the sequence on weakly
+        // ordered targets.
+        //
+
+        fence(Ordering::Acquire);
+
+        if self.sequence.load(Ordering::Relaxed) != before {
+            return None;
+        }
+
+        //
+        // UNSAFE: The sequence did not change, so no write overlapped the copy.
+        //
+
+        Some(unsafe { value.assume_init() })
+    }
+
+    /// Writes the value.
+    ///
+    /// # Arguments
+    /// value - Provides the value.
+    ///
+    /// # Panics
+    /// Panics if another write is in progress, since the lock only supports a single writer.
+    pub fn write(&self, value: T) {
+        self.update(|data| *data = value);
+    }
+
+    /// Modifies the value in place with a closure.
+    ///
+    /// Readers retry until the closure returns, so it should be short.
+    ///
+    /// # Arguments
+    /// f - Provides the closure, which receives the value.
+    ///
+    /// # Returns
+    /// The value returned by the closure.
+    ///
+    /// # Panics
+    /// Panics if another write is in progress, since the lock only supports a single writer.
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        //
+        // Ends the write when dropped, so a panicking closure does not leave readers retrying
+        // forever on hosts that unwind.
+        //
+
+        struct End<'a>(&'a AtomicUsize, usize);
+
+        impl<'a> Drop for End<'a> {
+            fn drop(&mut self) {
+                self.0.store(self.1.wrapping_add(2), Ordering::Release);
+            }
+        }
+
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        if sequence & 1 != 0
+            || self
+                .sequence
+                .compare_exchange(
+                    sequence,
+                    sequence.wrapping_add(1),
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+        {
+            panic!("the SeqLock is written by more than one writer at once");
+        }
+
+        let _end = End(&self.sequence, sequence);
+
+        //
+        // Keeps the writes of the value from being moved before the odd sequence on weakly
+        // ordered targets, where a reader could otherwise see part of the new value with the old
+        // even sequence.
+        //
+
+        fence(Ordering::Release);
+
+        //
+        // UNSAFE: The odd sequence excludes other writers, and readers discard their copies.
+        //
+
+        f(unsafe { &mut *self.data.get() })
+    }
+
+    /// Gets the sequence counter, which changes by two with every write.
+    ///
+    /// # Returns
+    /// The sequence, odd while a write is in progress.
+    pub fn sequence(&self) -> usize {
+        self.sequence.load(Ordering::Relaxed)
+    }
+
+    /// Gets the value without locking, since the borrow guarantees no reader or writer exists.
+    ///
+    /// # Returns
+    /// A mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Consumes the lock, returning the value.
+    ///
+    /// # Returns
+    /// The value.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Defines the default value for SeqLock.
+impl<T: Copy + Default> Default for SeqLock<T> {
+    fn default() -> Self {
+        SeqLock::new(T::default())
+    }
+}
+
+/// Formats a SeqLock with its value, if it is not being written.
+impl<T: Copy + fmt::Debug> fmt::Debug for SeqLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_read() {
+            Some(value) => f.debug_struct("SeqLock").field("data", &value).finish(),
+            None => f
+                .debug_struct("SeqLock")
+                .field("data", &format_args!("<writing>"))
+                .finish(),
+        }
+    }
+}
+
+//
+// Unit Tests
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    #[test]
+    fn writes_advance_sequence() {
+        let mut lock = SeqLock::new((1, 2));
+        assert_eq!(lock.sequence(), 0);
+
+        lock.write((3, 4));
+        assert_eq!(lock.update(|value| value.0 + value.1), 7);
+        assert_eq!(lock.sequence(), 4);
+        assert_eq!(lock.read(), (3, 4));
+
+        lock.get_mut().0 = 5;
+        assert_eq!(format!("{:?}", lock), "SeqLock { data: (5, 4) }");
+        assert_eq!(lock.into_inner(), (5, 4));
+    }
+
+    #[test]
+    fn reads_fail_during_write() {
+        let lock = SeqLock::new(0u32);
+
+        lock.update(|value| {
+            *value = 1;
+            assert_eq!(lock.try_read(), None);
+            assert_eq!(format!("{:?}", lock), "SeqLock { data: <writing> }");
+
+            let nested = panic::catch_unwind(AssertUnwindSafe(|| lock.write(2)));
+            assert!(nested.is_err());
+        });
+
+        assert_eq!(lock.try_read(), Some(1));
+    }
+
+    #[test]
+    fn panicking_writer_ends_write() {
+        let lock = SeqLock::new(0u32);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| lock.update(|_| panic!())));
+        assert!(result.is_err());
+        assert_eq!(lock.sequence(), 2);
+        assert_eq!(lock.try_read(), Some(0));
+    }
+
+    #[test]
+    fn reads_are_never_torn() {
+        const WRITES: usize = 20000;
+
+        let lock = Arc::new(SeqLock::new([0usize; 8]));
+        let mut handles = Vec::new();
+
+        for _ in 0..3 {
+            let lock = lock.clone();
+            handles.push(thread::spawn(move || loop {
+                let value = lock.read();
+                assert!(value.iter().all(|element| *element == value[0]));
+                if value[0] == WRITES {
+                    break;
+                }
+            }));
+        }
+
+        for write in 1..=WRITES {
+            lock.write([write; 8]);
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+}